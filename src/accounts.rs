@@ -0,0 +1,137 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Management of several wrappers (one per account) for those selling under multiple author accounts.
+
+use crate::error::Result;
+use crate::pagination;
+use crate::range::DateRange;
+use crate::sort::SortOptions;
+use crate::{APIToken, APIWrapper};
+
+use std::collections::BTreeMap;
+use std::future::Future;
+
+/// Revenue totals (keyed by currency) across a set of accounts.
+#[derive(Debug, Clone, Default)]
+pub struct RevenueReport {
+    /// The revenue of each account, keyed by account (member) ID and then currency.
+    pub per_account: BTreeMap<u64, BTreeMap<String, f64>>,
+    /// The combined revenue of all accounts, keyed by currency.
+    pub total: BTreeMap<String, f64>,
+    /// The number of purchases counted.
+    pub purchases: u64,
+}
+
+/// A set of wrappers, each authenticated as a different account, routed by account (member) ID.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::{accounts::AccountManager, range::DateRange, APIToken};
+/// # async fn run(tokens: Vec<APIToken>) -> bbb_api_wrapper::error::Result<()> {
+/// let mut accounts = AccountManager::new();
+///
+/// for token in tokens {
+///     accounts.connect(token).await?;
+/// }
+///
+/// let report = accounts.revenue_between(DateRange::last_days(30)).await?;
+/// println!("{:?}", report.total);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct AccountManager {
+    accounts: BTreeMap<u64, APIWrapper>,
+}
+
+impl AccountManager {
+    pub fn new() -> AccountManager {
+        AccountManager::default()
+    }
+
+    /// Construct a wrapper for a token and add it under the ID of the account it authenticates as.
+    ///
+    /// Returns the account ID. Any wrapper previously held for the same account is replaced.
+    pub async fn connect(&mut self, token: APIToken) -> Result<u64> {
+        self.add(APIWrapper::new(token).await?).await
+    }
+
+    /// Add an existing wrapper under the ID of the account it authenticates as, returning that ID.
+    ///
+    /// Any wrapper previously held for the same account is replaced.
+    pub async fn add(&mut self, wrapper: APIWrapper) -> Result<u64> {
+        let account_id = *wrapper.members().fetch_self().await?.member_id();
+        self.accounts.insert(account_id, wrapper);
+
+        Ok(account_id)
+    }
+
+    /// Remove the wrapper held for an account, returning it if present.
+    pub fn remove(&mut self, account_id: u64) -> Option<APIWrapper> {
+        self.accounts.remove(&account_id)
+    }
+
+    /// Returns the wrapper held for an account, if any.
+    pub fn get(&self, account_id: u64) -> Option<&APIWrapper> {
+        self.accounts.get(&account_id)
+    }
+
+    /// Returns the IDs of all held accounts in ascending order.
+    pub fn account_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.accounts.keys().copied()
+    }
+
+    /// Returns each held account ID along with its wrapper, in ascending order of ID.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &APIWrapper)> {
+        self.accounts.iter().map(|(account_id, wrapper)| (*account_id, wrapper))
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Run an operation against every account in turn, returning each account's result rather than stopping at the
+    /// first failure.
+    pub async fn for_each<'a, T, F, Fut>(&'a self, mut operation: F) -> Vec<(u64, Result<T>)>
+    where
+        F: FnMut(&'a APIWrapper) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut results = Vec::with_capacity(self.accounts.len());
+
+        for (account_id, wrapper) in &self.accounts {
+            results.push((*account_id, operation(wrapper).await));
+        }
+
+        results
+    }
+
+    /// Total the revenue of every resource owned by every account, for purchases made within a date range.
+    pub async fn revenue_between(&self, range: DateRange) -> Result<RevenueReport> {
+        let mut report = RevenueReport::default();
+
+        for (account_id, wrapper) in &self.accounts {
+            let revenue = report.per_account.entry(*account_id).or_default();
+
+            let owned = pagination::collect_all(|page| async move {
+                wrapper.resources().list_owned(Some(&SortOptions::default().page(page))).await
+            })
+            .await?;
+
+            for resource in &owned {
+                for purchase in wrapper.resources().purchases().list_between(*resource.resource_id(), range).await? {
+                    *revenue.entry(purchase.currency().clone()).or_default() += purchase.price();
+                    *report.total.entry(purchase.currency().clone()).or_default() += purchase.price();
+                    report.purchases += 1;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Analytics which join a resource's purchases against its downloads.
+
+use crate::data::resources::{DownloadData, PurchaseData};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+/// The number of times a member downloaded a resource.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MemberDownloads {
+    pub member_id: u64,
+    pub downloads: u64,
+}
+
+/// How the buyers of a resource have engaged with its downloads.
+#[derive(Debug, Clone, Serialize)]
+pub struct EngagementReport {
+    /// The members who purchased the resource but never downloaded it (eg. candidates for an onboarding message).
+    pub never_downloaded: Vec<u64>,
+    /// The members who downloaded the resource far more than is typical, most downloads first.
+    pub heavy_downloaders: Vec<MemberDownloads>,
+    /// The median number of downloads per buyer who downloaded at least once.
+    pub median_downloads: u64,
+}
+
+/// Options controlling which members are considered to be heavy downloaders.
+#[derive(Debug, Clone, Copy)]
+pub struct EngagementOptions {
+    /// A member is a heavy downloader if their downloads exceed this multiple of the median.
+    pub heavy_factor: u64,
+    /// A member with no more than this number of downloads is never a heavy downloader.
+    pub min_heavy_downloads: u64,
+}
+
+impl Default for EngagementOptions {
+    fn default() -> EngagementOptions {
+        EngagementOptions { heavy_factor: 5, min_heavy_downloads: 10 }
+    }
+}
+
+/// Join a resource's purchases against its downloads per member.
+///
+/// Refunded or reversed purchases aren't counted. Members are listed in ascending order of ID (with heavy downloaders
+/// ordered by downloads first). Downloads by members without a purchase (eg. of a free resource or by its author) still
+/// count towards heavy downloaders, but not towards the median.
+pub fn engagement(
+    purchases: &[PurchaseData],
+    downloads: &[DownloadData],
+    options: EngagementOptions,
+) -> EngagementReport {
+    let buyers: BTreeSet<u64> =
+        purchases.iter().filter(|purchase| !purchase.is_reversed()).map(|purchase| *purchase.purchaser_id()).collect();
+
+    let mut counts: BTreeMap<u64, u64> = BTreeMap::new();
+
+    for download in downloads {
+        *counts.entry(*download.downloader_id()).or_default() += 1;
+    }
+
+    let never_downloaded = buyers.iter().copied().filter(|buyer| !counts.contains_key(buyer)).collect();
+
+    let mut buyer_counts: Vec<u64> =
+        counts.iter().filter(|(member_id, _)| buyers.contains(member_id)).map(|(_, count)| *count).collect();
+    buyer_counts.sort_unstable();
+
+    let median_downloads = buyer_counts.get(buyer_counts.len() / 2).copied().unwrap_or(0);
+    let threshold = (median_downloads.max(1) * options.heavy_factor).max(options.min_heavy_downloads);
+
+    let mut heavy_downloaders: Vec<MemberDownloads> = counts
+        .into_iter()
+        .filter(|(_, downloads)| *downloads > threshold)
+        .map(|(member_id, downloads)| MemberDownloads { member_id, downloads })
+        .collect();
+    heavy_downloaders.sort_by(|a, b| b.downloads.cmp(&a.downloads).then(a.member_id.cmp(&b.member_id)));
+
+    EngagementReport { never_downloaded, heavy_downloaders, median_downloads }
+}
@@ -0,0 +1,219 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Detection of abnormal spikes in a resource's download rate, which are often the first sign that it has been leaked.
+//!
+//! Downloads are counted within fixed-length buckets, and the most recent bucket is compared against the mean and
+//! standard deviation of those before it.
+
+use crate::error::Result;
+use crate::range::DateRange;
+use crate::throttler::unix_timestamp;
+use crate::APIWrapper;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Serialize;
+
+/// Count timestamps (in seconds) into consecutive buckets of a fixed length which end at the end of a range.
+///
+/// The final bucket ends at `range.to`, and as many buckets as fit within the range are returned, oldest-first.
+/// Timestamps outside of the range (or the buckets) are ignored.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::anomaly::bucket_counts;
+/// # use bbb_api_wrapper::range::DateRange;
+/// let counts = bucket_counts([5, 15, 16, 29, 30], DateRange::between(0, 30), 10);
+/// assert_eq!(vec![1, 2, 2], counts);
+/// ```
+pub fn bucket_counts<I>(timestamps: I, range: DateRange, bucket: u64) -> Vec<u64>
+where
+    I: IntoIterator<Item = u64>,
+{
+    let bucket = bucket.max(1);
+    let length = ((range.to.saturating_sub(range.from)) / bucket) as usize;
+    let mut counts = vec![0; length];
+
+    for timestamp in timestamps.into_iter().filter(|timestamp| range.contains(*timestamp)) {
+        // Buckets are open at their start and closed at their end, so the final one includes the end of the range.
+        let index = match range.to.checked_sub(timestamp) {
+            Some(0) => 0,
+            Some(age) => ((age - 1) / bucket) as usize,
+            None => continue,
+        };
+
+        if index < length {
+            counts[length - 1 - index] += 1;
+        }
+    }
+
+    counts
+}
+
+/// The parameters by which a download spike is detected.
+#[derive(Debug, Clone)]
+pub struct SpikeDetector {
+    bucket: u64,
+    baseline: usize,
+    sensitivity: f64,
+    min_downloads: u64,
+}
+
+impl Default for SpikeDetector {
+    /// Hourly buckets against a baseline of the previous day, flagging hours with at least 10 downloads which are three
+    /// standard deviations above the mean.
+    fn default() -> SpikeDetector {
+        SpikeDetector { bucket: 3_600, baseline: 24, sensitivity: 3.0, min_downloads: 10 }
+    }
+}
+
+impl SpikeDetector {
+    /// Set the length of each bucket (at least one second).
+    pub fn bucket(mut self, bucket: Duration) -> Self {
+        self.bucket = bucket.as_secs().max(1);
+        self
+    }
+
+    /// Set the number of buckets preceding the most recent one which form the baseline (at least two).
+    pub fn baseline(mut self, baseline: usize) -> Self {
+        self.baseline = baseline.max(2);
+        self
+    }
+
+    /// Set the number of standard deviations above the baseline mean at which a bucket is considered a spike.
+    pub fn sensitivity(mut self, sensitivity: f64) -> Self {
+        self.sensitivity = sensitivity;
+        self
+    }
+
+    /// Set the minimum number of downloads within a bucket for it to be considered a spike, so that small absolute
+    /// changes on quiet resources aren't reported.
+    pub fn min_downloads(mut self, min_downloads: u64) -> Self {
+        self.min_downloads = min_downloads;
+        self
+    }
+
+    /// Returns the range covered by the baseline and most recent bucket, ending at a timestamp.
+    pub fn window_ending(&self, to: u64) -> DateRange {
+        DateRange::between(to.saturating_sub(self.bucket.saturating_mul((self.baseline as u64).saturating_add(1))), to)
+    }
+
+    /// Compare the final count against those before it (up to the baseline length), returning the baseline's mean
+    /// and standard deviation if it's a spike.
+    ///
+    /// The standard deviation is floored at one when comparing, so that a perfectly steady baseline doesn't cause
+    /// every small increase to be a spike.
+    ///
+    /// # Example
+    /// ```
+    /// # use bbb_api_wrapper::anomaly::SpikeDetector;
+    /// let detector = SpikeDetector::default();
+    ///
+    /// assert!(detector.detect(&[3, 4, 2, 3, 5, 4, 40]).is_some());
+    /// assert!(detector.detect(&[3, 4, 2, 3, 5, 4, 6]).is_none());
+    /// ```
+    pub fn detect(&self, counts: &[u64]) -> Option<(f64, f64)> {
+        let (latest, previous) = counts.split_last()?;
+        let baseline = &previous[previous.len().saturating_sub(self.baseline)..];
+
+        if baseline.len() < 2 || *latest < self.min_downloads {
+            return None;
+        }
+
+        let mean = baseline.iter().sum::<u64>() as f64 / baseline.len() as f64;
+        let variance = baseline.iter().map(|count| (*count as f64 - mean).powi(2)).sum::<f64>() / baseline.len() as f64;
+        let stddev = variance.sqrt();
+
+        if *latest as f64 > mean + self.sensitivity * stddev.max(1.0) {
+            Some((mean, stddev))
+        } else {
+            None
+        }
+    }
+}
+
+/// An abnormal spike in a resource's download rate.
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadSpike {
+    pub resource_id: u64,
+    /// The bucket in which the spike occurred.
+    pub period: DateRange,
+    pub downloads: u64,
+    /// The mean number of downloads per bucket across the baseline.
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+/// Checks a set of resources for download spikes each time it's polled.
+///
+/// Each poll fetches the downloads of every watched resource across the detector's window, so the poll interval
+/// should be similar to the detector's bucket length.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::anomaly::{DownloadSpikeMonitor, SpikeDetector};
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let mut monitor = DownloadSpikeMonitor::new(&wrapper, SpikeDetector::default());
+/// monitor.watch_resource(1);
+///
+/// for spike in monitor.poll().await? {
+///     println!("Resource {} had {} downloads (usually {:.1}).", spike.resource_id, spike.downloads, spike.mean);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct DownloadSpikeMonitor<'a> {
+    wrapper: &'a APIWrapper,
+    detector: SpikeDetector,
+    /// The end of the most recently reported spike per resource, used to avoid reporting the same spike repeatedly.
+    resources: HashMap<u64, Option<u64>>,
+}
+
+impl<'a> DownloadSpikeMonitor<'a> {
+    pub fn new(wrapper: &'a APIWrapper, detector: SpikeDetector) -> DownloadSpikeMonitor<'a> {
+        DownloadSpikeMonitor { wrapper, detector, resources: HashMap::new() }
+    }
+
+    /// Watch a resource for download spikes.
+    pub fn watch_resource(&mut self, resource_id: u64) -> &mut Self {
+        self.resources.entry(resource_id).or_default();
+        self
+    }
+
+    /// Stop watching a resource.
+    pub fn unwatch_resource(&mut self, resource_id: u64) -> &mut Self {
+        self.resources.remove(&resource_id);
+        self
+    }
+
+    /// Check every watched resource, returning those whose most recent bucket is a spike.
+    ///
+    /// A spike isn't reported again until a full bucket has passed since it was last reported.
+    pub async fn poll(&mut self) -> Result<Vec<DownloadSpike>> {
+        let now = unix_timestamp() / 1000;
+        let window = self.detector.window_ending(now);
+        let mut spikes = Vec::new();
+
+        for (&resource_id, reported) in self.resources.iter_mut() {
+            if reported.is_some_and(|reported| now < reported + self.detector.bucket) {
+                continue;
+            }
+
+            let downloads = self.wrapper.resources().downloads().list_between(resource_id, window).await?;
+            let counts =
+                bucket_counts(downloads.iter().map(|download| *download.download_date()), window, self.detector.bucket);
+
+            if let Some((mean, stddev)) = self.detector.detect(&counts) {
+                let period = DateRange::between(now.saturating_sub(self.detector.bucket), now);
+                let downloads = counts.last().copied().unwrap_or(0);
+
+                spikes.push(DownloadSpike { resource_id, period, downloads, mean, stddev });
+                *reported = Some(now);
+            }
+        }
+
+        Ok(spikes)
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Cross-referencing of a resource's licenses and purchases to find inconsistencies between them.
+
+use crate::data::resources::{LicenseData, PurchaseData};
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+/// A single inconsistency between a resource's licenses and purchases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum AuditIssue {
+    /// A license is still active despite its purchase having been refunded or reversed.
+    ActiveLicenseWithReversedPurchase { license_id: u64, purchase_id: u64 },
+    /// A purchase references a license which doesn't exist.
+    PurchaseWithoutLicense { purchase_id: u64, license_id: u64 },
+    /// A member holds two temporary licenses whose periods overlap.
+    OverlappingTemporaryLicenses { purchaser_id: u64, first_license_id: u64, second_license_id: u64 },
+}
+
+/// The outcome of auditing a resource's licenses and purchases.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditReport {
+    pub licenses_checked: u64,
+    pub purchases_checked: u64,
+    pub issues: Vec<AuditIssue>,
+}
+
+impl AuditReport {
+    /// Returns whether or not no inconsistencies were found.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Audit the licenses and purchases of a single resource.
+///
+/// Issues are reported in a stable order: reversed purchases and missing licenses (by purchase ID), followed by
+/// overlapping temporary licenses (by purchaser and then license ID).
+pub fn audit(licenses: &[LicenseData], purchases: &[PurchaseData]) -> AuditReport {
+    let by_id: HashMap<u64, &LicenseData> = licenses.iter().map(|license| (*license.license_id(), license)).collect();
+    let mut issues = Vec::new();
+
+    let mut purchases: Vec<&PurchaseData> = purchases.iter().collect();
+    purchases.sort_by_key(|purchase| *purchase.purchase_id());
+
+    for purchase in &purchases {
+        let license_id = *purchase.license_id();
+
+        match by_id.get(&license_id) {
+            Some(license) if purchase.is_reversed() && *license.active() => {
+                issues.push(AuditIssue::ActiveLicenseWithReversedPurchase {
+                    license_id,
+                    purchase_id: *purchase.purchase_id(),
+                });
+            }
+            Some(_) => (),
+            None => {
+                issues.push(AuditIssue::PurchaseWithoutLicense { purchase_id: *purchase.purchase_id(), license_id })
+            }
+        }
+    }
+
+    let mut temporary: Vec<&LicenseData> = licenses.iter().filter(|license| !license.permanent()).collect();
+    temporary.sort_by_key(|license| (*license.purchaser_id(), *license.start_date(), *license.license_id()));
+
+    // Once sorted by start date, a license overlaps an earlier one of the same member if it starts before the latest
+    // end date seen so far.
+    for group in temporary.chunk_by(|a, b| a.purchaser_id() == b.purchaser_id()) {
+        let mut latest: Option<&LicenseData> = None;
+
+        for license in group {
+            if let Some(previous) = latest {
+                if license.start_date() < previous.end_date() {
+                    issues.push(AuditIssue::OverlappingTemporaryLicenses {
+                        purchaser_id: *license.purchaser_id(),
+                        first_license_id: *previous.license_id(),
+                        second_license_id: *license.license_id(),
+                    });
+                }
+            }
+
+            if latest.is_none_or(|previous| license.end_date() > previous.end_date()) {
+                latest = Some(license);
+            }
+        }
+    }
+
+    AuditReport { licenses_checked: licenses.len() as u64, purchases_checked: purchases.len() as u64, issues }
+}
@@ -0,0 +1,117 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Types for running many requests whilst reporting on the outcome of each.
+
+use crate::error::{APIError, Result};
+use crate::runtime::Instant;
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Counts of how a batch of requests concluded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchSummary {
+    pub succeeded: u64,
+    pub failed: u64,
+    /// The number of failures (included within `failed`) which were due to rate limiting.
+    pub rate_limited: u64,
+}
+
+/// The ordered per-request results of a batch along with a summary.
+#[derive(Debug)]
+pub struct BatchReport<T> {
+    pub results: Vec<Result<T>>,
+    pub summary: BatchSummary,
+}
+
+impl<T> BatchReport<T> {
+    /// Construct a report (computing its summary) from a set of results.
+    pub fn from_results(results: Vec<Result<T>>) -> BatchReport<T> {
+        let mut summary = BatchSummary::default();
+
+        for result in &results {
+            match result {
+                Ok(_) => summary.succeeded += 1,
+                Err(error) => {
+                    summary.failed += 1;
+
+                    if error.is_rate_limited() {
+                        summary.rate_limited += 1;
+                    }
+                }
+            }
+        }
+
+        BatchReport { results, summary }
+    }
+
+    /// Returns whether or not every request in the batch succeeded.
+    pub fn is_success(&self) -> bool {
+        self.summary.failed == 0
+    }
+}
+
+/// The outcome of a best-effort transactional bulk license modification.
+#[derive(Debug, Default)]
+pub struct BulkLicenseReport {
+    /// The IDs of licenses which were modified (and not subsequently rolled back).
+    pub applied: Vec<u64>,
+    /// The license whose modification failed and caused the rollback, if any.
+    pub failure: Option<(u64, APIError)>,
+    /// The IDs of licenses which were successfully restored to their prior state.
+    pub rolled_back: Vec<u64>,
+    /// The licenses which couldn't be restored to their prior state along with why.
+    pub rollback_failures: Vec<(u64, APIError)>,
+}
+
+impl BulkLicenseReport {
+    /// Returns whether or not every modification was applied.
+    pub fn is_success(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Run each request to completion in order, collecting their results rather than stopping at the first failure.
+pub(crate) async fn execute<T, I, F>(requests: I) -> BatchReport<T>
+where
+    I: IntoIterator<Item = F>,
+    F: Future<Output = Result<T>>,
+{
+    let mut results = Vec::new();
+
+    for request in requests {
+        results.push(request.await);
+    }
+
+    BatchReport::from_results(results)
+}
+
+/// Run each request in order, starting them at evenly spaced intervals across a window rather than all at once.
+///
+/// Start times are scheduled against the beginning of the window. If a request overruns its slot, the next is started
+/// as soon as it completes rather than pushing back the remainder of the schedule.
+pub(crate) async fn execute_spread<T, I, F>(requests: I, window: Duration) -> BatchReport<T>
+where
+    I: IntoIterator<Item = F>,
+    F: Future<Output = Result<T>>,
+{
+    let requests: Vec<F> = requests.into_iter().collect();
+    let spacing = window.checked_div(requests.len() as u32).unwrap_or_default();
+
+    let start = Instant::now();
+    let mut results = Vec::with_capacity(requests.len());
+
+    for (index, request) in requests.into_iter().enumerate() {
+        let due = start + spacing * index as u32;
+        let now = Instant::now();
+
+        if due > now {
+            crate::runtime::sleep(due - now).await;
+        }
+
+        results.push(request.await);
+    }
+
+    BatchReport::from_results(results)
+}
@@ -0,0 +1,455 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A configurable means of constructing an API wrapper, for environments where the defaults don't suffice (eg. behind
+//! a proxy, with a corporate TLS root, or within tests).
+
+use crate::error::{APIError, Result};
+use crate::middleware::RequestInterceptor;
+use crate::pool::TokenPool;
+use crate::rate_limit::RateLimitBackend;
+use crate::retry::{ExponentialBackoff, RetryPolicy};
+use crate::semaphore::Semaphore;
+use crate::stats::StatsCollector;
+use crate::throttler::{RateLimitStore, TokenRotation};
+use crate::transport::HttpTransport;
+use crate::{APIToken, APIWrapper, BASE_URL};
+
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, AUTHORIZATION};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::Proxy;
+use reqwest::{Client, ClientBuilder, Url};
+
+/// The error code we attach to builders whose configuration is invalid.
+const BUILDER_CODE: &str = "BuilderError";
+
+/// The default maximum numbers of reads and writes in flight at once.
+const DEFAULT_MAX_CONCURRENT_READS: usize = 8;
+const DEFAULT_MAX_CONCURRENT_WRITES: usize = 2;
+
+/// The default maximum duration of each request, which is generous enough for most version downloads.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The default maximum duration to wait when connecting to the API.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The default maximum number of responses held within the response cache.
+#[cfg(feature = "cache")]
+const DEFAULT_CACHE_MAX_ENTRIES: usize = 1_000;
+
+/// A builder for an API wrapper, constructed via `APIWrapper::builder()`.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::{APIToken, APIWrapper};
+/// # use std::time::Duration;
+/// # async fn run() -> bbb_api_wrapper::error::Result<()> {
+/// let token = APIToken::Private(String::from("y6xWrGkAzh8Gp4qBWFMG7tDyB+zB+Lub"));
+///
+/// let wrapper = APIWrapper::builder(token)
+///     .timeout(Duration::from_secs(30))
+///     .connect_timeout(Duration::from_secs(5))
+///     .health_check(false)
+///     .build()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct APIWrapperBuilder {
+    token: APIToken,
+    pool: Option<TokenPool>,
+    base_url: Url,
+    client: Option<Client>,
+    transport: Option<Box<dyn HttpTransport>>,
+    default_headers: HeaderMap,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    #[cfg(not(target_arch = "wasm32"))]
+    proxies: Vec<Proxy>,
+    #[cfg(not(target_arch = "wasm32"))]
+    env_proxies: bool,
+    #[cfg(all(feature = "compression", not(target_arch = "wasm32")))]
+    compression: bool,
+    rate_limit_backend: Option<Box<dyn RateLimitBackend>>,
+    max_rate_limit_retries: Option<u32>,
+    rate_limit_deadline: Option<Duration>,
+    retry_policy: Option<Box<dyn RetryPolicy + Send + Sync>>,
+    retry_writes: bool,
+    max_concurrent_reads: Option<usize>,
+    max_concurrent_writes: Option<usize>,
+    interceptors: Vec<Box<dyn RequestInterceptor>>,
+    #[cfg(feature = "cache")]
+    cache_ttl: Option<Duration>,
+    #[cfg(feature = "cache")]
+    cache_max_entries: usize,
+    username_cache_ttl: Option<Duration>,
+    health_check: bool,
+}
+
+impl APIWrapperBuilder {
+    pub(crate) fn new(token: APIToken) -> APIWrapperBuilder {
+        APIWrapperBuilder {
+            token,
+            pool: None,
+            base_url: Url::parse(BASE_URL).expect("base URL not valid"),
+            client: None,
+            transport: None,
+            default_headers: HeaderMap::new(),
+            timeout: Some(DEFAULT_TIMEOUT),
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            #[cfg(not(target_arch = "wasm32"))]
+            proxies: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            env_proxies: true,
+            #[cfg(all(feature = "compression", not(target_arch = "wasm32")))]
+            compression: true,
+            rate_limit_backend: None,
+            max_rate_limit_retries: None,
+            rate_limit_deadline: None,
+            retry_policy: Some(Box::new(ExponentialBackoff::default())),
+            retry_writes: false,
+            max_concurrent_reads: Some(DEFAULT_MAX_CONCURRENT_READS),
+            max_concurrent_writes: Some(DEFAULT_MAX_CONCURRENT_WRITES),
+            interceptors: Vec::new(),
+            #[cfg(feature = "cache")]
+            cache_ttl: None,
+            #[cfg(feature = "cache")]
+            cache_max_entries: DEFAULT_CACHE_MAX_ENTRIES,
+            username_cache_ttl: None,
+            health_check: true,
+        }
+    }
+
+    pub(crate) fn pooled(pool: TokenPool) -> APIWrapperBuilder {
+        APIWrapperBuilder { pool: Some(pool.clone()), ..APIWrapperBuilder::new(pool.primary().clone()) }
+    }
+
+    /// Join all endpoints onto a base URL other than the API's own (eg. a staging instance or a local mock server).
+    ///
+    /// The URL should include the API version, as with the default of `https://api.builtbybit.com/v1`. Unlike the
+    /// default, plain HTTP is permitted so that local servers can be used.
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = base_url;
+        self
+    }
+
+    /// Make all requests with a pre-configured client rather than one built by the wrapper.
+    ///
+    /// The token and any default headers are attached to each API request, so needn't be configured on the client.
+    /// Timeouts and proxies set on this builder don't apply to a custom client, so should be configured on the client
+    /// itself.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Send every request (including those outside of the API) via a custom transport rather than the HTTP client (eg.
+    /// a fake returning canned responses within tests).
+    ///
+    /// Requests are still built by the HTTP client, so its default headers apply but its timeouts don't.
+    pub fn transport<T>(mut self, transport: T) -> Self
+    where
+        T: HttpTransport + 'static,
+    {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
+    /// Send additional headers with every API request.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers.extend(headers);
+        self
+    }
+
+    /// Set the maximum duration of each request, from connecting until the response body has been read (60 seconds by
+    /// default).
+    ///
+    /// Requests which exceed this (or the connect timeout) fail with `APIError::Timeout`, which is retryable.
+    ///
+    /// Browsers don't support timeouts on the client, so this is ignored within them in favour of per-request
+    /// timeouts (see `RequestOptions::with_timeout`).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum duration to wait when connecting to the API (10 seconds by default).
+    ///
+    /// This is ignored within browsers, which manage connections themselves.
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Send requests via a proxy (eg. `Proxy::https("http://proxy.internal:3128")`), which may be called repeatedly to
+    /// proxy different schemes via different proxies.
+    ///
+    /// Proxies from the `HTTP_PROXY`, `HTTPS_PROXY`, and `NO_PROXY` environment variables are honoured by default, but
+    /// are unused once any proxy is set here. SOCKS proxies (eg. `Proxy::all("socks5://127.0.0.1:1080")`) require the
+    /// `socks` feature.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Ignore any proxies set via environment variables, connecting to the API directly unless a proxy is set via
+    /// `proxy`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn no_env_proxy(mut self) -> Self {
+        self.env_proxies = false;
+        self
+    }
+
+    /// Never time out requests, waiting on hung connections indefinitely.
+    pub fn without_timeouts(mut self) -> Self {
+        self.timeout = None;
+        self.connect_timeout = None;
+        self
+    }
+
+    /// Set whether or not responses may be compressed with gzip or brotli (enabled by default), which greatly reduces
+    /// the transfer time of large lists.
+    ///
+    /// Browsers always negotiate compression themselves, so this isn't available within them.
+    #[cfg(all(feature = "compression", not(target_arch = "wasm32")))]
+    pub fn compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Store the back-off the API asks us to observe within a custom backend (eg. one shared by multiple processes
+    /// using the same token), rather than within the wrapper itself.
+    pub fn rate_limit_backend<B>(mut self, rate_limit_backend: B) -> Self
+    where
+        B: RateLimitBackend + 'static,
+    {
+        self.rate_limit_backend = Some(Box::new(rate_limit_backend));
+        self
+    }
+
+    /// Give up on a request with a `RateLimited` error once it has been rate limited this many times, rather than
+    /// resending it indefinitely.
+    pub fn max_rate_limit_retries(mut self, max_rate_limit_retries: u32) -> Self {
+        self.max_rate_limit_retries = Some(max_rate_limit_retries);
+        self
+    }
+
+    /// Give up on a request with a `RateLimited` error if it would be stalled by rate limiting (including any local
+    /// ceiling or budget) beyond this duration in total, rather than waiting indefinitely.
+    pub fn rate_limit_deadline(mut self, rate_limit_deadline: Duration) -> Self {
+        self.rate_limit_deadline = Some(rate_limit_deadline);
+        self
+    }
+
+    /// Retry failed requests (eg. due to a connection reset or a 502 response) under a policy.
+    ///
+    /// By default, reads are retried under `ExponentialBackoff::default()`, and writes aren't retried.
+    pub fn retry_policy<P>(mut self, retry_policy: P) -> Self
+    where
+        P: RetryPolicy + Send + Sync + 'static,
+    {
+        self.retry_policy = Some(Box::new(retry_policy));
+        self
+    }
+
+    /// Never retry failed requests (other than those which were rate limited).
+    pub fn without_retries(mut self) -> Self {
+        self.retry_policy = None;
+        self
+    }
+
+    /// Set whether or not writes are also retried under the retry policy (disabled by default).
+    ///
+    /// # Note
+    /// A write which failed after reaching the API (eg. due to the connection being reset before the response arrived)
+    /// may have already been applied, so retrying it may apply it twice. This should only be enabled if duplicated
+    /// writes are acceptable.
+    pub fn retry_writes(mut self, retry_writes: bool) -> Self {
+        self.retry_writes = retry_writes;
+        self
+    }
+
+    /// Limit the number of reads in flight at once (eight by default), or remove the limit with `None`.
+    ///
+    /// Reads beyond the limit wait for an earlier one to complete before passing the throttler, so that a burst of
+    /// simultaneous reads doesn't all hit the API's rate limit together.
+    pub fn max_concurrent_reads(mut self, max_concurrent_reads: Option<usize>) -> Self {
+        self.max_concurrent_reads = max_concurrent_reads;
+        self
+    }
+
+    /// Limit the number of writes in flight at once (two by default), or remove the limit with `None`.
+    pub fn max_concurrent_writes(mut self, max_concurrent_writes: Option<usize>) -> Self {
+        self.max_concurrent_writes = max_concurrent_writes;
+        self
+    }
+
+    /// Register an interceptor which can inspect and modify every API request, and observe every response.
+    ///
+    /// Multiple interceptors may be registered, and are called in the order they were registered.
+    pub fn with_middleware<I>(mut self, interceptor: I) -> Self
+    where
+        I: RequestInterceptor + 'static,
+    {
+        self.interceptors.push(Box::new(interceptor));
+        self
+    }
+
+    /// Serve repeat reads of the same endpoint and query from memory for a duration after a successful response
+    /// (disabled by default).
+    ///
+    /// Where the API returns an `ETag` or `Last-Modified` header, an expired response is revalidated via a conditional
+    /// request rather than fetched in full, so frequent polling of an unchanged list costs little bandwidth. A TTL of
+    /// zero revalidates every read, whilst `Duration::MAX` never expires.
+    ///
+    /// Any write invalidates the cached reads of its endpoint and those beneath it (eg. editing a resource invalidates
+    /// its cached details). Other cached reads can be invalidated via `APIWrapper::invalidate_cache`, or bypassed per
+    /// request via `RequestOptions::bypass_cache`.
+    #[cfg(feature = "cache")]
+    pub fn cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = Some(cache_ttl);
+        self
+    }
+
+    /// Set the maximum number of responses held within the response cache (1,000 by default).
+    ///
+    /// Once full, the least recently used response is evicted to make room for another, so a long-running client
+    /// reading many distinct endpoints doesn't grow without bound.
+    #[cfg(feature = "cache")]
+    pub fn cache_max_entries(mut self, cache_max_entries: usize) -> Self {
+        self.cache_max_entries = cache_max_entries;
+        self
+    }
+
+    /// Remember the member ID which each username resolves to for a duration (disabled by default).
+    ///
+    /// Whilst enabled, `MembersHelper::resolve_id` answers repeat lookups of a username from memory rather than
+    /// fetching the member again. Entries can be invalidated via `MembersHelper::invalidate_username`.
+    ///
+    /// Pass `Duration::MAX` for entries which never expire.
+    pub fn username_cache_ttl(mut self, username_cache_ttl: Duration) -> Self {
+        self.username_cache_ttl = Some(username_cache_ttl);
+        self
+    }
+
+    /// Set whether or not to make a request to the `health` endpoint during construction (enabled by default).
+    ///
+    /// Disabling this allows a wrapper to be constructed while the API is unreachable (eg. in tests or at startup).
+    pub fn health_check(mut self, health_check: bool) -> Self {
+        self.health_check = health_check;
+        self
+    }
+
+    /// Construct the wrapper, making a health check request if enabled.
+    pub async fn build(self) -> Result<APIWrapper> {
+        if self.base_url.cannot_be_a_base() {
+            let message = format!("The base URL '{}' cannot have endpoints joined onto it", self.base_url);
+            return Err(APIError::local(BUILDER_CODE, message));
+        }
+
+        // Without a TLS backend, the client would otherwise fail each request with a far less helpful error.
+        #[cfg(not(any(
+            feature = "native-tls",
+            feature = "rustls",
+            feature = "rustls-native-roots",
+            target_arch = "wasm32"
+        )))]
+        if self.client.is_none() && self.transport.is_none() && self.base_url.scheme() == "https" {
+            let message = "No TLS backend is enabled, so HTTPS requests can't be sent (see the `rustls` feature)";
+            return Err(APIError::local(BUILDER_CODE, message.to_string()));
+        }
+
+        let mut headers = self.default_headers;
+        headers.remove(AUTHORIZATION);
+
+        // The token is attached per request rather than as a default header, so the same client can fetch media hosted
+        // outside of the API without ever sending the token there.
+        let http_client = match self.client {
+            Some(client) => client,
+            None => {
+                #[cfg(not(target_arch = "wasm32"))]
+                let mut builder = ClientBuilder::new().https_only(self.base_url.scheme() == "https");
+
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                if !self.env_proxies {
+                    builder = builder.no_proxy();
+                }
+                #[cfg(all(feature = "compression", not(target_arch = "wasm32")))]
+                {
+                    builder = builder.gzip(self.compression).brotli(self.compression);
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                for proxy in self.proxies {
+                    builder = builder.proxy(proxy);
+                }
+
+                #[cfg(target_arch = "wasm32")]
+                let builder = ClientBuilder::new();
+
+                builder.build()?
+            }
+        };
+
+        let mut rate_limit_store = match self.rate_limit_backend {
+            Some(backend) => RateLimitStore::with_backend(backend),
+            None => RateLimitStore::new(),
+        };
+
+        if let Some(pool) = &self.pool {
+            let tokens = pool.tokens.iter().skip(1).map(APIToken::header_value).collect::<Result<_>>()?;
+            rate_limit_store.pool = Some(TokenRotation::new(tokens, pool.rotation));
+        }
+
+        let transport = match self.transport {
+            Some(mut transport) => {
+                transport.use_client(&http_client);
+                transport
+            }
+            None => Box::new(http_client.clone()),
+        };
+
+        let wrapper = APIWrapper {
+            transport,
+            http_client,
+            headers,
+            token: RwLock::new(self.token.header_value()?),
+            base_url: self.base_url,
+            rate_limit_store,
+            max_rate_limit_retries: self.max_rate_limit_retries,
+            rate_limit_deadline: self.rate_limit_deadline,
+            retry_policy: self.retry_policy,
+            retry_writes: self.retry_writes,
+            read_concurrency: self.max_concurrent_reads.map(|permits| Semaphore::new(permits.max(1))),
+            write_concurrency: self.max_concurrent_writes.map(|permits| Semaphore::new(permits.max(1))),
+            interceptors: self.interceptors,
+            stats: StatsCollector::new(),
+            #[cfg(feature = "cache")]
+            cache: self.cache_ttl.map(|ttl| crate::cache::ResponseCache::new(ttl, self.cache_max_entries)),
+            usernames: self.username_cache_ttl.map(crate::usernames::UsernameCache::new),
+            maintenance: AtomicBool::new(false),
+            unhealthy: AtomicBool::new(false),
+            watchdogs: AtomicUsize::new(0),
+            #[cfg(feature = "fault-injection")]
+            faults: std::sync::Mutex::new(None),
+        };
+
+        if self.health_check {
+            wrapper.health().await?;
+        }
+
+        Ok(wrapper)
+    }
+}
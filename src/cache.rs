@@ -0,0 +1,219 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! An in-memory cache of successful read responses, so that repeat reads of slowly-changing data (eg. resource details
+//! or member profiles) needn't count towards the API's rate limits.
+//!
+//! Responses are cached as their raw body by their full URL (ie. endpoint and query string), and decoded afresh for
+//! each read. If the API returned validators (an `ETag` or `Last-Modified` header) with a response, it's kept beyond
+//! its expiry and revalidated via a conditional request, so an unchanged response needn't be sent again. Health checks
+//! are never cached, so they always reflect the API's current state.
+//!
+//! Responses aren't keyed by the token which read them, so the cache is cleared whenever the token is replaced. Reads
+//! are spread across a pool's tokens regardless of the cache, so pooled tokens should authenticate as the same account.
+
+use crate::error::{APIError, Result};
+use crate::http::{self, APIResponse};
+use crate::options;
+use crate::runtime::Instant;
+use crate::throttler::RequestType;
+use crate::APIWrapper;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
+
+/// The error code we attach to responses which are only valid for a cached response that we don't have.
+const CACHE_CODE: &str = "CacheError";
+
+struct Entry {
+    body: Bytes,
+    /// The conditional request headers which revalidate this response, if the API returned any validators.
+    validators: HeaderMap,
+    /// When this response expires, or `None` if the TTL is too large to ever expire.
+    expires_at: Option<Instant>,
+    last_used: Instant,
+}
+
+impl Entry {
+    fn is_fresh(&self, now: Instant) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+pub(crate) struct ResponseCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<Url, Entry>>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration, max_entries: usize) -> ResponseCache {
+        ResponseCache { ttl, max_entries, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Returns a cached body along with its validators and whether or not it has yet to expire.
+    fn lookup(&self, url: &Url) -> Option<(Bytes, HeaderMap, bool)> {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(url)?;
+
+        entry.last_used = now;
+        Some((entry.body.clone(), entry.validators.clone(), entry.is_fresh(now)))
+    }
+
+    fn store(&self, url: &Url, body: Bytes, validators: HeaderMap) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+
+        // Expired entries without validators can never be used again, so we purge them here to stop the cache growing
+        // without bound.
+        entries.retain(|_, entry| entry.is_fresh(now) || !entry.validators.is_empty());
+
+        // Those with validators are kept indefinitely, so we then evict the least recently used once full.
+        while !entries.contains_key(url) && entries.len() >= self.max_entries {
+            match entries.iter().min_by_key(|(_, entry)| entry.last_used).map(|(key, _)| key.clone()) {
+                Some(key) => entries.remove(&key),
+                None => return,
+            };
+        }
+
+        entries.insert(url.clone(), Entry { body, validators, expires_at: now.checked_add(self.ttl), last_used: now });
+    }
+
+    /// Extend the expiry of a cached response which the API confirmed is unchanged.
+    fn refresh(&self, url: &Url) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(url) {
+            entry.expires_at = Instant::now().checked_add(self.ttl);
+        }
+    }
+
+    /// Returns the number of responses cached, including any which have expired but are kept for revalidation.
+    pub(crate) fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Remove every cached response.
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Remove the cached responses of a URL's path and any paths beneath it, regardless of their query strings.
+    pub(crate) fn invalidate(&self, url: &Url) {
+        let path = url.path().trim_end_matches('/');
+        self.entries.lock().unwrap().retain(|key, _| !is_within(key.path(), path));
+    }
+
+    /// Remove the cached responses which a write to a URL may have made stale, regardless of their query strings.
+    ///
+    /// Alongside the URL's path and any paths beneath it, this includes each collection above it (up to the base URL)
+    /// and the named views within each along with the paths beneath them (eg. `resources/owned` or
+    /// `resources/1/updates/latest`), as they may list or be derived from the content written to.
+    pub(crate) fn invalidate_write(&self, base: &Url, url: &Url) {
+        let base = base.path().trim_end_matches('/');
+        let path = url.path().trim_end_matches('/');
+
+        let ancestors: Vec<&str> = path
+            .match_indices('/')
+            .map(|(index, _)| &path[..index])
+            .filter(|ancestor| ancestor.len() > base.len() && ancestor.starts_with(base))
+            .collect();
+
+        self.entries.lock().unwrap().retain(|key, _| {
+            let key = key.path();
+            !(is_within(key, path) || ancestors.iter().any(|ancestor| key == *ancestor || is_named_view(key, ancestor)))
+        });
+    }
+}
+
+/// Returns whether or not a path is the same as, or beneath, another.
+fn is_within(path: &str, parent: &str) -> bool {
+    path == parent || path.strip_prefix(parent).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Returns whether or not a path is beneath a non-numeric segment directly within a collection (ie. it's a view of the
+/// collection rather than one of its items).
+fn is_named_view(path: &str, collection: &str) -> bool {
+    let rest = path.strip_prefix(collection).and_then(|rest| rest.strip_prefix('/'));
+    rest.and_then(|rest| rest.split('/').next()).is_some_and(|segment| segment.parse::<u64>().is_err())
+}
+
+/// Make a GET request via the cache, unless bypassed by the current scope's options.
+///
+/// Only successful responses are cached. A read which bypasses the cache (or whose cached response has expired) still
+/// sends a conditional request if possible, and refreshes the cached response.
+pub(crate) async fn get<D>(wrapper: &APIWrapper, cache: &ResponseCache, url: &Url) -> Result<APIResponse<D>>
+where
+    D: DeserializeOwned,
+{
+    let bypass = options::current().and_then(|options| options.bypass_cache).unwrap_or(false);
+
+    let (cached, validators) = match cache.lookup(url) {
+        Some((body, _, true)) if !bypass => return decode(url, body),
+        Some((body, validators, _)) => (Some(body), validators),
+        None => (None, HeaderMap::new()),
+    };
+
+    let response = http::retrying(wrapper, RequestType::Read, || async {
+        let response = http::get_response_with(wrapper, url, &validators).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        let response = http::check_status(response)?;
+        let validators = validators_of(response.headers());
+        Ok(Some((response.bytes().await?, validators)))
+    })
+    .await?;
+
+    match (response, cached) {
+        (None, Some(cached)) => {
+            cache.refresh(url);
+            decode(url, cached)
+        }
+        (Some((body, validators)), _) => {
+            let response: APIResponse<D> = decode(url, body.clone())?;
+
+            if response.is_success() {
+                cache.store(url, body, validators);
+            }
+
+            Ok(response)
+        }
+        // We only send validators alongside a cached response, so the API shouldn't respond as such otherwise.
+        // Resending wouldn't help as the request was unconditional, so this isn't retryable.
+        (None, None) => {
+            let message =
+                format!("The API responded with status {} to an unconditional request", StatusCode::NOT_MODIFIED);
+            Err(APIError::local(CACHE_CODE, message))
+        }
+    }
+}
+
+/// Map the validators of a response onto the headers which make a conditional request for it.
+fn validators_of(headers: &HeaderMap) -> HeaderMap {
+    let mut validators = HeaderMap::new();
+
+    if let Some(etag) = headers.get(ETAG) {
+        validators.insert(IF_NONE_MATCH, etag.clone());
+    }
+    if let Some(last_modified) = headers.get(LAST_MODIFIED) {
+        validators.insert(IF_MODIFIED_SINCE, last_modified.clone());
+    }
+
+    validators
+}
+
+fn decode<D>(url: &Url, body: Bytes) -> Result<APIResponse<D>>
+where
+    D: DeserializeOwned,
+{
+    // As the body is shared with the cache, converting it into a vector copies it (leaving the cached body intact).
+    http::decode(&mut Vec::from(body)).map_err(|error| error.at_endpoint(url.path()))
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Probing of which endpoint families the current token is permitted to use.
+
+use crate::error::Result;
+use crate::sort::SortOptions;
+use crate::APIWrapper;
+
+use serde::Serialize;
+
+/// Whether the current token can use an endpoint family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Capability {
+    Available,
+    Unavailable,
+    /// The family couldn't be probed (eg. resource sub-families when no resources are owned).
+    Unknown,
+}
+
+impl Capability {
+    pub fn is_available(&self) -> bool {
+        *self == Capability::Available
+    }
+}
+
+/// The endpoint families the current token can use.
+///
+/// # Note
+/// Each family is probed via a read-only request, so availability reflects read access. Write endpoints within a
+/// family are assumed to follow, as probing them would require making changes.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Capabilities {
+    pub alerts: Capability,
+    pub conversations: Capability,
+    pub threads: Capability,
+    pub members: Capability,
+    pub resources: Capability,
+    pub downloads: Capability,
+    pub licenses: Capability,
+    pub purchases: Capability,
+    pub reviews: Capability,
+    /// Only accessible to staff members.
+    pub metrics: Capability,
+}
+
+/// Map the outcome of a probe to a capability, propagating errors which don't tell us anything about permissions.
+fn probe<T>(result: Result<T>) -> Result<Capability> {
+    match result {
+        Ok(_) => Ok(Capability::Available),
+        // Content being missing still means we were permitted to ask for it.
+        Err(error) if error.is_not_found() => Ok(Capability::Available),
+        Err(error) if error.is_retryable() => Err(error),
+        Err(_) => Ok(Capability::Unavailable),
+    }
+}
+
+pub(crate) async fn probe_all(wrapper: &APIWrapper) -> Result<Capabilities> {
+    // A single page is enough to tell whether a family can be listed.
+    let sort = SortOptions::default().page(1);
+    let sort = Some(&sort);
+
+    let owned = wrapper.resources().list_owned(sort).await;
+    let resource_id = owned.as_ref().ok().and_then(|owned| owned.first()).map(|resource| *resource.resource_id());
+
+    let mut capabilities = Capabilities {
+        alerts: probe(wrapper.alerts().list_unread(sort).await)?,
+        conversations: probe(wrapper.conversations().list_unread(sort).await)?,
+        threads: probe(wrapper.threads().list_threads(sort).await)?,
+        members: probe(wrapper.members().fetch_self().await)?,
+        resources: probe(owned)?,
+        downloads: Capability::Unknown,
+        licenses: Capability::Unknown,
+        purchases: Capability::Unknown,
+        reviews: Capability::Unknown,
+        metrics: probe(wrapper.metrics().await)?,
+    };
+
+    if let Some(resource_id) = resource_id {
+        let resources = wrapper.resources();
+
+        capabilities.downloads = probe(resources.downloads().list(resource_id, sort).await)?;
+        capabilities.licenses = probe(resources.licenses().list(resource_id, sort).await)?;
+        capabilities.purchases = probe(resources.purchases().list(resource_id, sort).await)?;
+        capabilities.reviews = probe(resources.reviews().list(resource_id, sort).await)?;
+    }
+
+    Ok(capabilities)
+}
@@ -0,0 +1,213 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Recording of real API responses to a JSON file (a "cassette") and deterministic replay of them afterwards, so that
+//! test suites can run quickly, offline, and without consuming the rate limit budget once recorded.
+//!
+//! # Example
+//! ```no_run
+//! # use bbb_api_wrapper::cassette::{Cassette, CassetteMode};
+//! # use bbb_api_wrapper::{APIToken, APIWrapper};
+//! # async fn run() -> bbb_api_wrapper::error::Result<()> {
+//! // Records on the first run (requiring a real token), then replays from the file on every run afterwards.
+//! let cassette = Cassette::open("tests/cassettes/licenses.json", CassetteMode::Auto)?;
+//!
+//! let token = APIToken::Private(std::env::var("BBB_TOKEN").unwrap_or_default());
+//! let wrapper = APIWrapper::builder(token).transport(cassette).build().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{APIError, Result};
+use crate::transport::{self, HttpTransport, TransportFuture};
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::{Client, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+/// The error code we attach to requests which a cassette can't serve (eg. those with no recorded response).
+const CASSETTE_CODE: &str = "CassetteError";
+
+/// Response headers which describe how the body was transferred rather than the response itself, so aren't recorded.
+const TRANSFER_HEADERS: &[&str] =
+    &["connection", "content-encoding", "content-length", "set-cookie", "transfer-encoding"];
+
+/// Whether a cassette sends requests to the API or replays recorded responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send every request and record its response, replacing any previous recording.
+    Record,
+    /// Replay recorded responses without sending any requests, failing those which weren't recorded.
+    Replay,
+    /// Replay if the cassette's file exists, and record otherwise.
+    Auto,
+}
+
+/// A single recorded request and its response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    method: String,
+    /// The request's path and query string (eg. `/v1/resources/1/licenses?page=2`).
+    path: String,
+    status: u16,
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+/// A transport which records responses to, or replays them from, a JSON file.
+///
+/// Requests are matched by method, path, and query string (but not body). Identical requests are replayed in the order
+/// they were recorded, with the final response repeating once exhausted. Rate limited responses aren't recorded, as
+/// the wrapper resends those requests itself.
+///
+/// No request headers are recorded, so the token never appears within a cassette. Bodies are recorded as text, so
+/// binary responses (eg. version downloads) aren't replayed faithfully.
+pub struct Cassette {
+    path: PathBuf,
+    recording: bool,
+    /// The transport which recorded requests are sent via, or `None` to use the wrapper's HTTP client.
+    inner: Option<Box<dyn HttpTransport>>,
+    interactions: Mutex<Vec<Interaction>>,
+    /// The number of times each request has been replayed, keyed by method and path.
+    replayed: Mutex<HashMap<(String, String), usize>>,
+}
+
+impl Cassette {
+    /// Open a cassette, recording via the wrapper's HTTP client (so with its timeouts and proxies).
+    pub fn open<P: Into<PathBuf>>(path: P, mode: CassetteMode) -> Result<Cassette> {
+        Cassette::new(path.into(), mode, None)
+    }
+
+    /// Open a cassette, recording via another transport rather than the wrapper's HTTP client.
+    pub fn with_transport<P, T>(path: P, mode: CassetteMode, inner: T) -> Result<Cassette>
+    where
+        P: Into<PathBuf>,
+        T: HttpTransport + 'static,
+    {
+        Cassette::new(path.into(), mode, Some(Box::new(inner)))
+    }
+
+    fn new(path: PathBuf, mode: CassetteMode, inner: Option<Box<dyn HttpTransport>>) -> Result<Cassette> {
+        let recording = match mode {
+            CassetteMode::Record => true,
+            CassetteMode::Replay => false,
+            CassetteMode::Auto => !path.exists(),
+        };
+
+        let interactions = if recording { Vec::new() } else { serde_json::from_slice(&fs::read(&path)?)? };
+
+        Ok(Cassette {
+            path,
+            recording,
+            inner,
+            interactions: Mutex::new(interactions),
+            replayed: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns whether or not this cassette is recording (rather than replaying) responses.
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    async fn record(&self, request: Request) -> Result<Response> {
+        let method = request.method().to_string();
+        let path = path_of(&request);
+
+        let Some(inner) = &self.inner else {
+            let message = "The cassette has no client to record with, as it isn't set as a wrapper's transport";
+            return Err(APIError::local(CASSETTE_CODE, message.to_string()));
+        };
+
+        // The wrapper's request bodies are always buffered, so can be cloned.
+        let response = inner.execute(request.try_clone().expect("request body not buffered")).await?;
+
+        let status = response.status();
+        let headers: BTreeMap<String, String> = response
+            .headers()
+            .iter()
+            .filter(|(name, _)| !TRANSFER_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.text().await?;
+
+        let mut recorded = transport::respond(&request, status, body.clone());
+        recorded.headers_mut().extend(response_headers(&headers));
+
+        if status != StatusCode::TOO_MANY_REQUESTS {
+            let mut interactions = self.interactions.lock().unwrap();
+            interactions.push(Interaction { method, path, status: status.as_u16(), headers, body });
+
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&self.path, serde_json::to_vec_pretty(&*interactions)?)?;
+        }
+
+        Ok(recorded)
+    }
+
+    fn replay(&self, request: &Request) -> Result<Response> {
+        let key = (request.method().to_string(), path_of(request));
+        let interactions = self.interactions.lock().unwrap();
+
+        let matching: Vec<&Interaction> = interactions
+            .iter()
+            .filter(|interaction| interaction.method == key.0 && interaction.path == key.1)
+            .collect();
+
+        if matching.is_empty() {
+            let message =
+                format!("The cassette '{}' has no recorded response for {} {}", self.path.display(), key.0, key.1);
+            return Err(APIError::local(CASSETTE_CODE, message));
+        }
+
+        let mut replayed = self.replayed.lock().unwrap();
+        let count = replayed.entry(key).or_insert(0);
+        let interaction = matching[(*count).min(matching.len() - 1)];
+        *count += 1;
+
+        let status = StatusCode::from_u16(interaction.status).unwrap_or(StatusCode::OK);
+        let mut response = transport::respond(request, status, interaction.body.clone());
+        response.headers_mut().extend(response_headers(&interaction.headers));
+
+        Ok(response)
+    }
+}
+
+impl HttpTransport for Cassette {
+    fn execute(&self, request: Request) -> TransportFuture<'_> {
+        Box::pin(async move {
+            if self.recording {
+                self.record(request).await
+            } else {
+                self.replay(&request)
+            }
+        })
+    }
+
+    fn use_client(&mut self, client: &Client) {
+        if self.inner.is_none() {
+            self.inner = Some(Box::new(client.clone()));
+        }
+    }
+}
+
+fn path_of(request: &Request) -> String {
+    match request.url().query() {
+        Some(query) => format!("{}?{}", request.url().path(), query),
+        None => request.url().path().to_string(),
+    }
+}
+
+fn response_headers(headers: &BTreeMap<String, String>) -> reqwest::header::HeaderMap {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((HeaderName::try_from(name).ok()?, HeaderValue::try_from(value).ok()?)))
+        .collect()
+}
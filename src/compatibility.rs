@@ -0,0 +1,118 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Checking of whether the API still matches the version and response shapes this crate was built against, so that
+//! breaking upstream changes are noticed proactively rather than via deserialisation failures.
+
+use crate::data::members::MemberData;
+use crate::endpoints::Endpoint;
+use crate::error::Result;
+use crate::APIWrapper;
+
+use std::collections::BTreeSet;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+/// The API versions this crate was built against.
+pub const SUPPORTED_VERSIONS: &[&str] = &["v1"];
+
+/// A single difference between the API and what this crate expects of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum CompatibilityIssue {
+    /// The wrapper targets an API version this crate wasn't built for.
+    UnsupportedVersion { version: String },
+    /// A response contained a field which this crate doesn't model (usually a harmless addition).
+    UnexpectedField { endpoint: String, field: String },
+    /// A response couldn't be deserialised into this crate's types (eg. a field was removed or changed type).
+    UndecodableResponse { endpoint: String, error: String },
+}
+
+impl CompatibilityIssue {
+    /// Returns whether or not this issue will cause requests to fail, rather than being a warning.
+    pub fn is_mismatch(&self) -> bool {
+        !matches!(self, CompatibilityIssue::UnexpectedField { .. })
+    }
+}
+
+/// The outcome of checking the API's compatibility with this crate.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompatibilityReport {
+    /// The API version the wrapper targets, taken from its base URL.
+    pub api_version: Option<String>,
+    pub supported_versions: Vec<String>,
+    pub issues: Vec<CompatibilityIssue>,
+}
+
+impl CompatibilityReport {
+    /// Returns whether or not no mismatches were found (warnings may still be present).
+    pub fn is_compatible(&self) -> bool {
+        !self.issues.iter().any(CompatibilityIssue::is_mismatch)
+    }
+
+    /// Returns whether or not any warnings (ie. issues which aren't mismatches) were found.
+    pub fn has_warnings(&self) -> bool {
+        self.issues.iter().any(|issue| !issue.is_mismatch())
+    }
+}
+
+/// Check the wrapper's API version and probe the shape of a representative response.
+///
+/// The API doesn't currently report its version other than within the base URL, so the shape probe is what detects
+/// changes made within a version.
+pub(crate) async fn check(wrapper: &APIWrapper) -> Result<CompatibilityReport> {
+    // A trailing slash leaves an empty final segment, so the version is the last non-empty one.
+    let api_version = wrapper
+        .base_url
+        .path_segments()
+        .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+        .map(str::to_string);
+
+    let mut issues = Vec::new();
+
+    match &api_version {
+        Some(version) if SUPPORTED_VERSIONS.contains(&version.as_str()) => (),
+        _ => issues.push(CompatibilityIssue::UnsupportedVersion { version: api_version.clone().unwrap_or_default() }),
+    }
+
+    let response: Value = wrapper.get(Endpoint::MembersSelf, None).await?;
+    issues.extend(probe::<MemberData>("members/self", response));
+
+    Ok(CompatibilityReport {
+        api_version,
+        supported_versions: SUPPORTED_VERSIONS.iter().map(|version| version.to_string()).collect(),
+        issues,
+    })
+}
+
+/// Compare a raw response against the type it's deserialised into by this crate.
+fn probe<T>(endpoint: &str, response: Value) -> Vec<CompatibilityIssue>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let decoded = match serde_json::from_value::<T>(response.clone()) {
+        Ok(decoded) => decoded,
+        Err(error) => {
+            return vec![CompatibilityIssue::UndecodableResponse {
+                endpoint: endpoint.to_string(),
+                error: error.to_string(),
+            }]
+        }
+    };
+
+    // Round-tripping through our type drops any fields it doesn't model, leaving just those which the API added.
+    let known = match serde_json::to_value(decoded) {
+        Ok(Value::Object(map)) => map.keys().cloned().collect(),
+        _ => BTreeSet::new(),
+    };
+
+    match response {
+        Value::Object(map) => map
+            .into_iter()
+            .filter(|(field, _)| !known.contains(field))
+            .map(|(field, _)| CompatibilityIssue::UnexpectedField { endpoint: endpoint.to_string(), field })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -1,19 +1,93 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
+use crate::data::conversations::ConversationData;
+use crate::data::members::{MemberData, ProfilePostData};
+use crate::data::resources::ResourceData;
+use crate::data::threads::ThreadData;
+use crate::timestamps::format_iso_date;
+
+use std::fmt;
+
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
+string_enum!(
+    /// The type of content an alert refers to.
+    ContentType {
+        Resource => "resource",
+        ResourceUpdate => "resource_update",
+        /// A review of a resource.
+        ResourceReview => "resource_rating",
+        Thread => "thread",
+        /// A reply within a thread.
+        Post => "post",
+        ProfilePost => "profile_post",
+        ProfilePostComment => "profile_post_comment",
+        Conversation => "conversation",
+        ConversationMessage => "conversation_message",
+        /// A member's profile.
+        Member => "user",
+    }
+);
+
+string_enum!(
+    /// The action an alert notifies of.
+    AlertType {
+        Reaction => "reaction",
+        Reply => "reply",
+        Review => "review",
+        Quote => "quote",
+        Mention => "mention",
+        /// The content was newly created (eg. an update posted to a watched resource).
+        Insert => "insert",
+    }
+);
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AlertData {
     caused_member_id: u64,
-    content_type: String,
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    content_type: ContentType,
     content_id: u64,
-    alert_type: String,
+    #[cfg_attr(feature = "schemars", schemars(with = "String"))]
+    alert_type: AlertType,
     alert_date: u64,
 }
 
+impl fmt::Display for AlertData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Alert: {} on {} #{} by member {} on {}",
+            self.alert_type,
+            self.content_type,
+            self.content_id,
+            self.caused_member_id,
+            format_iso_date(self.alert_date)
+        )
+    }
+}
+
+/// The content an alert refers to, fetched based on its content type and ID.
+#[derive(Debug, Clone)]
+pub enum ResolvedContent {
+    Resource(ResourceData),
+    Thread(ThreadData),
+    ProfilePost(ProfilePostData),
+    Conversation(ConversationData),
+    /// The member whose profile the alert refers to (eg. a new follower).
+    Member(MemberData),
+    /// The content type isn't one we're able to resolve via the API.
+    Unresolved {
+        content_type: ContentType,
+        content_id: u64,
+    },
+}
+
 #[derive(Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct AlertReadBody {
     pub read: bool,
 }
@@ -1,10 +1,15 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
+use crate::timestamps::format_iso_date;
+
+use std::fmt;
+
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ConversationData {
     conversation_id: u64,
     title: String,
@@ -17,7 +22,24 @@ pub struct ConversationData {
     recipient_ids: Vec<u64>,
 }
 
+impl fmt::Display for ConversationData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = if self.open { "open" } else { "closed" };
+
+        write!(
+            f,
+            "Conversation #{}: {} ({}, {} replies, last message on {})",
+            self.conversation_id,
+            self.title,
+            state,
+            self.reply_count,
+            format_iso_date(self.last_message_date)
+        )
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ReplyData {
     message_id: u64,
     message_date: u64,
@@ -25,7 +47,15 @@ pub struct ReplyData {
     message: String,
 }
 
+impl fmt::Display for ReplyData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date = format_iso_date(self.message_date);
+        write!(f, "Message #{}: by member {} on {}", self.message_id, self.author_id, date)
+    }
+}
+
 #[derive(Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct ConversationStartBody<'a> {
     pub title: &'a str,
     pub message: &'a str,
@@ -33,6 +63,7 @@ pub(crate) struct ConversationStartBody<'a> {
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct ConversationReplyBody<'a> {
     pub message: &'a str,
 }
@@ -1,10 +1,76 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
+use crate::timestamps::format_iso_date;
+
+use std::fmt;
+
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
+/// The sizes in which the site serves avatars.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AvatarSize {
+    /// 48x48 pixels.
+    Small,
+    /// 96x96 pixels.
+    Medium,
+    /// 192x192 pixels.
+    #[default]
+    Large,
+    /// 384x384 pixels.
+    High,
+    /// The image as uploaded.
+    Original,
+}
+
+impl AvatarSize {
+    fn code(self) -> &'static str {
+        match self {
+            AvatarSize::Small => "s",
+            AvatarSize::Medium => "m",
+            AvatarSize::Large => "l",
+            AvatarSize::High => "h",
+            AvatarSize::Original => "o",
+        }
+    }
+}
+
+/// Returns the URL of a member's avatar following the site's avatar URL scheme.
+///
+/// # Note
+/// The URL is constructed locally and will point to a missing image if the member hasn't set an avatar.
+pub fn avatar_url(member_id: u64, size: AvatarSize) -> String {
+    format!("{}/data/avatars/{}/{}/{}.jpg", crate::SITE_URL, size.code(), member_id / 1000, member_id)
+}
+
+/// Returns the URL of a member's profile, which includes their username (if known) as the site's canonical URLs do.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::data::members::profile_url;
+/// assert_eq!("https://builtbybit.com/members/harry.87939/", profile_url(87939, Some("Harry")));
+/// assert_eq!("https://builtbybit.com/members/87939/", profile_url(87939, None));
+/// ```
+pub fn profile_url(member_id: u64, username: Option<&str>) -> String {
+    let mut slug = String::new();
+
+    for character in username.unwrap_or_default().chars() {
+        if character.is_ascii_alphanumeric() {
+            slug.push(character.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    match slug.trim_end_matches('-') {
+        "" => format!("{}/members/{}/", crate::SITE_URL, member_id),
+        slug => format!("{}/members/{}.{}/", crate::SITE_URL, slug, member_id),
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MemberData {
     member_id: u64,
     username: String,
@@ -24,9 +90,66 @@ pub struct MemberData {
     feedback_positive: u64,
     feedback_neutral: u64,
     feedback_negative: u64,
+    /// The member's IANA timezone (eg. "Europe/London"), if known.
+    timezone: Option<String>,
+}
+
+impl MemberData {
+    /// Returns the URL of this member's (large) avatar following the site's avatar URL scheme.
+    ///
+    /// # Note
+    /// The URL is constructed locally and will point to a missing image if the member hasn't set an avatar.
+    pub fn avatar_url(&self) -> String {
+        avatar_url(self.member_id, AvatarSize::Large)
+    }
+
+    /// Returns the URL of this member's avatar in a specific size (see `avatar_url`).
+    pub fn avatar_url_sized(&self, size: AvatarSize) -> String {
+        avatar_url(self.member_id, size)
+    }
+
+    /// Returns the canonical URL of this member's profile.
+    pub fn profile_url(&self) -> String {
+        profile_url(self.member_id, Some(&self.username))
+    }
+
+    /// Convert a UNIX timestamp (in seconds) into a date-time within this member's timezone.
+    ///
+    /// Returns `None` if the member's timezone isn't known or recognised.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper, expiry: u64) -> bbb_api_wrapper::error::Result<()> {
+    /// let member = wrapper.members().fetch_by_id(87939).await?;
+    ///
+    /// if let Some(expiry) = member.local_time(expiry) {
+    ///     println!("Your license expires on {}.", expiry.format("%-d %B %Y at %H:%M"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "timezones")]
+    pub fn local_time(&self, timestamp: u64) -> Option<chrono::DateTime<chrono_tz::Tz>> {
+        crate::timestamps::to_local(timestamp, self.timezone.as_deref()?)
+    }
+}
+
+impl fmt::Display for MemberData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Member #{}: {} (joined {})", self.member_id, self.username, format_iso_date(self.join_date))?;
+
+        if self.banned {
+            write!(f, " [banned]")?;
+        } else if self.suspended {
+            write!(f, " [suspended]")?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ProfilePostData {
     profile_post_id: u64,
     author_id: u64,
@@ -35,7 +158,21 @@ pub struct ProfilePostData {
     comment_count: u64,
 }
 
+impl fmt::Display for ProfilePostData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Profile post #{}: by member {} on {} with {} comments",
+            self.profile_post_id,
+            self.author_id,
+            format_iso_date(self.post_date),
+            self.comment_count
+        )
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BanData {
     member_id: u64,
     banned_by_id: u64,
@@ -43,14 +180,39 @@ pub struct BanData {
     reason: String,
 }
 
+impl fmt::Display for BanData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Ban of member {} by member {} on {}: {}",
+            self.member_id,
+            self.banned_by_id,
+            format_iso_date(self.ban_date),
+            self.reason
+        )
+    }
+}
+
 #[derive(Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub(crate) struct ProfilePostCreateBody<'a> {
+    pub message: &'a str,
+}
+
+#[derive(Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct ProfilePostEditBody<'a> {
     pub message: &'a str,
 }
 
+/// The fields of the authenticated member's profile to modify, where those left as `None` are left unchanged.
 #[derive(Debug, Default, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ModifySelfBody<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub about_me: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub signature: Option<&'a str>,
-}
\ No newline at end of file
+}
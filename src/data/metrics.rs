@@ -7,12 +7,14 @@ use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MetricsSnapshot {
     interval: MetricsInterval,
     metrics: BTreeMap<String, u64>,
 }
 
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct MetricsInterval {
     time: u16,
     unit: String,
@@ -3,9 +3,107 @@
 
 //! A collection of types representing data returned from the API.
 
+/// Generates a non-exhaustive enum of the raw names the API uses for a type, falling back to `Other` for any names
+/// not listed.
+macro_rules! string_enum {
+    ($(#[$meta:meta])* $name:ident { $($(#[$variant_meta:meta])* $variant:ident => $value:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+        #[serde(from = "String", into = "String")]
+        #[non_exhaustive]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+            /// A type not listed here, along with its raw name.
+            Other(String),
+        }
+
+        impl $name {
+            /// Returns the type's raw name as used by the API.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)+
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(value: String) -> Self {
+                match value.as_str() {
+                    $($value => $name::$variant,)+
+                    _ => $name::Other(value),
+                }
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Other(value) => value,
+                    value => value.as_str().to_string(),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
 pub mod alerts;
 pub mod conversations;
 pub mod members;
 pub mod metrics;
 pub mod resources;
 pub mod threads;
+
+/// Generate a JSON Schema for every data type returned by the API and every body sent to it, keyed by type name.
+///
+/// Conversation and thread replies are keyed as `ConversationReplyData` and `ThreadReplyData` respectively.
+///
+/// # Example
+/// ```
+/// let schemas = bbb_api_wrapper::data::schemas();
+/// println!("{}", serde_json::to_string_pretty(&schemas["PurchaseData"]).unwrap());
+/// ```
+#[cfg(feature = "schemars")]
+pub fn schemas() -> std::collections::BTreeMap<&'static str, schemars::schema::RootSchema> {
+    use schemars::schema_for;
+
+    [
+        ("AlertData", schema_for!(alerts::AlertData)),
+        ("AlertReadBody", schema_for!(alerts::AlertReadBody)),
+        ("ConversationData", schema_for!(conversations::ConversationData)),
+        ("ConversationReplyData", schema_for!(conversations::ReplyData)),
+        ("ConversationStartBody", schema_for!(conversations::ConversationStartBody)),
+        ("ConversationReplyBody", schema_for!(conversations::ConversationReplyBody)),
+        ("MemberData", schema_for!(members::MemberData)),
+        ("ProfilePostData", schema_for!(members::ProfilePostData)),
+        ("BanData", schema_for!(members::BanData)),
+        ("ProfilePostCreateBody", schema_for!(members::ProfilePostCreateBody)),
+        ("ProfilePostEditBody", schema_for!(members::ProfilePostEditBody)),
+        ("ModifySelfBody", schema_for!(members::ModifySelfBody)),
+        ("MetricsSnapshot", schema_for!(metrics::MetricsSnapshot)),
+        ("BasicResourceData", schema_for!(resources::BasicResourceData)),
+        ("ResourceData", schema_for!(resources::ResourceData)),
+        ("DownloadData", schema_for!(resources::DownloadData)),
+        ("ReviewData", schema_for!(resources::ReviewData)),
+        ("UpdateData", schema_for!(resources::UpdateData)),
+        ("VersionData", schema_for!(resources::VersionData)),
+        ("LicenseData", schema_for!(resources::LicenseData)),
+        ("PurchaseData", schema_for!(resources::PurchaseData)),
+        ("LicenseModifyPermData", schema_for!(resources::LicenseModifyPermData)),
+        ("LicenseModifyTempData", schema_for!(resources::LicenseModifyTempData)),
+        ("ReviewRespondData", schema_for!(resources::ReviewRespondData)),
+        ("ResourceModifyData", schema_for!(resources::ResourceModifyData)),
+        ("BasicThreadData", schema_for!(threads::BasicThreadData)),
+        ("ThreadData", schema_for!(threads::ThreadData)),
+        ("ThreadReplyData", schema_for!(threads::ReplyData)),
+        ("ReplyBody", schema_for!(threads::ReplyBody)),
+    ]
+    .into_iter()
+    .collect()
+}
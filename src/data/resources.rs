@@ -1,10 +1,16 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
+use crate::format::format_price_with_code;
+use crate::timestamps::format_iso_date;
+
+use std::fmt;
+
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BasicResourceData {
     resource_id: u64,
     author_id: u64,
@@ -14,7 +20,21 @@ pub struct BasicResourceData {
     currency: String,
 }
 
+impl BasicResourceData {
+    /// Returns this resource's price formatted with its currency (eg. "$4.99").
+    pub fn formatted_price(&self) -> String {
+        crate::format::format_price(self.price, &self.currency)
+    }
+}
+
+impl fmt::Display for BasicResourceData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Resource #{}: {} ({})", self.resource_id, self.title, price_or_free(self.price, &self.currency))
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ResourceData {
     resource_id: u64,
     author_id: u64,
@@ -31,9 +51,43 @@ pub struct ResourceData {
     download_count: u64,
     review_count: u64,
     review_average: f64,
+    icon_url: Option<String>,
+    banner_url: Option<String>,
+}
+
+impl ResourceData {
+    /// Returns this resource's price formatted with its currency (eg. "$4.99").
+    pub fn formatted_price(&self) -> String {
+        crate::format::format_price(self.price, &self.currency)
+    }
+}
+
+impl fmt::Display for ResourceData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Resource #{}: {} ({}) with {} purchases, {} downloads, and {:.1} stars from {} reviews",
+            self.resource_id,
+            self.title,
+            price_or_free(self.price, &self.currency),
+            self.purchase_count,
+            self.download_count,
+            self.review_average,
+            self.review_count
+        )
+    }
+}
+
+fn price_or_free(price: f64, currency: &str) -> String {
+    if price > 0.0 {
+        format_price_with_code(price, currency)
+    } else {
+        "free".to_string()
+    }
 }
 
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DownloadData {
     download_id: u64,
     version_id: u64,
@@ -41,7 +95,21 @@ pub struct DownloadData {
     download_date: u64,
 }
 
+impl fmt::Display for DownloadData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Download #{}: version {} by member {} on {}",
+            self.download_id,
+            self.version_id,
+            self.downloader_id,
+            format_iso_date(self.download_date)
+        )
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ReviewData {
     review_id: u64,
     reviewer_id: u64,
@@ -51,7 +119,21 @@ pub struct ReviewData {
     response: String,
 }
 
+impl fmt::Display for ReviewData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Review #{}: {} stars by member {} on {}",
+            self.review_id,
+            self.rating,
+            self.reviewer_id,
+            format_iso_date(self.review_date)
+        )
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct UpdateData {
     update_id: u64,
     title: String,
@@ -59,7 +141,14 @@ pub struct UpdateData {
     update_date: u64,
 }
 
+impl fmt::Display for UpdateData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Update #{}: {} on {}", self.update_id, self.title, format_iso_date(self.update_date))
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct VersionData {
     version_id: u64,
     name: String,
@@ -67,7 +156,21 @@ pub struct VersionData {
     download_count: u64,
 }
 
+impl fmt::Display for VersionData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Version #{}: {} released on {} with {} downloads",
+            self.version_id,
+            self.name,
+            format_iso_date(self.release_date),
+            self.download_count
+        )
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LicenseData {
     license_id: u64,
     purchaser_id: u64,
@@ -79,7 +182,21 @@ pub struct LicenseData {
     previous_end_date: u64,
 }
 
+impl fmt::Display for LicenseData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = if self.active { "active" } else { "inactive" };
+        write!(f, "License #{}: {} for member {}", self.license_id, state, self.purchaser_id)?;
+
+        if self.permanent {
+            write!(f, ", permanent")
+        } else {
+            write!(f, ", {} to {}", format_iso_date(self.start_date), format_iso_date(self.end_date))
+        }
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PurchaseData {
     purchase_id: u64,
     purchaser_id: u64,
@@ -92,28 +209,88 @@ pub struct PurchaseData {
     validation_date: u64,
 }
 
-#[derive(Debug, Default, Serialize)]
+impl PurchaseData {
+    /// Returns this purchase's price formatted with its currency (eg. "$4.99").
+    pub fn formatted_price(&self) -> String {
+        crate::format::format_price(self.price, &self.currency)
+    }
+
+    /// Returns whether or not this purchase has been refunded or reversed (eg. via a chargeback).
+    pub fn is_reversed(&self) -> bool {
+        self.status.eq_ignore_ascii_case("refunded") || self.status.eq_ignore_ascii_case("reversed")
+    }
+}
+
+impl fmt::Display for PurchaseData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Purchase #{}: {} by member {} on {}",
+            self.purchase_id,
+            format_price_with_code(self.price, &self.currency),
+            self.purchaser_id,
+            format_iso_date(self.purchase_date)
+        )?;
+
+        if self.is_reversed() {
+            write!(f, " ({})", self.status.to_ascii_lowercase())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LicenseModifyPermData {
     pub permanent: bool,
     pub active: bool,
 }
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct LicenseModifyTempData {
     pub permanent: bool,
     pub start_date: u64,
     pub end_date: u64,
 }
 
+/// A single modification to a license, either to a permanent or temporary one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum LicenseModification {
+    Permanent(LicenseModifyPermData),
+    Temporary(LicenseModifyTempData),
+}
+
+impl LicenseModification {
+    /// Construct the modification which would restore a license to its provided state.
+    ///
+    /// # Note
+    /// The API doesn't accept an active flag for temporary licenses, so that flag can't be restored for them.
+    pub fn restoring(license: &LicenseData) -> LicenseModification {
+        if license.permanent {
+            LicenseModification::Permanent(LicenseModifyPermData { permanent: true, active: license.active })
+        } else {
+            LicenseModification::Temporary(LicenseModifyTempData {
+                permanent: false,
+                start_date: license.start_date,
+                end_date: license.end_date,
+            })
+        }
+    }
+}
+
 #[derive(Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct ReviewRespondData<'a> {
     pub message: &'a str,
 }
 
-
 #[derive(Debug, Default, Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ResourceModifyData<'a> {
     pub title: Option<&'a str>,
     pub tag_line: Option<&'a str>,
     pub description: Option<&'a str>,
-}
\ No newline at end of file
+}
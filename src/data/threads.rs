@@ -1,10 +1,15 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
+use crate::timestamps::format_iso_date;
+
+use std::fmt;
+
 use derive_getters::Getters;
 use serde::{Deserialize, Serialize};
 
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct BasicThreadData {
     thread_id: u64,
     title: String,
@@ -14,7 +19,28 @@ pub struct BasicThreadData {
     last_message_date: u64,
 }
 
+impl fmt::Display for BasicThreadData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (replies, views) = (self.reply_count, self.view_count);
+        write!(f, "Thread #{}: {} ({} replies, {} views)", self.thread_id, self.title, replies, views)
+    }
+}
+
+string_enum!(
+    /// The kind of discussion a thread is.
+    ThreadType {
+        Discussion => "discussion",
+        Question => "question",
+        Poll => "poll",
+        Article => "article",
+        Suggestion => "suggestion",
+        /// A thread which has been moved, leaving a redirect in its original forum.
+        Redirect => "redirect",
+    }
+);
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ThreadData {
     thread_id: u64,
     forum_name: String,
@@ -27,15 +53,43 @@ pub struct ThreadData {
     last_post_date: u64,
 }
 
+impl ThreadData {
+    /// Returns the kind of discussion this thread is, as a typed enum.
+    pub fn kind(&self) -> ThreadType {
+        ThreadType::from(self.thread_type.clone())
+    }
+}
+
+impl fmt::Display for ThreadData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Thread #{}: {} in {} ({} replies, {} views)",
+            self.thread_id, self.title, self.forum_name, self.reply_count, self.view_count
+        )
+    }
+}
+
 #[derive(Getters, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ReplyData {
     reply_id: u64,
     author_id: u64,
     post_date: u64,
     message: String,
+    /// The number of reactions the reply has received, or zero if the API didn't include a count.
+    #[serde(default)]
+    like_count: u64,
+}
+
+impl fmt::Display for ReplyData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Reply #{}: by member {} on {}", self.reply_id, self.author_id, format_iso_date(self.post_date))
+    }
 }
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub(crate) struct ReplyBody<'a> {
     pub message: &'a str,
 }
@@ -0,0 +1,80 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Serialisable snapshots of the wrapper's internal state, for exposing within an application's own health checks.
+
+use crate::throttler::{self, RequestType};
+use crate::APIWrapper;
+
+use std::sync::atomic::Ordering;
+
+use serde::Serialize;
+
+/// A point-in-time view of the wrapper's internal state.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugSnapshot {
+    pub reads: ThrottleSnapshot,
+    pub writes: ThrottleSnapshot,
+    /// The number of requests currently stalled by the throttler.
+    pub queued: u64,
+    /// The number of requests which have been sent but have yet to receive a response.
+    pub in_flight: u64,
+    /// Whether or not the most recent response indicated that the API is down for maintenance.
+    pub maintenance: bool,
+    /// Whether or not faults are currently being injected into requests.
+    pub fault_injection: bool,
+    /// Whether or not the API is considered healthy (see `APIWrapper::is_healthy`).
+    pub healthy: bool,
+    /// Whether or not a health watchdog is running.
+    pub watchdog_running: bool,
+    /// The number of responses held within the response cache, if enabled.
+    pub cached_responses: Option<usize>,
+    /// The number of usernames held within the username cache, if enabled.
+    pub cached_usernames: Option<usize>,
+}
+
+/// A point-in-time view of the throttling state of a single request type.
+#[derive(Debug, Clone, Serialize)]
+pub struct ThrottleSnapshot {
+    /// How long the next request would be stalled for, in milliseconds.
+    pub stalled_for_ms: u64,
+    /// The configured requests-per-minute ceiling, if any.
+    pub ceiling: Option<u64>,
+    /// The number of requests counted towards the ceiling within the current one-minute window.
+    pub window_usage: u64,
+}
+
+pub(crate) fn snapshot(wrapper: &APIWrapper) -> DebugSnapshot {
+    let store = &wrapper.rate_limit_store;
+
+    DebugSnapshot {
+        reads: throttle_snapshot(wrapper, RequestType::Read),
+        writes: throttle_snapshot(wrapper, RequestType::Write),
+        queued: store.queued.load(Ordering::Acquire),
+        in_flight: store.in_flight.load(Ordering::Acquire),
+        maintenance: wrapper.is_in_maintenance(),
+        #[cfg(feature = "fault-injection")]
+        fault_injection: wrapper.faults.lock().unwrap().is_some(),
+        #[cfg(not(feature = "fault-injection"))]
+        fault_injection: false,
+        healthy: wrapper.is_healthy(),
+        watchdog_running: wrapper.watchdogs.load(Ordering::Acquire) > 0,
+        #[cfg(feature = "cache")]
+        cached_responses: wrapper.cache.as_ref().map(|cache| cache.len()),
+        #[cfg(not(feature = "cache"))]
+        cached_responses: None,
+        cached_usernames: wrapper.usernames.as_ref().map(|usernames| usernames.len()),
+    }
+}
+
+fn throttle_snapshot(wrapper: &APIWrapper, request_type: RequestType) -> ThrottleSnapshot {
+    let store = &wrapper.rate_limit_store;
+    let (stalled_for_ms, window_usage) = throttler::inspect(store, request_type);
+
+    let ceiling = match request_type {
+        RequestType::Read => &store.read_ceiling,
+        RequestType::Write => &store.write_ceiling,
+    };
+
+    ThrottleSnapshot { stalled_for_ms, ceiling: Some(ceiling.load(Ordering::Acquire)).filter(|&c| c > 0), window_usage }
+}
@@ -0,0 +1,175 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Centralised definitions of every endpoint made available by the API.
+//!
+//! Endpoints are joined onto a pre-parsed base URL segment-by-segment, which avoids formatting (and then re-parsing)
+//! an intermediate string for every request.
+
+use reqwest::Url;
+use url::PathSegmentsMut;
+
+#[derive(Clone, Copy)]
+pub(crate) enum Endpoint<'a> {
+    Health,
+    Metrics,
+
+    Alerts,
+
+    Conversations,
+    ConversationReplies(u64),
+
+    Threads,
+    Thread(u64),
+    ThreadReplies(u64),
+
+    MembersSelf,
+    Member(u64),
+    MemberByName(&'a str),
+    MemberByDiscord(u64),
+    MemberBans,
+    ProfilePosts,
+    ProfilePost(u64),
+
+    Resources,
+    ResourcesOwned,
+    ResourcesCollaborated,
+    Resource(u64),
+
+    Downloads(u64),
+    DownloadsByMember(u64, u64),
+    DownloadsByVersion(u64, u64),
+
+    Licenses(u64),
+    License(u64, u64),
+    LicenseByMember(u64, u64),
+
+    Purchases(u64),
+    Purchase(u64, u64),
+
+    Reviews(u64),
+    Review(u64, u64),
+    ReviewByMember(u64, u64),
+
+    Updates(u64),
+    LatestUpdate(u64),
+    Update(u64, u64),
+
+    Versions(u64),
+    LatestVersion(u64),
+    Version(u64, u64),
+    VersionDownload(u64, u64),
+
+    /// An endpoint not otherwise modelled by this crate, as a path relative to the base URL with an optional query
+    /// string (eg. `resources/1/licenses?page=2`).
+    Raw(&'a str),
+}
+
+/// A single path segment which can be pushed onto a URL.
+trait Segment {
+    fn push_onto(&self, path: &mut PathSegmentsMut<'_>);
+}
+
+impl Segment for &str {
+    fn push_onto(&self, path: &mut PathSegmentsMut<'_>) {
+        path.push(self);
+    }
+}
+
+impl Segment for u64 {
+    fn push_onto(&self, path: &mut PathSegmentsMut<'_>) {
+        path.push(itoa::Buffer::new().format(*self));
+    }
+}
+
+macro_rules! segments {
+    ($path:expr; $($segment:expr),+) => {{
+        $( Segment::push_onto(&$segment, $path); )+
+    }};
+}
+
+impl Endpoint<'_> {
+    /// Construct the full URL of this endpoint relative to a base URL.
+    pub(crate) fn url(&self, base: &Url) -> Url {
+        let mut url = base.clone();
+
+        {
+            let mut path = url.path_segments_mut().expect("base URL cannot be a base");
+            let path = path.pop_if_empty();
+
+            match self {
+                Endpoint::Health => segments!(path; "health"),
+                Endpoint::Metrics => segments!(path; "metrics"),
+
+                Endpoint::Alerts => segments!(path; "alerts"),
+
+                Endpoint::Conversations => segments!(path; "conversations"),
+                Endpoint::ConversationReplies(id) => segments!(path; "conversations", *id, "replies"),
+
+                Endpoint::Threads => segments!(path; "threads"),
+                Endpoint::Thread(id) => segments!(path; "threads", *id),
+                Endpoint::ThreadReplies(id) => segments!(path; "threads", *id, "replies"),
+
+                Endpoint::MembersSelf => segments!(path; "members", "self"),
+                Endpoint::Member(id) => segments!(path; "members", *id),
+                Endpoint::MemberByName(name) => segments!(path; "members", "usernames", *name),
+                Endpoint::MemberByDiscord(id) => segments!(path; "members", "discords", *id),
+                Endpoint::MemberBans => segments!(path; "members", "bans"),
+                Endpoint::ProfilePosts => segments!(path; "members", "profile-posts"),
+                Endpoint::ProfilePost(id) => segments!(path; "members", "profile-posts", *id),
+
+                Endpoint::Resources => segments!(path; "resources"),
+                Endpoint::ResourcesOwned => segments!(path; "resources", "owned"),
+                Endpoint::ResourcesCollaborated => segments!(path; "resources", "collaborated"),
+                Endpoint::Resource(id) => segments!(path; "resources", *id),
+
+                Endpoint::Downloads(id) => segments!(path; "resources", *id, "downloads"),
+                Endpoint::DownloadsByMember(id, member_id) => {
+                    segments!(path; "resources", *id, "downloads", "members", *member_id)
+                }
+                Endpoint::DownloadsByVersion(id, version_id) => {
+                    segments!(path; "resources", *id, "downloads", "versions", *version_id)
+                }
+
+                Endpoint::Licenses(id) => segments!(path; "resources", *id, "licenses"),
+                Endpoint::License(id, license_id) => segments!(path; "resources", *id, "licenses", *license_id),
+                Endpoint::LicenseByMember(id, member_id) => {
+                    segments!(path; "resources", *id, "licenses", "members", *member_id)
+                }
+
+                Endpoint::Purchases(id) => segments!(path; "resources", *id, "purchases"),
+                Endpoint::Purchase(id, purchase_id) => segments!(path; "resources", *id, "purchases", *purchase_id),
+
+                Endpoint::Reviews(id) => segments!(path; "resources", *id, "reviews"),
+                Endpoint::Review(id, review_id) => segments!(path; "resources", *id, "reviews", *review_id),
+                Endpoint::ReviewByMember(id, member_id) => {
+                    segments!(path; "resources", *id, "reviews", "members", *member_id)
+                }
+
+                Endpoint::Updates(id) => segments!(path; "resources", *id, "updates"),
+                Endpoint::LatestUpdate(id) => segments!(path; "resources", *id, "updates", "latest"),
+                Endpoint::Update(id, update_id) => segments!(path; "resources", *id, "updates", *update_id),
+
+                Endpoint::Versions(id) => segments!(path; "resources", *id, "versions"),
+                Endpoint::LatestVersion(id) => segments!(path; "resources", *id, "versions", "latest"),
+                Endpoint::Version(id, version_id) => segments!(path; "resources", *id, "versions", *version_id),
+                Endpoint::VersionDownload(id, version_id) => {
+                    segments!(path; "resources", *id, "versions", *version_id, "download")
+                }
+
+                Endpoint::Raw(raw) => {
+                    let raw = raw.split('?').next().unwrap_or_default();
+                    path.extend(raw.split('/').filter(|segment| !segment.is_empty()));
+                }
+            }
+        }
+
+        if let Endpoint::Raw(raw) = self {
+            if let Some((_, query)) = raw.split_once('?') {
+                url.set_query(Some(query));
+            }
+        }
+
+        url
+    }
+}
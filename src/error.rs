@@ -3,38 +3,297 @@
 
 //! Holds relevant error reporting types and conversions.
 
+use std::time::Duration;
+
+use reqwest::StatusCode;
 use serde::Deserialize;
+use thiserror::Error;
 
 pub type Result<V> = std::result::Result<V, APIError>;
 
-#[derive(Hash, Clone, Debug, PartialEq, Deserialize)]
-pub struct APIError {
+/// The error code returned by the API when the requested content doesn't exist.
+pub(crate) const NOT_FOUND_CODE: &str = "ContentNotFoundError";
+
+/// The error code returned by the API when the token isn't permitted to perform the request.
+pub(crate) const INSUFFICIENT_PRIVILEGES_CODE: &str = "InsufficientPrivilegesError";
+
+/// The error code returned by the API when a request's parameters (or body) are invalid.
+pub(crate) const INVALID_PARAMETER_CODE: &str = "InvalidParameterError";
+
+/// The error code we attach to transport-level failures (ie. the API couldn't be reached).
+pub(crate) const TRANSPORT_CODE: &str = "HttpClientError";
+
+/// The error code we attach to requests which didn't complete within their timeout.
+pub(crate) const TIMEOUT_CODE: &str = "TimeoutError";
+
+/// The error code we attach to responses with a server error (5xx) status.
+pub(crate) const SERVER_ERROR_CODE: &str = "ServerError";
+
+/// The error code we attach to responses indicating that the API is down for maintenance (503).
+pub(crate) const MAINTENANCE_CODE: &str = "MaintenanceError";
+
+/// The error code we attach to requests which were abandoned due to rate limiting.
+pub(crate) const RATE_LIMITED_CODE: &str = "RateLimitError";
+
+/// The error code we attach to successful responses whose body couldn't be decoded.
+pub(crate) const DECODE_CODE: &str = "DecodeError";
+
+/// The error code we attach to requests which couldn't be encoded locally (eg. invalid sort options), so were never
+/// sent.
+pub(crate) const ENCODE_CODE: &str = "EncodeError";
+
+/// The error code we attach to local I/O failures (eg. when persisting state to disk).
+pub(crate) const IO_CODE: &str = "IoError";
+
+/// A typed error code, parsed from either the code returned by the API or the one attached by this wrapper.
+///
+/// Codes which aren't known to this wrapper are kept as `Unknown`, so new codes introduced by the API can still be
+/// handled without an update.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::error::KnownErrorCode;
+/// assert_eq!(KnownErrorCode::ContentNotFound, KnownErrorCode::parse("ContentNotFoundError"));
+/// assert_eq!(KnownErrorCode::Unknown("NewError".to_string()), KnownErrorCode::parse("NewError"));
+/// assert_eq!("ContentNotFoundError", KnownErrorCode::ContentNotFound.as_str());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum KnownErrorCode {
+    ContentNotFound,
+    InsufficientPrivileges,
+    InvalidParameter,
+    Transport,
+    Timeout,
+    ServerError,
+    Maintenance,
+    RateLimited,
+    Decode,
+    Encode,
+    Io,
+    Unknown(String),
+}
+
+impl KnownErrorCode {
+    pub fn parse(code: &str) -> KnownErrorCode {
+        match code {
+            NOT_FOUND_CODE => KnownErrorCode::ContentNotFound,
+            INSUFFICIENT_PRIVILEGES_CODE => KnownErrorCode::InsufficientPrivileges,
+            INVALID_PARAMETER_CODE => KnownErrorCode::InvalidParameter,
+            TRANSPORT_CODE => KnownErrorCode::Transport,
+            TIMEOUT_CODE => KnownErrorCode::Timeout,
+            SERVER_ERROR_CODE => KnownErrorCode::ServerError,
+            MAINTENANCE_CODE => KnownErrorCode::Maintenance,
+            RATE_LIMITED_CODE => KnownErrorCode::RateLimited,
+            DECODE_CODE => KnownErrorCode::Decode,
+            ENCODE_CODE => KnownErrorCode::Encode,
+            IO_CODE => KnownErrorCode::Io,
+            code => KnownErrorCode::Unknown(code.to_string()),
+        }
+    }
+
+    /// Returns the raw error code.
+    pub fn as_str(&self) -> &str {
+        match self {
+            KnownErrorCode::ContentNotFound => NOT_FOUND_CODE,
+            KnownErrorCode::InsufficientPrivileges => INSUFFICIENT_PRIVILEGES_CODE,
+            KnownErrorCode::InvalidParameter => INVALID_PARAMETER_CODE,
+            KnownErrorCode::Transport => TRANSPORT_CODE,
+            KnownErrorCode::Timeout => TIMEOUT_CODE,
+            KnownErrorCode::ServerError => SERVER_ERROR_CODE,
+            KnownErrorCode::Maintenance => MAINTENANCE_CODE,
+            KnownErrorCode::RateLimited => RATE_LIMITED_CODE,
+            KnownErrorCode::Decode => DECODE_CODE,
+            KnownErrorCode::Encode => ENCODE_CODE,
+            KnownErrorCode::Io => IO_CODE,
+            KnownErrorCode::Unknown(code) => code,
+        }
+    }
+}
+
+impl std::fmt::Display for KnownErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The class of failure which occurred, so that callers can match on it rather than comparing error codes.
+///
+/// Every error still has a code (see `APIError::code`), which is either the one returned by the API or one attached by
+/// this wrapper for each class of local failure.
+#[derive(Debug, Error)]
+pub enum APIError {
+    /// The request couldn't be completed (eg. the API couldn't be reached or the connection was interrupted).
+    #[error("Unable to complete request: {0}")]
+    Http(reqwest::Error),
+    /// The request didn't complete within its timeout (or couldn't connect within the connect timeout), along with the
+    /// underlying client error if the client timed it out itself.
+    #[error("Request timed out")]
+    Timeout { source: Option<reqwest::Error> },
+    /// A response's body couldn't be deserialised, along with the path of the endpoint it was returned from (if known).
+    #[error("Unable to parse successful response: {source}")]
+    Deserialize { endpoint: Option<String>, source: Box<dyn std::error::Error + Send + Sync> },
+    /// The request was abandoned due to rate limiting, along with how long the API asked us to wait.
+    #[error("Rate limited by the API for another {}ms", retry_after.as_millis())]
+    RateLimited { retry_after: Duration },
+    /// A local I/O operation failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The API responded with a server error (5xx) status.
+    #[error("The API responded with status {status}")]
+    ServerError { status: StatusCode },
+    /// The API responded that it's temporarily unavailable for maintenance (503).
+    #[error("The API is temporarily unavailable for maintenance")]
+    Maintenance,
+    /// A request was rejected locally before being sent, or a local component failed (eg. an invalid builder option
+    /// or cassette mismatch), along with the code of its class of failure.
+    #[error("{message}")]
+    Local { code: &'static str, message: String },
+    /// An error returned by the API, with its code.
+    #[error("{message}")]
+    Api { code: String, message: String },
+}
+
+/// The error object within an unsuccessful response from the API.
+#[derive(Deserialize)]
+pub(crate) struct ErrorBody {
     code: String,
     message: String,
 }
 
+impl From<ErrorBody> for APIError {
+    fn from(value: ErrorBody) -> APIError {
+        APIError::Api { code: value.code, message: value.message }
+    }
+}
+
 impl APIError {
     pub fn from_raw(code: String, message: String) -> APIError {
-        Self { code, message }
+        APIError::Api { code, message }
+    }
+
+    /// Construct a local error with the code of its class of failure.
+    pub(crate) fn local(code: &'static str, message: String) -> APIError {
+        APIError::Local { code, message }
     }
 
-    pub fn code(&self) -> &String {
-        &self.code
+    /// Construct a deserialisation error from any underlying parse error.
+    pub(crate) fn deserialize<E>(source: E) -> APIError
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        APIError::Deserialize { endpoint: None, source: Box::new(source) }
     }
 
-    pub fn message(&self) -> &String {
-        &self.message
+    /// Attach the path of the endpoint a response was returned from, if this is a deserialisation error without one.
+    pub(crate) fn at_endpoint(self, path: &str) -> APIError {
+        match self {
+            APIError::Deserialize { endpoint: None, source } => {
+                APIError::Deserialize { endpoint: Some(path.to_string()), source }
+            }
+            error => error,
+        }
+    }
+
+    /// Returns the error's code, as returned by the API or attached by this wrapper for its class of failure.
+    pub fn code(&self) -> &str {
+        match self {
+            APIError::Http(_) => TRANSPORT_CODE,
+            APIError::Timeout { .. } => TIMEOUT_CODE,
+            APIError::Deserialize { .. } => DECODE_CODE,
+            APIError::RateLimited { .. } => RATE_LIMITED_CODE,
+            APIError::Io(_) => IO_CODE,
+            APIError::ServerError { .. } => SERVER_ERROR_CODE,
+            APIError::Maintenance => MAINTENANCE_CODE,
+            APIError::Local { code, .. } => code,
+            APIError::Api { code, .. } => code,
+        }
+    }
+
+    /// Returns the error's code as a typed enum.
+    pub fn known_code(&self) -> KnownErrorCode {
+        KnownErrorCode::parse(self.code())
+    }
+
+    /// Returns whether or not the requested content doesn't exist.
+    pub fn is_not_found(&self) -> bool {
+        self.code() == NOT_FOUND_CODE
+    }
+
+    /// Returns whether or not the token isn't permitted to perform the request.
+    pub fn is_permission_denied(&self) -> bool {
+        self.code() == INSUFFICIENT_PRIVILEGES_CODE
+    }
+
+    /// Returns whether or not the API rejected the request's parameters (or body) as invalid.
+    pub fn is_invalid_parameter(&self) -> bool {
+        self.code() == INVALID_PARAMETER_CODE
+    }
+
+    /// Returns a human-readable description of the error.
+    pub fn message(&self) -> String {
+        match self {
+            APIError::Local { message, .. } | APIError::Api { message, .. } => message.clone(),
+            error => error.to_string(),
+        }
+    }
+
+    /// Returns whether or not this error is likely to be transient, such that retrying the request may succeed.
+    ///
+    /// Failures to reach the API (including timeouts), server errors, and maintenance downtime are retryable. Errors
+    /// returned by the API for the request itself (eg. validation or permission errors) and malformed responses aren't.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self.code(), TRANSPORT_CODE | TIMEOUT_CODE | SERVER_ERROR_CODE | MAINTENANCE_CODE)
+    }
+
+    /// Returns whether or not the request didn't complete within its timeout.
+    pub fn is_timeout(&self) -> bool {
+        self.code() == TIMEOUT_CODE
+    }
+
+    /// Returns whether or not the API responded that it's unavailable due to maintenance.
+    pub fn is_maintenance(&self) -> bool {
+        self.code() == MAINTENANCE_CODE
+    }
+
+    /// Returns whether or not the request was abandoned due to rate limiting.
+    pub fn is_rate_limited(&self) -> bool {
+        self.code() == RATE_LIMITED_CODE
+    }
+
+    /// Returns whether or not this error will recur if the same request is retried.
+    pub fn is_permanent(&self) -> bool {
+        !self.is_retryable()
     }
 }
 
 impl From<reqwest::Error> for APIError {
     fn from(value: reqwest::Error) -> APIError {
-        APIError::from_raw("HttpClientError".to_string(), format!("Unable to parse successful response: {}", value))
+        if value.is_decode() {
+            let endpoint = value.url().map(|url| url.path().to_string());
+            APIError::Deserialize { endpoint, source: Box::new(value) }
+        } else if value.is_timeout() {
+            APIError::Timeout { source: Some(value) }
+        } else {
+            APIError::Http(value)
+        }
+    }
+}
+
+impl From<serde_json::Error> for APIError {
+    fn from(value: serde_json::Error) -> APIError {
+        APIError::deserialize(value)
+    }
+}
+
+#[cfg(feature = "simd-json")]
+impl From<simd_json::Error> for APIError {
+    fn from(value: simd_json::Error) -> APIError {
+        APIError::deserialize(value)
     }
 }
 
 impl From<serde_qs::Error> for APIError {
     fn from(value: serde_qs::Error) -> APIError {
-        APIError::from_raw("HttpClientError".to_string(), format!("Sort options parse error: {}", value))
-    } 
+        APIError::local(ENCODE_CODE, format!("Sort options parse error: {}", value))
+    }
 }
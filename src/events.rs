@@ -0,0 +1,282 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Typed events describing activity on an account, along with a bridge which produces them by polling.
+//!
+//! BuiltByBit doesn't currently offer outbound webhooks, so events are derived by polling the API and comparing
+//! against what was previously seen. Should webhooks become available, a receiver would produce the same `Event` type,
+//! allowing consumers to switch between the two without changes to their handling.
+
+use crate::data::alerts::{AlertData, AlertType, ContentType};
+use crate::data::conversations::ConversationData;
+use crate::data::resources::{PurchaseData, ResourceData, ReviewData};
+use crate::error::Result;
+use crate::pagination;
+use crate::sort::{Order, PurchaseSortField, ReviewSortField, SortOptions};
+use crate::APIWrapper;
+
+use std::collections::{HashMap, HashSet};
+
+/// A single piece of activity on an account.
+#[derive(Debug, Clone)]
+pub enum Event {
+    NewPurchase {
+        resource_id: u64,
+        purchase: PurchaseData,
+    },
+    NewReview {
+        resource_id: u64,
+        review: ReviewData,
+    },
+    /// A recent purchase was refunded or reversed. This is produced by a `RefundMonitor` rather than the polling
+    /// bridge.
+    PurchaseReversed {
+        resource_id: u64,
+        purchase: PurchaseData,
+    },
+    /// A new review was at or below the bridge's low rating threshold. This is emitted in addition to `NewReview`.
+    LowRating {
+        resource_id: u64,
+        review: ReviewData,
+        /// The resource's average rating once this review (and those before it) are included.
+        average: f64,
+    },
+    NewAlert(AlertData),
+    /// An unread conversation was started, or received a new message.
+    ConversationActivity(ConversationData),
+}
+
+/// Identifies an alert, as the API doesn't give alerts an ID of their own.
+pub(crate) type AlertKey = (u64, ContentType, u64, AlertType, u64);
+
+pub(crate) fn alert_key(alert: &AlertData) -> AlertKey {
+    (
+        *alert.caused_member_id(),
+        alert.content_type().clone(),
+        *alert.content_id(),
+        alert.alert_type().clone(),
+        *alert.alert_date(),
+    )
+}
+
+#[derive(Default)]
+struct ResourceState {
+    newest_purchase: Option<u64>,
+    newest_review: Option<u64>,
+    /// The number of reviews and sum of their ratings, tracked only when a low rating threshold is set.
+    ratings: Option<(u64, f64)>,
+}
+
+/// Produces events by polling the API for changes since the previous poll.
+///
+/// The first poll establishes a baseline and yields no events, so pre-existing activity isn't reported as new.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::events::{Event, PollingBridge};
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let mut bridge = PollingBridge::new(&wrapper);
+/// bridge.watch_resource(1);
+///
+/// loop {
+///     for event in bridge.poll().await? {
+///         if let Event::NewPurchase { purchase, .. } = event {
+///             println!("New purchase by {}.", purchase.purchaser_id());
+///         }
+///     }
+/// #   break;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct PollingBridge<'a> {
+    wrapper: &'a APIWrapper,
+    resources: HashMap<u64, ResourceState>,
+    alerts: HashSet<AlertKey>,
+    conversations: HashMap<u64, u64>,
+    low_rating_threshold: Option<u8>,
+    primed: bool,
+}
+
+impl<'a> PollingBridge<'a> {
+    pub fn new(wrapper: &'a APIWrapper) -> PollingBridge<'a> {
+        PollingBridge {
+            wrapper,
+            resources: HashMap::new(),
+            alerts: HashSet::new(),
+            conversations: HashMap::new(),
+            low_rating_threshold: None,
+            primed: false,
+        }
+    }
+
+    /// Watch a resource for new purchases and reviews.
+    ///
+    /// Activity on a newly watched resource is baselined on the next poll rather than reported.
+    pub fn watch_resource(&mut self, resource_id: u64) -> &mut Self {
+        self.resources.entry(resource_id).or_default();
+        self
+    }
+
+    /// Stop watching a resource.
+    pub fn unwatch_resource(&mut self, resource_id: u64) -> &mut Self {
+        self.resources.remove(&resource_id);
+        self
+    }
+
+    /// Emit a `LowRating` event (including the resource's new average rating) for each new review which is rated at or
+    /// below a threshold.
+    ///
+    /// Average ratings are computed from each resource's review statistics, which are fetched once when the threshold
+    /// is first applied to it.
+    pub fn low_rating_threshold(&mut self, threshold: u8) -> &mut Self {
+        self.low_rating_threshold = Some(threshold);
+        self
+    }
+
+    /// Poll for activity since the previous poll.
+    ///
+    /// Everything is fetched before any state is updated, so if an error occurs then no activity is lost; it's instead
+    /// reported by the next successful poll.
+    pub async fn poll(&mut self) -> Result<Vec<Event>> {
+        let wrapper = self.wrapper;
+        let mut fetched = Vec::with_capacity(self.resources.len());
+
+        for (&resource_id, state) in &self.resources {
+            let purchases = fetch_newer(
+                |page| async move {
+                    let sort = SortOptions::default()
+                        .sort_by(PurchaseSortField::PurchaseDate)
+                        .order_by(Order::Desc)
+                        .page(page);
+                    wrapper.resources().purchases().list(resource_id, Some(&sort)).await
+                },
+                state.newest_purchase,
+                |purchase: &PurchaseData| *purchase.purchase_id(),
+            )
+            .await?;
+
+            let reviews = fetch_newer(
+                |page| async move {
+                    let sort =
+                        SortOptions::default().sort_by(ReviewSortField::ReviewDate).order_by(Order::Desc).page(page);
+                    wrapper.resources().reviews().list(resource_id, Some(&sort)).await
+                },
+                state.newest_review,
+                |review: &ReviewData| *review.review_id(),
+            )
+            .await?;
+
+            // Fetched after the reviews, so the statistics will already include any reviews reported by this poll.
+            let resource = if self.low_rating_threshold.is_some() && state.ratings.is_none() {
+                Some(wrapper.resources().fetch(resource_id).await?)
+            } else {
+                None
+            };
+
+            fetched.push((resource_id, purchases, reviews, resource));
+        }
+
+        let alerts = wrapper.alerts().list_unread(None).await?;
+        let conversations = wrapper.conversations().list_unread(None).await?;
+
+        let mut events = Vec::new();
+
+        for (resource_id, purchases, reviews, resource) in fetched {
+            let state = self.resources.entry(resource_id).or_default();
+            let baseline = state.newest_purchase.is_none();
+
+            let previous_purchase = state.newest_purchase.unwrap_or(0);
+            let previous_review = state.newest_review.unwrap_or(0);
+
+            // Once baselined, the high-water marks are always set (even if zero) so that an empty resource's first
+            // purchase or review is still reported.
+            let newest_purchase = purchases.iter().map(|purchase| *purchase.purchase_id()).max().unwrap_or(0);
+            let newest_review = reviews.iter().map(|review| *review.review_id()).max().unwrap_or(0);
+
+            state.newest_purchase = Some(newest_purchase.max(previous_purchase));
+            state.newest_review = Some(newest_review.max(previous_review));
+
+            let counted = resource.is_some();
+
+            if let Some(resource) = resource {
+                state.ratings = Some(initial_ratings(&resource));
+            }
+
+            if baseline {
+                continue;
+            }
+
+            // Lists are newest-first, so reverse them to report activity in the order it occurred.
+            for purchase in purchases.into_iter().rev().filter(|purchase| *purchase.purchase_id() > previous_purchase) {
+                events.push(Event::NewPurchase { resource_id, purchase });
+            }
+
+            for review in reviews.into_iter().rev().filter(|review| *review.review_id() > previous_review) {
+                events.push(Event::NewReview { resource_id, review: review.clone() });
+
+                let (threshold, (count, sum)) = match (self.low_rating_threshold, state.ratings.as_mut()) {
+                    (Some(threshold), Some(ratings)) => (threshold, ratings),
+                    _ => continue,
+                };
+
+                if !counted {
+                    *count += 1;
+                    *sum += f64::from(*review.rating());
+                }
+
+                if *review.rating() <= threshold {
+                    let average = *sum / (*count).max(1) as f64;
+                    events.push(Event::LowRating { resource_id, review, average });
+                }
+            }
+        }
+
+        let alert_keys: HashSet<AlertKey> = alerts.iter().map(alert_key).collect();
+        let conversation_dates: HashMap<u64, u64> = conversations
+            .iter()
+            .map(|conversation| (*conversation.conversation_id(), *conversation.last_message_date()))
+            .collect();
+
+        if self.primed {
+            for alert in alerts.into_iter().rev().filter(|alert| !self.alerts.contains(&alert_key(alert))) {
+                events.push(Event::NewAlert(alert));
+            }
+
+            for conversation in conversations.into_iter().rev() {
+                let previous = self.conversations.get(conversation.conversation_id());
+
+                if previous.is_none_or(|&date| *conversation.last_message_date() > date) {
+                    events.push(Event::ConversationActivity(conversation));
+                }
+            }
+        }
+
+        // Only retain what's currently unread, as anything which has been read won't be listed again.
+        self.alerts = alert_keys;
+        self.conversations = conversation_dates;
+        self.primed = true;
+
+        Ok(events)
+    }
+}
+
+/// Returns the (count, sum) rating statistics of a resource.
+fn initial_ratings(resource: &ResourceData) -> (u64, f64) {
+    let count = *resource.review_count();
+    (count, resource.review_average() * count as f64)
+}
+
+/// Fetch the items of a newest-first list which are newer than a high-water mark, or only the first page if there's no
+/// mark yet (as we only need to establish one).
+async fn fetch_newer<T, F, Fut, I>(mut fetch: F, newest: Option<u64>, id: I) -> Result<Vec<T>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<T>>>,
+    I: Fn(&T) -> u64,
+{
+    match newest {
+        Some(newest) => pagination::collect_until(fetch, |batch| batch.iter().any(|item| id(item) <= newest)).await,
+        None => fetch(1).await,
+    }
+}
@@ -0,0 +1,399 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Writers for exporting sales data as CSV or JSON, with opt-in anonymisation of member identities.
+//!
+//! Anonymised exports replace member IDs and usernames with stable pseudonyms, so that a dataset can be shared with
+//! third parties whilst still allowing rows belonging to the same member to be correlated.
+//!
+//! `export_archive` writes everything the token can access into a directory, for backups and data-portability requests.
+
+use crate::data::conversations::{ConversationData, ReplyData};
+use crate::data::resources::{DownloadData, LicenseData, PurchaseData, ReviewData};
+use crate::error::Result;
+use crate::pagination;
+use crate::sort::SortOptions;
+use crate::APIWrapper;
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+/// Replaces member identities with stable pseudonyms derived from a secret salt.
+///
+/// The same salt always produces the same pseudonym for a given identity, so exports made at different times can be
+/// joined. Without the salt, pseudonyms can't be reversed by hashing every possible member ID, so the salt should be
+/// kept private and only reused for exports which are intended to be linkable.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::export::Anonymizer;
+/// let anonymizer = Anonymizer::new("keep-this-secret");
+///
+/// assert_eq!(anonymizer.member_id(87939), anonymizer.member_id(87939));
+/// assert_ne!(anonymizer.member_id(87939), Anonymizer::new("another-salt").member_id(87939));
+/// ```
+#[derive(Clone)]
+pub struct Anonymizer {
+    salt: Vec<u8>,
+}
+
+impl Anonymizer {
+    pub fn new(salt: impl AsRef<[u8]>) -> Anonymizer {
+        Anonymizer { salt: salt.as_ref().to_vec() }
+    }
+
+    /// Returns the pseudonym of a member ID.
+    pub fn member_id(&self, member_id: u64) -> String {
+        self.pseudonym(b"member_id", &member_id.to_le_bytes())
+    }
+
+    /// Returns the pseudonym of a username (compared case-insensitively).
+    pub fn username(&self, username: &str) -> String {
+        self.pseudonym(b"username", username.to_lowercase().as_bytes())
+    }
+
+    fn pseudonym(&self, domain: &[u8], value: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+
+        // Length-prefix the salt and domain so that no two distinct inputs can produce the same hashed bytes.
+        hasher.update((self.salt.len() as u64).to_le_bytes());
+        hasher.update(&self.salt);
+        hasher.update((domain.len() as u64).to_le_bytes());
+        hasher.update(domain);
+        hasher.update(value);
+
+        let digest = hasher.finalize();
+        let mut pseudonym = String::with_capacity(21);
+        pseudonym.push_str("anon-");
+
+        for byte in &digest[..8] {
+            pseudonym.push_str(&format!("{:02x}", byte));
+        }
+
+        pseudonym
+    }
+}
+
+/// A type which can be exported as a single row of a CSV or JSON export.
+pub trait Record {
+    /// The names of each column, in order.
+    fn columns() -> &'static [&'static str];
+
+    /// The values of each column, in order, with member identities replaced if an anonymiser is provided.
+    fn values(&self, anonymizer: Option<&Anonymizer>) -> Vec<Value>;
+}
+
+fn member(member_id: u64, anonymizer: Option<&Anonymizer>) -> Value {
+    match anonymizer {
+        Some(anonymizer) => Value::from(anonymizer.member_id(member_id)),
+        None => Value::from(member_id),
+    }
+}
+
+impl Record for PurchaseData {
+    fn columns() -> &'static [&'static str] {
+        &[
+            "purchase_id",
+            "purchaser_id",
+            "license_id",
+            "renewal",
+            "status",
+            "price",
+            "currency",
+            "purchase_date",
+            "validation_date",
+        ]
+    }
+
+    fn values(&self, anonymizer: Option<&Anonymizer>) -> Vec<Value> {
+        vec![
+            Value::from(*self.purchase_id()),
+            member(*self.purchaser_id(), anonymizer),
+            Value::from(*self.license_id()),
+            Value::from(*self.renewal()),
+            Value::from(self.status().as_str()),
+            Value::from(*self.price()),
+            Value::from(self.currency().as_str()),
+            Value::from(*self.purchase_date()),
+            Value::from(*self.validation_date()),
+        ]
+    }
+}
+
+impl Record for LicenseData {
+    fn columns() -> &'static [&'static str] {
+        &[
+            "license_id",
+            "purchaser_id",
+            "validated",
+            "active",
+            "permanent",
+            "start_date",
+            "end_date",
+            "previous_end_date",
+        ]
+    }
+
+    fn values(&self, anonymizer: Option<&Anonymizer>) -> Vec<Value> {
+        vec![
+            Value::from(*self.license_id()),
+            member(*self.purchaser_id(), anonymizer),
+            Value::from(*self.validated()),
+            Value::from(*self.active()),
+            Value::from(*self.permanent()),
+            Value::from(*self.start_date()),
+            Value::from(*self.end_date()),
+            Value::from(*self.previous_end_date()),
+        ]
+    }
+}
+
+impl Record for DownloadData {
+    fn columns() -> &'static [&'static str] {
+        &["download_id", "version_id", "downloader_id", "download_date"]
+    }
+
+    fn values(&self, anonymizer: Option<&Anonymizer>) -> Vec<Value> {
+        vec![
+            Value::from(*self.download_id()),
+            Value::from(*self.version_id()),
+            member(*self.downloader_id(), anonymizer),
+            Value::from(*self.download_date()),
+        ]
+    }
+}
+
+impl Record for ReviewData {
+    fn columns() -> &'static [&'static str] {
+        &["review_id", "reviewer_id", "review_date", "rating", "message", "response"]
+    }
+
+    fn values(&self, anonymizer: Option<&Anonymizer>) -> Vec<Value> {
+        vec![
+            Value::from(*self.review_id()),
+            member(*self.reviewer_id(), anonymizer),
+            Value::from(*self.review_date()),
+            Value::from(*self.rating()),
+            Value::from(self.message().as_str()),
+            Value::from(self.response().as_str()),
+        ]
+    }
+}
+
+/// Write records as CSV, including a header row.
+///
+/// Text which a spreadsheet would evaluate as a formula (ie. starting with `=`, `+`, `-`, `@`, a tab, or a carriage
+/// return) is prefixed with `'`, so member-supplied text can't inject formulas into the opened export.
+///
+/// # Note
+/// Free-text fields (eg. review messages) are written as-is and may still identify a member when anonymising.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::export::{self, Anonymizer};
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let purchases = wrapper.resources().purchases().list(1, None).await?;
+/// let file = std::fs::File::create("purchases.csv")?;
+///
+/// export::write_csv(file, &purchases, Some(&Anonymizer::new("keep-this-secret")))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_csv<W, R>(mut writer: W, records: &[R], anonymizer: Option<&Anonymizer>) -> Result<()>
+where
+    W: Write,
+    R: Record,
+{
+    let header: Vec<String> = R::columns().iter().map(|column| escape_csv(column)).collect();
+    writeln!(writer, "{}", header.join(","))?;
+
+    for record in records {
+        let row: Vec<String> = record.values(anonymizer).iter().map(csv_cell).collect();
+        writeln!(writer, "{}", row.join(","))?;
+    }
+
+    Ok(writer.flush()?)
+}
+
+/// Write records as a JSON array of objects keyed by column name.
+///
+/// # Note
+/// Free-text fields (eg. review messages) are written as-is and may still identify a member when anonymising.
+pub fn write_json<W, R>(writer: W, records: &[R], anonymizer: Option<&Anonymizer>) -> Result<()>
+where
+    W: Write,
+    R: Record,
+{
+    let objects: Vec<Value> = records
+        .iter()
+        .map(|record| {
+            let values = record.values(anonymizer);
+            Value::Object(R::columns().iter().map(|column| column.to_string()).zip(values).collect::<Map<_, _>>())
+        })
+        .collect();
+
+    serde_json::to_writer_pretty(writer, &objects).map_err(std::io::Error::from)?;
+    Ok(())
+}
+
+/// Counts of what was written by an archive export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ArchiveReport {
+    pub resources: u64,
+    pub purchases: u64,
+    pub licenses: u64,
+    pub reviews: u64,
+    pub conversations: u64,
+    pub alerts: u64,
+}
+
+#[derive(Serialize)]
+struct ConversationEntry<'a> {
+    conversation: &'a ConversationData,
+    replies: Vec<ReplyData>,
+}
+
+/// Export everything the token can access into a directory, creating it if it doesn't exist.
+///
+/// The archive is laid out as follows:
+/// - `profile.json` - the authenticated member.
+/// - `resources.json` - each owned resource.
+/// - `resources/{resource_id}/{purchases,licenses,reviews}.csv` - the sales data of each owned resource.
+/// - `conversations.json` - unread conversations along with their replies.
+/// - `alerts.json` - unread alerts.
+///
+/// # Note
+/// The API only exposes unread conversations and alerts, so read ones can't be included. The anonymiser (if any) is
+/// only applied to the sales data; the remaining files describe the account itself, so are written as-is.
+///
+/// # Example
+/// ```no_run
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let report = bbb_api_wrapper::export::export_archive(&wrapper, "backup", None).await?;
+/// println!("Exported {} purchases across {} resources.", report.purchases, report.resources);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn export_archive(
+    wrapper: &APIWrapper,
+    directory: impl AsRef<Path>,
+    anonymizer: Option<&Anonymizer>,
+) -> Result<ArchiveReport> {
+    let directory = directory.as_ref();
+    let mut report = ArchiveReport::default();
+
+    fs::create_dir_all(directory)?;
+    write_value(&directory.join("profile.json"), &wrapper.members().fetch_self().await?)?;
+
+    let owned = pagination::collect_all(|page| async move {
+        wrapper.resources().list_owned(Some(&SortOptions::default().page(page))).await
+    })
+    .await?;
+
+    let mut resources = Vec::with_capacity(owned.len());
+
+    for resource in &owned {
+        let resource_id = *resource.resource_id();
+        let resource_directory = directory.join("resources").join(resource_id.to_string());
+        fs::create_dir_all(&resource_directory)?;
+
+        let purchases = pagination::collect_all(|page| async move {
+            wrapper.resources().purchases().list(resource_id, Some(&SortOptions::default().page(page))).await
+        })
+        .await?;
+
+        let licenses = pagination::collect_all(|page| async move {
+            wrapper.resources().licenses().list(resource_id, Some(&SortOptions::default().page(page))).await
+        })
+        .await?;
+
+        let reviews = pagination::collect_all(|page| async move {
+            wrapper.resources().reviews().list(resource_id, Some(&SortOptions::default().page(page))).await
+        })
+        .await?;
+
+        write_file(&resource_directory.join("purchases.csv"), |file| write_csv(file, &purchases, anonymizer))?;
+        write_file(&resource_directory.join("licenses.csv"), |file| write_csv(file, &licenses, anonymizer))?;
+        write_file(&resource_directory.join("reviews.csv"), |file| write_csv(file, &reviews, anonymizer))?;
+
+        report.resources += 1;
+        report.purchases += purchases.len() as u64;
+        report.licenses += licenses.len() as u64;
+        report.reviews += reviews.len() as u64;
+
+        resources.push(wrapper.resources().fetch(resource_id).await?);
+    }
+
+    write_value(&directory.join("resources.json"), &resources)?;
+
+    let conversations = pagination::collect_all(|page| async move {
+        wrapper.conversations().list_unread(Some(&SortOptions::default().page(page))).await
+    })
+    .await?;
+
+    let mut entries = Vec::with_capacity(conversations.len());
+
+    for conversation in &conversations {
+        let conversation_id = *conversation.conversation_id();
+
+        let replies = pagination::collect_all(|page| async move {
+            wrapper.conversations().list_replies(conversation_id, Some(&SortOptions::default().page(page))).await
+        })
+        .await?;
+
+        entries.push(ConversationEntry { conversation, replies });
+    }
+
+    write_value(&directory.join("conversations.json"), &entries)?;
+    report.conversations = entries.len() as u64;
+
+    let alerts = pagination::collect_all(|page| async move {
+        wrapper.alerts().list_unread(Some(&SortOptions::default().page(page))).await
+    })
+    .await?;
+
+    write_value(&directory.join("alerts.json"), &alerts)?;
+    report.alerts = alerts.len() as u64;
+
+    Ok(report)
+}
+
+fn write_file<F>(path: &Path, write: F) -> Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> Result<()>,
+{
+    write(&mut BufWriter::new(File::create(path)?))
+}
+
+fn write_value<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    write_file(path, |file| {
+        serde_json::to_writer_pretty(&mut *file, value).map_err(std::io::Error::from)?;
+        Ok(file.flush()?)
+    })
+}
+
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => escape_csv(value),
+        value => value.to_string(),
+    }
+}
+
+/// Neutralise a CSV field which a spreadsheet would evaluate as a formula, then quote it if it contains a delimiter,
+/// quote, or line break.
+fn escape_csv(value: &str) -> String {
+    let value =
+        if value.starts_with(['=', '+', '-', '@', '\t', '\r']) { format!("'{}", value) } else { value.to_string() };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
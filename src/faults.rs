@@ -0,0 +1,139 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Feature-gated injection of artificial latency and failures into requests, for testing resilience paths.
+//!
+//! Faults are drawn from a seeded pseudo-random sequence so that a given configuration produces the same series of
+//! outcomes on every run.
+
+use crate::error::{APIError, Result};
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+/// A configuration describing which faults to inject into requests, and how often.
+#[derive(Debug, Clone)]
+pub struct FaultInjection {
+    /// A fixed delay added before every request.
+    pub latency: Duration,
+    /// An upper bound on an additional random delay added before every request.
+    pub jitter: Duration,
+    /// The probability (between 0 and 1) of a request timing out.
+    pub timeout_rate: f64,
+    /// How long a timed out request stalls for before failing.
+    pub timeout_after: Duration,
+    /// The probability (between 0 and 1) of a request failing with a server error.
+    pub failure_rate: f64,
+    /// The seed of the pseudo-random sequence from which faults are drawn.
+    pub seed: u64,
+}
+
+impl Default for FaultInjection {
+    fn default() -> Self {
+        FaultInjection {
+            latency: Duration::ZERO,
+            jitter: Duration::ZERO,
+            timeout_rate: 0.0,
+            timeout_after: Duration::from_secs(30),
+            failure_rate: 0.0,
+            seed: 0x2545_F491_4F6C_DD1D,
+        }
+    }
+}
+
+impl FaultInjection {
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn timeout_rate(mut self, timeout_rate: f64, timeout_after: Duration) -> Self {
+        self.timeout_rate = timeout_rate;
+        self.timeout_after = timeout_after;
+        self
+    }
+
+    pub fn failure_rate(mut self, failure_rate: f64) -> Self {
+        self.failure_rate = failure_rate;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// The active fault configuration along with the state of its pseudo-random sequence.
+pub(crate) struct FaultInjector {
+    config: FaultInjection,
+    state: u64,
+}
+
+impl FaultInjector {
+    pub(crate) fn new(config: FaultInjection) -> FaultInjector {
+        // A xorshift generator never leaves a zero state, so we avoid seeding it with one.
+        let state = if config.seed == 0 { 1 } else { config.seed };
+        FaultInjector { config, state }
+    }
+
+    /// Returns the next value of the pseudo-random sequence within [0, 1).
+    fn next(&mut self) -> f64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        (self.state >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+enum Fault {
+    Timeout(Duration),
+    Failure,
+}
+
+/// Apply any configured faults ahead of a request being sent.
+pub(crate) async fn inject(faults: &Mutex<Option<FaultInjector>>) -> Result<()> {
+    // Draw everything up-front so the lock isn't held across an await.
+    let (delay, fault) = {
+        let mut faults = faults.lock().unwrap();
+
+        let injector = match faults.as_mut() {
+            Some(injector) => injector,
+            None => return Ok(()),
+        };
+
+        let delay = injector.config.latency + injector.config.jitter.mul_f64(injector.next());
+        let roll = injector.next();
+
+        let fault = if roll < injector.config.timeout_rate {
+            Some(Fault::Timeout(injector.config.timeout_after))
+        } else if roll < injector.config.timeout_rate + injector.config.failure_rate {
+            Some(Fault::Failure)
+        } else {
+            None
+        };
+
+        (delay, fault)
+    };
+
+    if !delay.is_zero() {
+        crate::runtime::sleep(delay).await;
+    }
+
+    match fault {
+        Some(Fault::Timeout(after)) => {
+            crate::runtime::sleep(after).await;
+            Err(APIError::Timeout { source: None })
+        }
+        Some(Fault::Failure) => Err(APIError::ServerError { status: StatusCode::INTERNAL_SERVER_ERROR }),
+        None => Ok(()),
+    }
+}
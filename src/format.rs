@@ -0,0 +1,99 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Consistent formatting of values for presentation, such as within Discord embeds or CSV exports.
+
+/// The symbol, whether it's placed as a prefix, and the number of decimal places used for a currency.
+fn currency_format(currency: &str) -> Option<(&'static str, bool, usize)> {
+    let format = match currency.to_ascii_uppercase().as_str() {
+        "USD" => ("$", true, 2),
+        "EUR" => ("€", true, 2),
+        "GBP" => ("£", true, 2),
+        "AUD" => ("A$", true, 2),
+        "CAD" => ("CA$", true, 2),
+        "NZD" => ("NZ$", true, 2),
+        "JPY" => ("¥", true, 0),
+        "INR" => ("₹", true, 2),
+        "BRL" => ("R$", true, 2),
+        "CHF" => ("CHF ", true, 2),
+        "SEK" => (" kr", false, 2),
+        "NOK" => (" kr", false, 2),
+        "DKK" => (" kr", false, 2),
+        "PLN" => (" zł", false, 2),
+        _ => return None,
+    };
+
+    Some(format)
+}
+
+/// Format a price with its currency's symbol, decimal places, and thousands separators.
+///
+/// Unrecognised currencies are formatted to two decimal places and suffixed with their code.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::format::format_price;
+/// assert_eq!("$1,234.50", format_price(1234.5, "USD"));
+/// assert_eq!("-£0.99", format_price(-0.99, "gbp"));
+/// assert_eq!("¥12,000", format_price(11999.6, "JPY"));
+/// assert_eq!("1,000,000.00 kr", format_price(1e6, "SEK"));
+/// assert_eq!("5.00 XYZ", format_price(5.0, "XYZ"));
+/// ```
+pub fn format_price(amount: f64, currency: &str) -> String {
+    let format = currency_format(currency);
+    let decimals = format.map_or(2, |(_, _, decimals)| decimals);
+
+    // Avoid a stray sign on amounts which round to zero (eg. "-$0.00").
+    let negative = amount < 0.0 && format!("{:.*}", decimals, -amount).bytes().any(|byte| matches!(byte, b'1'..=b'9'));
+    let sign = if negative { "-" } else { "" };
+    let number = group_thousands(amount.abs(), decimals);
+
+    match format {
+        Some((symbol, true, _)) => format!("{}{}{}", sign, symbol, number),
+        Some((symbol, false, _)) => format!("{}{}{}", sign, number, symbol),
+        None => format!("{}{} {}", sign, number, currency),
+    }
+}
+
+/// Format a price as with `format_price`, followed by its currency code if that isn't already included (eg.
+/// "$4.99 USD").
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::format::format_price_with_code;
+/// assert_eq!("$9.99 USD", format_price_with_code(9.99, "usd"));
+/// assert_eq!("5.00 XYZ", format_price_with_code(5.0, "XYZ"));
+/// ```
+pub fn format_price_with_code(amount: f64, currency: &str) -> String {
+    match currency_format(currency) {
+        Some(_) => format!("{} {}", format_price(amount, currency), currency.to_ascii_uppercase()),
+        None => format_price(amount, currency),
+    }
+}
+
+/// Format a non-negative number to a fixed number of decimal places, separating groups of thousands with commas.
+fn group_thousands(amount: f64, decimals: usize) -> String {
+    let fixed = format!("{:.*}", decimals, amount);
+
+    let (integer, fraction) = match fixed.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (fixed.as_str(), None),
+    };
+
+    let mut grouped = String::with_capacity(fixed.len() + integer.len() / 3);
+
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+
+        grouped.push(digit);
+    }
+
+    if let Some(fraction) = fraction {
+        grouped.push('.');
+        grouped.push_str(fraction);
+    }
+
+    grouped
+}
@@ -0,0 +1,203 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Tracking of revenue against per-period targets, fed by the purchases reported by a polling bridge.
+
+use crate::data::resources::PurchaseData;
+use crate::events::Event;
+use crate::timestamps::{civil_from_days, days_from_civil};
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// The calendar period (in UTC) over which revenue is accumulated before resetting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GoalPeriod {
+    Daily,
+    /// Weeks start on Monday.
+    Weekly,
+    Monthly,
+}
+
+impl GoalPeriod {
+    /// Returns the UNIX timestamp (in seconds) at which the period containing a timestamp starts.
+    ///
+    /// # Example
+    /// ```
+    /// # use bbb_api_wrapper::goals::GoalPeriod;
+    /// // Tuesday 14 November 2023, 22:13 UTC.
+    /// assert_eq!(1_699_920_000, GoalPeriod::Daily.start_of(1_700_000_000));
+    /// assert_eq!(1_699_833_600, GoalPeriod::Weekly.start_of(1_700_000_000));
+    /// assert_eq!(1_698_796_800, GoalPeriod::Monthly.start_of(1_700_000_000));
+    /// ```
+    pub fn start_of(&self, timestamp: u64) -> u64 {
+        let days = timestamp / SECONDS_PER_DAY;
+
+        let start = match self {
+            GoalPeriod::Daily => days,
+            // The UNIX epoch was a Thursday.
+            GoalPeriod::Weekly => days.saturating_sub((days + 3) % 7),
+            GoalPeriod::Monthly => {
+                let (year, month, _) = civil_from_days(days);
+                days_from_civil(year, month, 1)
+            }
+        };
+
+        start * SECONDS_PER_DAY
+    }
+}
+
+/// Progress made towards a revenue goal.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum GoalEvent {
+    /// Revenue for the period passed one of the goal's milestones (a fraction of the target, eg. 0.5).
+    Progress { period_start: u64, milestone: f64, revenue: f64, target: f64 },
+    /// Revenue for the period reached the target.
+    Reached { period_start: u64, revenue: f64, target: f64 },
+}
+
+/// Accumulates revenue from purchases within each period, reporting when milestones and the target are reached.
+///
+/// Only purchases made in the goal's currency are counted, as no conversion between currencies is performed.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::events::PollingBridge;
+/// # use bbb_api_wrapper::goals::{GoalEvent, GoalPeriod, RevenueGoal};
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let mut bridge = PollingBridge::new(&wrapper);
+/// bridge.watch_resource(1);
+///
+/// let mut goal = RevenueGoal::new(GoalPeriod::Monthly, 500.0, "USD").milestones(&[0.25, 0.5, 0.75]);
+///
+/// loop {
+///     for event in bridge.poll().await? {
+///         for progress in goal.consume(&event) {
+///             if let GoalEvent::Reached { revenue, .. } = progress {
+///                 println!("Monthly goal reached with ${:.2}!", revenue);
+///             }
+///         }
+///     }
+/// #   break;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RevenueGoal {
+    period: GoalPeriod,
+    target: f64,
+    currency: String,
+    milestones: Vec<f64>,
+    resource_ids: Option<HashSet<u64>>,
+    period_start: Option<u64>,
+    revenue: f64,
+    passed: usize,
+    reached: bool,
+}
+
+impl RevenueGoal {
+    /// Construct a goal of reaching a target revenue (in a currency, eg. "USD") within each period.
+    pub fn new(period: GoalPeriod, target: f64, currency: &str) -> RevenueGoal {
+        RevenueGoal {
+            period,
+            target,
+            currency: currency.to_string(),
+            milestones: Vec::new(),
+            resource_ids: None,
+            period_start: None,
+            revenue: 0.0,
+            passed: 0,
+            reached: false,
+        }
+    }
+
+    /// Report progress as revenue passes each of the provided fractions of the target (eg. 0.5 for half way).
+    pub fn milestones(mut self, fractions: &[f64]) -> Self {
+        self.milestones = fractions.iter().copied().filter(|fraction| *fraction > 0.0 && *fraction < 1.0).collect();
+        self.milestones.sort_by(f64::total_cmp);
+        self.milestones.dedup();
+        self
+    }
+
+    /// Only count purchases of the provided resources when consuming events.
+    pub fn resources(mut self, resource_ids: &[u64]) -> Self {
+        self.resource_ids = Some(resource_ids.iter().copied().collect());
+        self
+    }
+
+    /// Returns the revenue accumulated within the current period.
+    pub fn revenue(&self) -> f64 {
+        self.revenue
+    }
+
+    /// Returns the fraction of the target reached within the current period.
+    pub fn progress(&self) -> f64 {
+        if self.target > 0.0 {
+            self.revenue / self.target
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns the UNIX timestamp (in seconds) at which the current period started, if any purchases have been
+    /// recorded.
+    pub fn period_start(&self) -> Option<u64> {
+        self.period_start
+    }
+
+    /// Record the purchase within a `NewPurchase` event, ignoring any other events.
+    pub fn consume(&mut self, event: &Event) -> Vec<GoalEvent> {
+        match event {
+            Event::NewPurchase { resource_id, purchase }
+                if self.resource_ids.as_ref().is_none_or(|ids| ids.contains(resource_id)) =>
+            {
+                self.record(purchase)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Record a purchase, returning any milestones or the target which it caused to be reached.
+    ///
+    /// A purchase from a later period than the current one starts that period afresh, and a purchase from an earlier
+    /// period is ignored.
+    pub fn record(&mut self, purchase: &PurchaseData) -> Vec<GoalEvent> {
+        if !purchase.currency().eq_ignore_ascii_case(&self.currency) {
+            return Vec::new();
+        }
+
+        let period_start = self.period.start_of(*purchase.purchase_date());
+
+        match self.period_start {
+            Some(current) if period_start < current => return Vec::new(),
+            Some(current) if period_start == current => (),
+            _ => {
+                self.period_start = Some(period_start);
+                self.revenue = 0.0;
+                self.passed = 0;
+                self.reached = false;
+            }
+        }
+
+        self.revenue += purchase.price();
+
+        let mut events = Vec::new();
+
+        while self.passed < self.milestones.len() && self.progress() >= self.milestones[self.passed] {
+            let milestone = self.milestones[self.passed];
+            events.push(GoalEvent::Progress { period_start, milestone, revenue: self.revenue, target: self.target });
+            self.passed += 1;
+        }
+
+        if !self.reached && self.progress() >= 1.0 {
+            events.push(GoalEvent::Reached { period_start, revenue: self.revenue, target: self.target });
+            self.reached = true;
+        }
+
+        events
+    }
+}
@@ -1,21 +1,184 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
-use crate::data::alerts::{AlertData, AlertReadBody};
-use crate::sort::SortOptions;
+use crate::data::alerts::{AlertData, AlertReadBody, ContentType, ResolvedContent};
+use crate::endpoints::Endpoint;
 use crate::error::Result;
+use crate::events::{self, AlertKey};
+use crate::pagination;
+use crate::sort::{AlertFilter, SortOptions};
+use crate::stream::{poll_on_interval, ItemStream};
 use crate::APIWrapper;
 
+use std::collections::{HashSet, VecDeque};
+use std::time::Duration;
+
+use futures_util::stream::{self, Stream};
+
 pub struct AlertsHelper<'a> {
     pub(crate) wrapper: &'a APIWrapper,
 }
 
 impl<'a> AlertsHelper<'a> {
     pub async fn list_unread(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<AlertData>> {
-        self.wrapper.get(&format!("{}/alerts", crate::BASE_URL), sort).await
+        self.wrapper.get(Endpoint::Alerts, sort).await
+    }
+
+    /// List every unread alert by walking pages until an empty one is returned.
+    pub async fn list_all_unread(&self) -> Result<Vec<AlertData>> {
+        pagination::collect_all(|page| async move { self.list_unread(Some(&SortOptions::default().page(page))).await })
+            .await
+    }
+
+    /// List the unread alerts which match a filter (eg. only those on resource reviews).
+    pub async fn list_unread_filtered(
+        &self,
+        filter: &AlertFilter,
+        sort: Option<&SortOptions<'_>>,
+    ) -> Result<Vec<AlertData>> {
+        let sort = sort.cloned().unwrap_or_default().filter(filter.into());
+        self.wrapper.get(Endpoint::Alerts, Some(&sort)).await
+    }
+
+    /// List every unread alert which matches a filter by walking pages until an empty one is returned.
+    pub async fn list_all_unread_filtered(&self, filter: &AlertFilter) -> Result<Vec<AlertData>> {
+        pagination::collect_all(|page| async move {
+            self.list_unread_filtered(filter, Some(&SortOptions::default().page(page))).await
+        })
+        .await
+    }
+
+    /// Stream the unread alerts, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_unread(&self, sort: SortOptions<'a>) -> ItemStream<'a, AlertData> {
+        ItemStream::new(self.wrapper, Endpoint::Alerts, sort)
+    }
+
+    /// Watch for unread alerts by polling on an interval, yielding each alert once (oldest first).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// let mut alerts = wrapper.alerts().watch(Duration::from_secs(60)).mark_read(true);
+    ///
+    /// loop {
+    ///     println!("{}", alerts.next().await?);
+    /// }
+    /// # }
+    /// ```
+    pub fn watch(&self, interval: Duration) -> AlertWatch<'a> {
+        AlertWatch {
+            wrapper: self.wrapper,
+            interval,
+            filter: AlertFilter::default(),
+            mark_read: false,
+            pending: VecDeque::new(),
+            seen: HashSet::new(),
+            polled: false,
+        }
     }
 
     pub async fn mark_as_read(&self) -> Result<()> {
-        self.wrapper.patch(&format!("{}/alerts", crate::BASE_URL), &AlertReadBody { read: true }).await
+        self.wrapper.patch(Endpoint::Alerts, &AlertReadBody { read: true }).await
+    }
+
+    /// Fetch the content an alert refers to based on its content type and ID.
+    ///
+    /// # Note
+    /// The API has no endpoint for fetching a single conversation, so conversation content is resolved by searching
+    /// the first page of unread conversations. Content types which can't be resolved (including ones where the
+    /// content ID doesn't identify a fetchable object, such as resource updates) are returned as `Unresolved`.
+    pub async fn resolve(&self, alert: &AlertData) -> Result<ResolvedContent> {
+        let content_id = *alert.content_id();
+
+        let resolved = match alert.content_type() {
+            ContentType::Resource => ResolvedContent::Resource(self.wrapper.resources().fetch(content_id).await?),
+            ContentType::Thread => ResolvedContent::Thread(self.wrapper.threads().fetch_thread(content_id).await?),
+            ContentType::ProfilePost => {
+                ResolvedContent::ProfilePost(self.wrapper.members().fetch_profile_post(content_id).await?)
+            }
+            ContentType::Member => ResolvedContent::Member(self.wrapper.members().fetch_by_id(content_id).await?),
+            ContentType::Conversation => {
+                let conversations = self.wrapper.conversations().list_unread(None).await?;
+
+                match conversations.into_iter().find(|c| *c.conversation_id() == content_id) {
+                    Some(conversation) => ResolvedContent::Conversation(conversation),
+                    None => ResolvedContent::Unresolved { content_type: alert.content_type().clone(), content_id },
+                }
+            }
+            _ => ResolvedContent::Unresolved { content_type: alert.content_type().clone(), content_id },
+        };
+
+        Ok(resolved)
+    }
+}
+
+/// A watch of the unread alerts, constructed via `AlertsHelper::watch`.
+///
+/// The first poll yields every alert which is already unread. Each later poll yields only those alerts which weren't
+/// listed by the poll before it, so an alert is yielded once however long it stays unread. Polls are sent with
+/// background priority, so they yield to other requests whilst rate limited.
+///
+/// A poll which fails yields its error, and polling resumes after the interval if the watch is awaited again.
+pub struct AlertWatch<'a> {
+    wrapper: &'a APIWrapper,
+    interval: Duration,
+    filter: AlertFilter,
+    mark_read: bool,
+    pending: VecDeque<AlertData>,
+    seen: HashSet<AlertKey>,
+    polled: bool,
+}
+
+impl<'a> AlertWatch<'a> {
+    /// Only watch for alerts which match a filter.
+    pub fn filter(mut self, filter: AlertFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Set whether or not to mark alerts as read once listed (disabled by default).
+    ///
+    /// # Note
+    /// The API only supports marking every alert as read, so this includes any which don't match the filter, and any
+    /// which arrive between a poll listing and marking them.
+    pub fn mark_read(mut self, mark_read: bool) -> Self {
+        self.mark_read = mark_read;
+        self
+    }
+
+    /// Wait for the next unread alert.
+    pub async fn next(&mut self) -> Result<AlertData> {
+        loop {
+            if let Some(alert) = self.pending.pop_front() {
+                return Ok(alert);
+            }
+
+            let first = !std::mem::replace(&mut self.polled, true);
+            poll_on_interval(first, self.interval, self.poll()).await?;
+        }
+    }
+
+    /// Convert into a `futures` stream, for use with stream combinators.
+    pub fn into_stream(self) -> impl Stream<Item = Result<AlertData>> + 'a {
+        stream::unfold(self, |mut watch| async move { Some((watch.next().await, watch)) })
+    }
+
+    async fn poll(&mut self) -> Result<()> {
+        let alerts = self.wrapper.alerts().list_all_unread_filtered(&self.filter).await?;
+
+        let mark_read = self.mark_read && !alerts.is_empty();
+        let keys: HashSet<AlertKey> = alerts.iter().map(events::alert_key).collect();
+        let unseen = alerts.into_iter().rev().filter(|alert| !self.seen.contains(&events::alert_key(alert)));
+        self.pending.extend(unseen);
+
+        // Only retain what's currently unread, as anything which has been read won't be listed again.
+        self.seen = keys;
+
+        if mark_read {
+            self.wrapper.alerts().mark_as_read().await?;
+        }
+
+        Ok(())
     }
 }
@@ -1,31 +1,103 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
+use crate::data::conversations::{ConversationData, ConversationReplyBody, ConversationStartBody, ReplyData};
+use crate::endpoints::Endpoint;
 use crate::error::Result;
-use crate::data::conversations::{ConversationData, ReplyData, ConversationStartBody, ConversationReplyBody};
-use crate::sort::SortOptions;
+use crate::pagination;
+use crate::sort::{ConversationReplySortField, Order, SortOptions};
+use crate::stream::{IdWatch, ItemStream};
+use crate::transcript::Transcript;
 use crate::APIWrapper;
 
+use std::time::Duration;
+
+/// Helping functions for the conversations of the authenticated member.
+///
+/// # Note
+/// The API has no endpoints for leaving a conversation or for closing and reopening one, so these can only be done
+/// via the website. Automated systems which archive resolved conversations need to track them as resolved locally
+/// (eg. by ID) and can notify participants via `reply`.
 pub struct ConversationsHelper<'a> {
     pub(crate) wrapper: &'a APIWrapper,
 }
 
 impl<'a> ConversationsHelper<'a> {
     pub async fn list_unread(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<ConversationData>> {
-        self.wrapper.get(&format!("{}/conversations", crate::BASE_URL), sort).await
+        self.wrapper.get(Endpoint::Conversations, sort).await
+    }
+
+    /// List every unread conversation by walking pages until an empty one is returned.
+    pub async fn list_all_unread(&self) -> Result<Vec<ConversationData>> {
+        pagination::collect_all(|page| async move { self.list_unread(Some(&SortOptions::default().page(page))).await })
+            .await
+    }
+
+    /// Stream the unread conversations, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_unread(&self, sort: SortOptions<'a>) -> ItemStream<'a, ConversationData> {
+        ItemStream::new(self.wrapper, Endpoint::Conversations, sort)
     }
 
     pub async fn list_replies(&self, conversation_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<ReplyData>> {
-        self.wrapper.get(&format!("{}/conversations/{}/replies", crate::BASE_URL, conversation_id), sort).await
+        self.wrapper.get(Endpoint::ConversationReplies(conversation_id), sort).await
+    }
+
+    /// List every reply to a conversation by walking pages until an empty one is returned.
+    pub async fn list_all_replies(&self, conversation_id: u64) -> Result<Vec<ReplyData>> {
+        pagination::collect_all(|page| async move {
+            self.list_replies(conversation_id, Some(&SortOptions::default().page(page))).await
+        })
+        .await
+    }
+
+    /// Stream the replies to a conversation, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_replies(&self, conversation_id: u64, sort: SortOptions<'a>) -> ItemStream<'a, ReplyData> {
+        ItemStream::new(self.wrapper, Endpoint::ConversationReplies(conversation_id), sort)
+    }
+
+    /// Watch a conversation for new replies by polling on an interval, yielding each reply once (oldest first).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// let mut replies = wrapper.conversations().watch_replies(1, Duration::from_secs(30));
+    ///
+    /// loop {
+    ///     let reply = replies.next().await?;
+    ///     println!("{}: {}", reply.author_id(), reply.message());
+    /// }
+    /// # }
+    /// ```
+    pub fn watch_replies(&self, conversation_id: u64, interval: Duration) -> IdWatch<'a, ReplyData> {
+        let wrapper = self.wrapper;
+
+        IdWatch::new(
+            interval,
+            |reply: &ReplyData| *reply.message_id(),
+            move |page| async move {
+                let sort = SortOptions::default().sort_by(ConversationReplySortField::MessageId).order_by(Order::Desc);
+                wrapper.conversations().list_replies(conversation_id, Some(&sort.page(page))).await
+            },
+        )
+    }
+
+    /// Fetch every reply to a conversation and arrange them into a transcript.
+    pub async fn transcript(&self, conversation: &ConversationData) -> Result<Transcript> {
+        let conversation_id = *conversation.conversation_id();
+
+        let replies = self.list_all_replies(conversation_id).await?;
+
+        Ok(Transcript::new(conversation, &replies))
     }
 
     pub async fn start(&self, title: &str, message: &str, recipient_ids: &[u64]) -> Result<u64> {
         let data = ConversationStartBody { title, message, recipient_ids };
-        self.wrapper.post(&format!("{}/conversations", crate::BASE_URL), &data).await
+        self.wrapper.post(Endpoint::Conversations, &data).await
     }
 
     pub async fn reply(&self, conversation_id: u64, message: &str) -> Result<u64> {
         let data = ConversationReplyBody { message };
-        self.wrapper.post(&format!("{}/conversations/{}/replies", crate::BASE_URL, conversation_id), &data).await
+        self.wrapper.post(Endpoint::ConversationReplies(conversation_id), &data).await
     }
 }
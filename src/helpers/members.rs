@@ -1,10 +1,15 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
+use crate::data::members::{
+    BanData, MemberData, ModifySelfBody, ProfilePostCreateBody, ProfilePostData, ProfilePostEditBody,
+};
+use crate::endpoints::Endpoint;
 use crate::error::Result;
-use crate::data::members::{MemberData, ProfilePostData, ProfilePostEditBody, ModifySelfBody, BanData};
-use crate::APIWrapper;
+use crate::pagination;
 use crate::sort::SortOptions;
+use crate::stream::ItemStream;
+use crate::APIWrapper;
 
 pub struct MembersHelper<'a> {
     pub(crate) wrapper: &'a APIWrapper,
@@ -12,43 +17,144 @@ pub struct MembersHelper<'a> {
 
 impl<'a> MembersHelper<'a> {
     pub async fn fetch_self(&self) -> Result<MemberData> {
-        self.wrapper.get(&format!("{}/members/self", crate::BASE_URL), None).await
+        self.wrapper.get(Endpoint::MembersSelf, None).await
     }
 
-    pub async fn modify_self(&self, fields: &ModifySelfBody<'_>) -> Result<MemberData> {
-        self.wrapper.patch(&format!("{}/members/self", crate::BASE_URL), fields).await
+    /// Modify the authenticated member's custom title, about me, and/or signature.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bbb_api_wrapper::data::members::ModifySelfBody;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// let fields = ModifySelfBody { custom_title: Some("Plugin Developer"), ..Default::default() };
+    /// wrapper.members().modify_self(&fields).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn modify_self(&self, fields: &ModifySelfBody<'_>) -> Result<()> {
+        self.wrapper.patch(Endpoint::MembersSelf, fields).await
     }
 
     pub async fn fetch_by_id(&self, member_id: u64) -> Result<MemberData> {
-        self.wrapper.get(&format!("{}/members/{}", crate::BASE_URL, member_id), None).await
+        let member = self.wrapper.get(Endpoint::Member(member_id), None).await?;
+        self.remember(&member);
+        Ok(member)
     }
 
     pub async fn fetch_by_name(&self, member_name: &str) -> Result<MemberData> {
-        self.wrapper.get(&format!("{}/members/usernames/{}", crate::BASE_URL, member_name), None).await
+        let member = self.wrapper.get(Endpoint::MemberByName(member_name), None).await?;
+        self.remember(&member);
+        Ok(member)
+    }
+
+    /// Resolve a username to its member's ID, answering from memory if the username cache is enabled (see
+    /// `APIWrapperBuilder::username_cache_ttl`) and the username was resolved recently.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// // Only the first lookup is sent to the API whilst the cache is enabled.
+    /// for _ in 0..3 {
+    ///     let member_id = wrapper.members().resolve_id("Harry").await?;
+    ///     println!("{}", member_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_id(&self, member_name: &str) -> Result<u64> {
+        if let Some(member_id) = self.wrapper.usernames.as_ref().and_then(|cache| cache.lookup(member_name)) {
+            return Ok(member_id);
+        }
+
+        Ok(*self.fetch_by_name(member_name).await?.member_id())
+    }
+
+    /// Forget the member ID which a username resolves to, if cached (eg. after learning the member changed it).
+    pub fn invalidate_username(&self, member_name: &str) {
+        if let Some(cache) = &self.wrapper.usernames {
+            cache.invalidate(member_name);
+        }
+    }
+
+    /// Forget every cached username, if the username cache is enabled.
+    pub fn clear_username_cache(&self) {
+        if let Some(cache) = &self.wrapper.usernames {
+            cache.clear();
+        }
+    }
+
+    fn remember(&self, member: &MemberData) {
+        if let Some(cache) = &self.wrapper.usernames {
+            cache.store(member.username(), *member.member_id());
+        }
     }
 
     pub async fn fetch_by_discord(&self, discord_id: u64) -> Result<MemberData> {
-        self.wrapper.get(&format!("{}/members/discords/{}", crate::BASE_URL, discord_id), None).await
+        self.wrapper.get(Endpoint::MemberByDiscord(discord_id), None).await
     }
 
-    pub async fn list_recent_bans(&self) -> Result<BanData> {
-        self.wrapper.get(&format!("{}/members/bans", crate::BASE_URL), None).await
+    pub async fn list_recent_bans(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<BanData>> {
+        self.wrapper.get(Endpoint::MemberBans, sort).await
+    }
+
+    /// List every recent ban by walking pages until an empty one is returned.
+    pub async fn list_all_recent_bans(&self) -> Result<Vec<BanData>> {
+        pagination::collect_all(
+            |page| async move { self.list_recent_bans(Some(&SortOptions::default().page(page))).await },
+        )
+        .await
+    }
+
+    /// Fetch the ban of a specific member, or `None` if they aren't banned.
+    ///
+    /// The API has no endpoint for a single member's ban, so the member is fetched first and the recent bans are only
+    /// searched if they're banned. A ban which has aged out of the recent bans is also reported as `None`.
+    pub async fn fetch_ban(&self, member_id: u64) -> Result<Option<BanData>> {
+        if !self.fetch_by_id(member_id).await?.banned() {
+            return Ok(None);
+        }
+
+        let bans = pagination::collect_until(
+            |page| async move { self.list_recent_bans(Some(&SortOptions::default().page(page))).await },
+            |bans| bans.iter().any(|ban| *ban.member_id() == member_id),
+        )
+        .await?;
+
+        Ok(bans.into_iter().find(|ban| *ban.member_id() == member_id))
     }
 
     pub async fn list_profile_posts(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<ProfilePostData>> {
-        self.wrapper.get(&format!("{}/members/profile-posts", crate::BASE_URL), sort).await
+        self.wrapper.get(Endpoint::ProfilePosts, sort).await
+    }
+
+    /// List every profile post by walking pages until an empty one is returned.
+    pub async fn list_all_profile_posts(&self) -> Result<Vec<ProfilePostData>> {
+        pagination::collect_all(|page| async move {
+            self.list_profile_posts(Some(&SortOptions::default().page(page))).await
+        })
+        .await
+    }
+
+    /// Stream the profile posts, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_profile_posts(&self, sort: SortOptions<'a>) -> ItemStream<'a, ProfilePostData> {
+        ItemStream::new(self.wrapper, Endpoint::ProfilePosts, sort)
     }
 
     pub async fn fetch_profile_post(&self, profile_post_id: u64) -> Result<ProfilePostData> {
-        self.wrapper.get(&format!("{}/members/profile-posts/{}", crate::BASE_URL, profile_post_id), None).await
+        self.wrapper.get(Endpoint::ProfilePost(profile_post_id), None).await
+    }
+
+    /// Post a message to the authenticated member's own profile, returning the new post's ID.
+    pub async fn create_profile_post(&self, message: &str) -> Result<u64> {
+        self.wrapper.post(Endpoint::ProfilePosts, &ProfilePostCreateBody { message }).await
     }
 
     pub async fn edit_profile_post(&self, profile_post_id: u64, message: &str) -> Result<()> {
         let data = ProfilePostEditBody { message };
-        self.wrapper.patch(&format!("{}/members/profile-posts/{}", crate::BASE_URL, profile_post_id), &data).await
+        self.wrapper.patch(Endpoint::ProfilePost(profile_post_id), &data).await
     }
 
     pub async fn delete_profile_post(&self, profile_post_id: u64) -> Result<()> {
-        self.wrapper.delete(&format!("{}/members/profile-posts/{}", crate::BASE_URL, profile_post_id)).await
+        self.wrapper.delete(Endpoint::ProfilePost(profile_post_id)).await
     }
 }
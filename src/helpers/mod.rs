@@ -4,7 +4,7 @@
 //! A collection of types which contain helping functions that fully cover the API.
 
 pub mod alerts;
+pub mod conversations;
+pub mod members;
 pub mod resources;
 pub mod threads;
-pub mod conversations;
-pub mod members;
\ No newline at end of file
@@ -1,9 +1,14 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
-use crate::data::resources::DownloadData; 
+use crate::analytics::{self, EngagementOptions, EngagementReport};
+use crate::data::resources::DownloadData;
+use crate::endpoints::Endpoint;
 use crate::error::Result;
-use crate::sort::SortOptions;
+use crate::pagination;
+use crate::range::{self, DateRange};
+use crate::sort::{DownloadSortField, SortOptions};
+use crate::stream::ItemStream;
 use crate::APIWrapper;
 
 pub struct DownloadHelper<'a> {
@@ -12,14 +17,93 @@ pub struct DownloadHelper<'a> {
 
 impl<'a> DownloadHelper<'a> {
     pub async fn list(&self, resource_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<DownloadData>> {
-        self.wrapper.get(&format!("{}/resources/{}/downloads", crate::BASE_URL, resource_id), sort).await
+        self.wrapper.get(Endpoint::Downloads(resource_id), sort).await
     }
 
-    pub async fn list_by_member(&self, resource_id: u64, member_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<DownloadData>> {
-        self.wrapper.get(&format!("{}/resources/{}/downloads/members/{}", crate::BASE_URL, resource_id, member_id), sort).await
+    /// List every download of a resource by walking pages until an empty one is returned.
+    pub async fn list_all(&self, resource_id: u64) -> Result<Vec<DownloadData>> {
+        pagination::collect_all(
+            |page| async move { self.list(resource_id, Some(&SortOptions::default().page(page))).await },
+        )
+        .await
     }
 
-    pub async fn list_by_version(&self, resource_id: u64, version_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<DownloadData>> {
-        self.wrapper.get(&format!("{}/resources/{}/downloads/versions/{}", crate::BASE_URL, resource_id, version_id), sort).await
+    pub async fn list_by_member(
+        &self,
+        resource_id: u64,
+        member_id: u64,
+        sort: Option<&SortOptions<'_>>,
+    ) -> Result<Vec<DownloadData>> {
+        self.wrapper.get(Endpoint::DownloadsByMember(resource_id, member_id), sort).await
     }
-}
\ No newline at end of file
+
+    /// List every download of a resource by a member by walking pages until an empty one is returned.
+    pub async fn list_all_by_member(&self, resource_id: u64, member_id: u64) -> Result<Vec<DownloadData>> {
+        pagination::collect_all(|page| async move {
+            self.list_by_member(resource_id, member_id, Some(&SortOptions::default().page(page))).await
+        })
+        .await
+    }
+
+    /// Stream the downloads of a resource by a member, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_by_member(
+        &self,
+        resource_id: u64,
+        member_id: u64,
+        sort: SortOptions<'a>,
+    ) -> ItemStream<'a, DownloadData> {
+        ItemStream::new(self.wrapper, Endpoint::DownloadsByMember(resource_id, member_id), sort)
+    }
+
+    pub async fn list_by_version(
+        &self,
+        resource_id: u64,
+        version_id: u64,
+        sort: Option<&SortOptions<'_>>,
+    ) -> Result<Vec<DownloadData>> {
+        self.wrapper.get(Endpoint::DownloadsByVersion(resource_id, version_id), sort).await
+    }
+
+    /// List every download of a resource version by walking pages until an empty one is returned.
+    pub async fn list_all_by_version(&self, resource_id: u64, version_id: u64) -> Result<Vec<DownloadData>> {
+        pagination::collect_all(|page| async move {
+            self.list_by_version(resource_id, version_id, Some(&SortOptions::default().page(page))).await
+        })
+        .await
+    }
+
+    /// Stream the downloads of a resource version, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_by_version(
+        &self,
+        resource_id: u64,
+        version_id: u64,
+        sort: SortOptions<'a>,
+    ) -> ItemStream<'a, DownloadData> {
+        ItemStream::new(self.wrapper, Endpoint::DownloadsByVersion(resource_id, version_id), sort)
+    }
+
+    /// Stream the downloads of a resource, decoding each as it arrives rather than buffering whole pages.
+    ///
+    /// See `ItemStream` for how pages are walked.
+    pub fn stream(&self, resource_id: u64, sort: SortOptions<'a>) -> ItemStream<'a, DownloadData> {
+        ItemStream::new(self.wrapper, Endpoint::Downloads(resource_id), sort)
+    }
+
+    /// List only the downloads of a resource which occurred within a date range.
+    pub async fn list_between(&self, resource_id: u64, range: DateRange) -> Result<Vec<DownloadData>> {
+        let fetch = |page| async move {
+            self.list(resource_id, Some(&DateRange::sort_options(DownloadSortField::DownloadDate, page))).await
+        };
+
+        range::collect(range, fetch, |download| *download.download_date()).await
+    }
+
+    /// Fetch every purchase and download of a resource and join them per member, to find buyers who never downloaded
+    /// it and members who downloaded it far more than expected.
+    pub async fn engagement(&self, resource_id: u64, options: EngagementOptions) -> Result<EngagementReport> {
+        let purchases = self.wrapper.resources().purchases().list_all(resource_id).await?;
+        let downloads = self.list_all(resource_id).await?;
+
+        Ok(analytics::engagement(&purchases, &downloads, options))
+    }
+}
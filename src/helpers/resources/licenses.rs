@@ -1,10 +1,15 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
+use crate::audit::{self, AuditReport};
+use crate::batch::BulkLicenseReport;
 use crate::data::resources::LicenseData;
-use crate::data::resources::{LicenseModifyPermData, LicenseModifyTempData};
+use crate::data::resources::{LicenseModification, LicenseModifyPermData, LicenseModifyTempData};
+use crate::endpoints::Endpoint;
 use crate::error::Result;
+use crate::pagination;
 use crate::sort::SortOptions;
+use crate::stream::ItemStream;
 use crate::APIWrapper;
 
 pub struct LicenseHelper<'a> {
@@ -13,22 +18,110 @@ pub struct LicenseHelper<'a> {
 
 impl<'a> LicenseHelper<'a> {
     pub async fn list(&self, resource_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<LicenseData>> {
-        self.wrapper.get(&format!("{}/resources/{}/licenses", crate::BASE_URL, resource_id), sort).await
+        self.wrapper.get(Endpoint::Licenses(resource_id), sort).await
+    }
+
+    /// List every license of a resource by walking pages until an empty one is returned.
+    pub async fn list_all(&self, resource_id: u64) -> Result<Vec<LicenseData>> {
+        pagination::collect_all(
+            |page| async move { self.list(resource_id, Some(&SortOptions::default().page(page))).await },
+        )
+        .await
+    }
+
+    /// Stream the licenses of a resource, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream(&self, resource_id: u64, sort: SortOptions<'a>) -> ItemStream<'a, LicenseData> {
+        ItemStream::new(self.wrapper, Endpoint::Licenses(resource_id), sort)
     }
 
     pub async fn fetch(&self, resource_id: u64, license_id: u64) -> Result<LicenseData> {
-        self.wrapper.get(&format!("{}/resources/{}/licenses/{}", crate::BASE_URL, resource_id, license_id), None).await
+        self.wrapper.get(Endpoint::License(resource_id, license_id), None).await
     }
 
     pub async fn fetch_by_member(&self, resource_id: u64, member_id: u64) -> Result<LicenseData> {
-        self.wrapper.get(&format!("{}/resources/{}/licenses/members/{}", crate::BASE_URL, resource_id, member_id), None).await
+        self.wrapper.get(Endpoint::LicenseByMember(resource_id, member_id), None).await
+    }
+
+    pub async fn modify_permanent(
+        &self,
+        resource_id: u64,
+        license_id: u64,
+        fields: &LicenseModifyPermData,
+    ) -> Result<()> {
+        self.wrapper.patch(Endpoint::License(resource_id, license_id), &fields).await
+    }
+
+    pub async fn modify_temporary(
+        &self,
+        resource_id: u64,
+        license_id: u64,
+        fields: &LicenseModifyTempData,
+    ) -> Result<()> {
+        self.wrapper.patch(Endpoint::License(resource_id, license_id), &fields).await
     }
 
-    pub async fn modify_permanent(&self, resource_id: u64, license_id: u64, fields: &LicenseModifyPermData) -> Result<()> {
-        self.wrapper.patch(&format!("{}/resources/{}/licenses/{}", crate::BASE_URL, resource_id, license_id), &fields).await
+    /// Fetch every license and purchase of a resource and cross-reference them for inconsistencies.
+    ///
+    /// See `audit::audit` for the issues which are detected.
+    pub async fn audit(&self, resource_id: u64) -> Result<AuditReport> {
+        let licenses = self.list_all(resource_id).await?;
+        let purchases = self.wrapper.resources().purchases().list_all(resource_id).await?;
+
+        Ok(audit::audit(&licenses, &purchases))
+    }
+
+    /// Apply a single permanent or temporary modification to a license.
+    pub async fn modify(&self, resource_id: u64, license_id: u64, modification: &LicenseModification) -> Result<()> {
+        match modification {
+            LicenseModification::Permanent(fields) => self.modify_permanent(resource_id, license_id, fields).await,
+            LicenseModification::Temporary(fields) => self.modify_temporary(resource_id, license_id, fields).await,
+        }
     }
 
-    pub async fn modify_temporary(&self, resource_id: u64, license_id: u64, fields: &LicenseModifyTempData) -> Result<()> {
-        self.wrapper.patch(&format!("{}/resources/{}/licenses/{}", crate::BASE_URL, resource_id, license_id), &fields).await
+    /// Apply a set of modifications in order, attempting to revert those already applied if any later one fails.
+    ///
+    /// # Note
+    /// This is best-effort as the API has no transactional semantics: each license's state is fetched immediately
+    /// before it's modified and, on failure, restored in reverse order. Restoration may itself fail (or race with
+    /// other writers), and the returned report details exactly what could and couldn't be rolled back.
+    pub async fn modify_many_transactional(
+        &self,
+        resource_id: u64,
+        modifications: &[(u64, LicenseModification)],
+    ) -> BulkLicenseReport {
+        let mut report = BulkLicenseReport::default();
+        let mut previous_states = Vec::new();
+
+        for (license_id, modification) in modifications {
+            let result = match self.fetch(resource_id, *license_id).await {
+                Ok(previous) => self.modify(resource_id, *license_id, modification).await.map(|_| previous),
+                Err(error) => Err(error),
+            };
+
+            match result {
+                Ok(previous) => previous_states.push((*license_id, previous)),
+                Err(error) => {
+                    report.failure = Some((*license_id, error));
+                    break;
+                }
+            }
+        }
+
+        if report.failure.is_none() {
+            report.applied = previous_states.into_iter().map(|(license_id, _)| license_id).collect();
+            return report;
+        }
+
+        for (license_id, previous) in previous_states.into_iter().rev() {
+            match self.modify(resource_id, license_id, &LicenseModification::restoring(&previous)).await {
+                Ok(_) => report.rolled_back.push(license_id),
+                Err(error) => {
+                    report.applied.push(license_id);
+                    report.rollback_failures.push((license_id, error));
+                }
+            }
+        }
+
+        report
     }
 }
@@ -8,8 +8,11 @@ pub mod reviews;
 pub mod updates;
 pub mod versions;
 
+use crate::endpoints::Endpoint;
 use crate::error::Result;
-use crate::sort::SortOptions;
+use crate::pagination;
+use crate::sort::{Order, ResourceSortField, SortOptions};
+use crate::stream::ItemStream;
 use crate::APIWrapper;
 
 use crate::data::resources::BasicResourceData;
@@ -23,52 +26,148 @@ use reviews::ReviewHelper;
 use updates::UpdateHelper;
 use versions::VersionHelper;
 
+use bytes::Bytes;
+
 pub struct ResourceHelper<'a> {
     pub(crate) wrapper: &'a APIWrapper,
 }
 
 impl<'a> ResourceHelper<'a> {
     pub async fn list(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<BasicResourceData>> {
-        self.wrapper.get(&format!("{}/resources", crate::BASE_URL), sort).await
+        self.wrapper.get(Endpoint::Resources, sort).await
+    }
+
+    /// List every public resource by walking pages until an empty one is returned.
+    pub async fn list_all(&self) -> Result<Vec<BasicResourceData>> {
+        pagination::collect_all(|page| async move { self.list(Some(&SortOptions::default().page(page))).await }).await
+    }
+
+    /// Stream the public resources, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream(&self, sort: SortOptions<'a>) -> ItemStream<'a, BasicResourceData> {
+        ItemStream::new(self.wrapper, Endpoint::Resources, sort)
     }
 
     pub async fn list_owned(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<BasicResourceData>> {
-        self.wrapper.get(&format!("{}/resources/owned", crate::BASE_URL), sort).await
+        self.wrapper.get(Endpoint::ResourcesOwned, sort).await
+    }
+
+    /// List every owned resource by walking pages until an empty one is returned.
+    pub async fn list_all_owned(&self) -> Result<Vec<BasicResourceData>> {
+        pagination::collect_all(|page| async move { self.list_owned(Some(&SortOptions::default().page(page))).await })
+            .await
+    }
+
+    /// Stream the owned resources, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_owned(&self, sort: SortOptions<'a>) -> ItemStream<'a, BasicResourceData> {
+        ItemStream::new(self.wrapper, Endpoint::ResourcesOwned, sort)
     }
 
     pub async fn list_collaborated(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<BasicResourceData>> {
-        self.wrapper.get(&format!("{}/resources/collaborated", crate::BASE_URL), sort).await
+        self.wrapper.get(Endpoint::ResourcesCollaborated, sort).await
+    }
+
+    /// List every collaborated resource by walking pages until an empty one is returned.
+    pub async fn list_all_collaborated(&self) -> Result<Vec<BasicResourceData>> {
+        pagination::collect_all(
+            |page| async move { self.list_collaborated(Some(&SortOptions::default().page(page))).await },
+        )
+        .await
+    }
+
+    /// Stream the collaborated resources, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_collaborated(&self, sort: SortOptions<'a>) -> ItemStream<'a, BasicResourceData> {
+        ItemStream::new(self.wrapper, Endpoint::ResourcesCollaborated, sort)
+    }
+
+    /// List the public resources of an author.
+    ///
+    /// # Note
+    /// The API has no endpoint for an author's resources, so the public resources are walked in order of author and
+    /// only the pages up to and including the author's are fetched.
+    pub async fn list_by_author(&self, author_id: u64) -> Result<Vec<BasicResourceData>> {
+        let sort = |page| SortOptions::default().sort_by(ResourceSortField::AuthorId).order_by(Order::Asc).page(page);
+
+        let resources = pagination::collect_until(
+            |page| async move { self.list(Some(&sort(page))).await },
+            |page| page.iter().any(|resource| *resource.author_id() > author_id),
+        )
+        .await?;
+
+        Ok(resources.into_iter().filter(|resource| *resource.author_id() == author_id).collect())
+    }
+
+    /// Search the public resources for those whose title or tag line contains every word of a query, regardless of
+    /// case.
+    ///
+    /// # Note
+    /// The API has no search endpoint, so every page of public resources is fetched and matched locally. Resources
+    /// also can't be listed by category, as their category is only included when fetched individually.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// for resource in wrapper.resources().search("anti cheat").await? {
+    ///     println!("{}", resource);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search(&self, query: &str) -> Result<Vec<BasicResourceData>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+        let matches = |resource: &BasicResourceData| {
+            let text = format!("{} {}", resource.title(), resource.tag_line()).to_lowercase();
+            words.iter().all(|word| text.contains(word.as_str()))
+        };
+
+        Ok(self.list_all().await?.into_iter().filter(matches).collect())
     }
 
     pub async fn fetch(&self, resource_id: u64) -> Result<ResourceData> {
-        self.wrapper.get(&format!("{}/resources/{}", crate::BASE_URL, resource_id), None).await
+        self.wrapper.get(Endpoint::Resource(resource_id), None).await
     }
 
     pub async fn modify(&self, resource_id: u64, fields: &ResourceModifyData<'_>) -> Result<ResourceData> {
-        self.wrapper.patch(&format!("{}/resources/{}", crate::BASE_URL, resource_id), fields).await
+        self.wrapper.patch(Endpoint::Resource(resource_id), fields).await
+    }
+
+    /// Fetch the image bytes of a resource's icon, if it has one.
+    pub async fn fetch_icon(&self, resource_id: u64) -> Result<Option<Bytes>> {
+        match self.fetch(resource_id).await?.icon_url() {
+            Some(url) => Ok(Some(self.wrapper.get_external(url).await?)),
+            None => Ok(None),
+        }
     }
 
-    pub fn downloads(&self) -> DownloadHelper<'_> {
+    /// Fetch the image bytes of a resource's banner, if it has one.
+    pub async fn fetch_banner(&self, resource_id: u64) -> Result<Option<Bytes>> {
+        match self.fetch(resource_id).await?.banner_url() {
+            Some(url) => Ok(Some(self.wrapper.get_external(url).await?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn downloads(&self) -> DownloadHelper<'a> {
         DownloadHelper { wrapper: self.wrapper }
     }
-    
-    pub fn licenses(&self) -> LicenseHelper<'_> {
+
+    pub fn licenses(&self) -> LicenseHelper<'a> {
         LicenseHelper { wrapper: self.wrapper }
     }
 
-    pub fn purchases(&self) -> PurchaseHelper<'_> {
+    pub fn purchases(&self) -> PurchaseHelper<'a> {
         PurchaseHelper { wrapper: self.wrapper }
     }
 
-    pub fn reviews(&self) -> ReviewHelper<'_> {
+    pub fn reviews(&self) -> ReviewHelper<'a> {
         ReviewHelper { wrapper: self.wrapper }
     }
 
-    pub fn updates(&self) -> UpdateHelper<'_> {
+    pub fn updates(&self) -> UpdateHelper<'a> {
         UpdateHelper { wrapper: self.wrapper }
     }
 
-    pub fn versions(&self) -> VersionHelper<'_> {
+    pub fn versions(&self) -> VersionHelper<'a> {
         VersionHelper { wrapper: self.wrapper }
     }
 }
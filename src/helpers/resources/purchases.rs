@@ -2,8 +2,12 @@
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
 use crate::data::resources::PurchaseData;
+use crate::endpoints::Endpoint;
 use crate::error::Result;
-use crate::sort::SortOptions;
+use crate::pagination;
+use crate::range::{self, DateRange};
+use crate::sort::{PurchaseSortField, SortOptions};
+use crate::stream::ItemStream;
 use crate::APIWrapper;
 
 pub struct PurchaseHelper<'a> {
@@ -12,10 +16,34 @@ pub struct PurchaseHelper<'a> {
 
 impl<'a> PurchaseHelper<'a> {
     pub async fn list(&self, resource_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<PurchaseData>> {
-        self.wrapper.get(&format!("{}/resources/{}/purchases", crate::BASE_URL, resource_id), sort).await
+        self.wrapper.get(Endpoint::Purchases(resource_id), sort).await
+    }
+
+    /// List every purchase of a resource by walking pages until an empty one is returned.
+    pub async fn list_all(&self, resource_id: u64) -> Result<Vec<PurchaseData>> {
+        pagination::collect_all(
+            |page| async move { self.list(resource_id, Some(&SortOptions::default().page(page))).await },
+        )
+        .await
     }
 
     pub async fn fetch(&self, resource_id: u64, purchase_id: u64) -> Result<PurchaseData> {
-        self.wrapper.get(&format!("{}/resources/{}/purchases/{}", crate::BASE_URL, resource_id, purchase_id), None).await
+        self.wrapper.get(Endpoint::Purchase(resource_id, purchase_id), None).await
+    }
+
+    /// Stream the purchases of a resource, decoding each as it arrives rather than buffering whole pages.
+    ///
+    /// See `ItemStream` for how pages are walked.
+    pub fn stream(&self, resource_id: u64, sort: SortOptions<'a>) -> ItemStream<'a, PurchaseData> {
+        ItemStream::new(self.wrapper, Endpoint::Purchases(resource_id), sort)
+    }
+
+    /// List only the purchases of a resource which were made within a date range.
+    pub async fn list_between(&self, resource_id: u64, range: DateRange) -> Result<Vec<PurchaseData>> {
+        let fetch = |page| async move {
+            self.list(resource_id, Some(&DateRange::sort_options(PurchaseSortField::PurchaseDate, page))).await
+        };
+
+        range::collect(range, fetch, |purchase| *purchase.purchase_date()).await
     }
 }
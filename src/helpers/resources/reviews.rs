@@ -2,8 +2,12 @@
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
 use crate::data::resources::{ReviewData, ReviewRespondData};
+use crate::endpoints::Endpoint;
 use crate::error::Result;
-use crate::sort::SortOptions;
+use crate::pagination;
+use crate::range::{self, DateRange};
+use crate::sort::{ReviewSortField, SortOptions};
+use crate::stream::ItemStream;
 use crate::APIWrapper;
 
 pub struct ReviewHelper<'a> {
@@ -12,15 +16,37 @@ pub struct ReviewHelper<'a> {
 
 impl<'a> ReviewHelper<'a> {
     pub async fn list(&self, resource_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<ReviewData>> {
-        self.wrapper.get(&format!("{}/resources/{}/reviews", crate::BASE_URL, resource_id), sort).await
+        self.wrapper.get(Endpoint::Reviews(resource_id), sort).await
+    }
+
+    /// List every review of a resource by walking pages until an empty one is returned.
+    pub async fn list_all(&self, resource_id: u64) -> Result<Vec<ReviewData>> {
+        pagination::collect_all(
+            |page| async move { self.list(resource_id, Some(&SortOptions::default().page(page))).await },
+        )
+        .await
+    }
+
+    /// Stream the reviews of a resource, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream(&self, resource_id: u64, sort: SortOptions<'a>) -> ItemStream<'a, ReviewData> {
+        ItemStream::new(self.wrapper, Endpoint::Reviews(resource_id), sort)
     }
 
     pub async fn fetch_by_member(&self, resource_id: u64, member_id: u64) -> Result<ReviewData> {
-        self.wrapper.get(&format!("{}/resources/{}/reviews/members/{}", crate::BASE_URL, resource_id, member_id), None).await
+        self.wrapper.get(Endpoint::ReviewByMember(resource_id, member_id), None).await
     }
 
     pub async fn respond(&self, resource_id: u64, review_id: u64, message: &str) -> Result<()> {
         let body = ReviewRespondData { message };
-        self.wrapper.patch(&format!("{}/resources/{}/reviews/{}", crate::BASE_URL, resource_id, review_id), &body).await
+        self.wrapper.patch(Endpoint::Review(resource_id, review_id), &body).await
+    }
+
+    /// List only the reviews of a resource which were posted within a date range.
+    pub async fn list_between(&self, resource_id: u64, range: DateRange) -> Result<Vec<ReviewData>> {
+        let fetch = |page| async move {
+            self.list(resource_id, Some(&DateRange::sort_options(ReviewSortField::ReviewDate, page))).await
+        };
+
+        range::collect(range, fetch, |review| *review.review_date()).await
     }
 }
@@ -2,8 +2,11 @@
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
 use crate::data::resources::UpdateData;
+use crate::endpoints::Endpoint;
 use crate::error::Result;
+use crate::pagination;
 use crate::sort::SortOptions;
+use crate::stream::ItemStream;
 use crate::APIWrapper;
 
 pub struct UpdateHelper<'a> {
@@ -12,18 +15,31 @@ pub struct UpdateHelper<'a> {
 
 impl<'a> UpdateHelper<'a> {
     pub async fn list(&self, resource_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<UpdateData>> {
-        self.wrapper.get(&format!("{}/resources/{}/updates", crate::BASE_URL, resource_id), sort).await
+        self.wrapper.get(Endpoint::Updates(resource_id), sort).await
+    }
+
+    /// List every update of a resource by walking pages until an empty one is returned.
+    pub async fn list_all(&self, resource_id: u64) -> Result<Vec<UpdateData>> {
+        pagination::collect_all(
+            |page| async move { self.list(resource_id, Some(&SortOptions::default().page(page))).await },
+        )
+        .await
+    }
+
+    /// Stream the updates of a resource, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream(&self, resource_id: u64, sort: SortOptions<'a>) -> ItemStream<'a, UpdateData> {
+        ItemStream::new(self.wrapper, Endpoint::Updates(resource_id), sort)
     }
 
     pub async fn latest(&self, resource_id: u64) -> Result<UpdateData> {
-        self.wrapper.get(&format!("{}/resources/{}/updates/latest", crate::BASE_URL, resource_id), None).await
+        self.wrapper.get(Endpoint::LatestUpdate(resource_id), None).await
     }
 
     pub async fn fetch(&self, resource_id: u64, update_id: u64) -> Result<UpdateData> {
-        self.wrapper.get(&format!("{}/resources/{}/updates/{}", crate::BASE_URL, resource_id, update_id), None).await
+        self.wrapper.get(Endpoint::Update(resource_id, update_id), None).await
     }
 
     pub async fn delete(&self, resource_id: u64, update_id: u64) -> Result<()> {
-        self.wrapper.delete(&format!("{}/resources/{}/updates/{}", crate::BASE_URL, resource_id, update_id)).await
+        self.wrapper.delete(Endpoint::Update(resource_id, update_id)).await
     }
 }
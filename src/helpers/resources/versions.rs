@@ -2,28 +2,82 @@
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
 use crate::data::resources::VersionData;
+use crate::endpoints::Endpoint;
 use crate::error::Result;
+use crate::http;
+use crate::pagination;
 use crate::sort::SortOptions;
+use crate::stream::ItemStream;
 use crate::APIWrapper;
 
+use bytes::Bytes;
+use futures_util::io::{AsyncWrite, AsyncWriteExt};
+use reqwest::Url;
+
 pub struct VersionHelper<'a> {
     pub(crate) wrapper: &'a APIWrapper,
 }
 
 impl<'a> VersionHelper<'a> {
     pub async fn list(&self, resource_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<VersionData>> {
-        self.wrapper.get(&format!("{}/resources/{}/versions", crate::BASE_URL, resource_id), sort).await
+        self.wrapper.get(Endpoint::Versions(resource_id), sort).await
+    }
+
+    /// List every version of a resource by walking pages until an empty one is returned.
+    pub async fn list_all(&self, resource_id: u64) -> Result<Vec<VersionData>> {
+        pagination::collect_all(
+            |page| async move { self.list(resource_id, Some(&SortOptions::default().page(page))).await },
+        )
+        .await
+    }
+
+    /// Stream the versions of a resource, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream(&self, resource_id: u64, sort: SortOptions<'a>) -> ItemStream<'a, VersionData> {
+        ItemStream::new(self.wrapper, Endpoint::Versions(resource_id), sort)
     }
 
     pub async fn latest(&self, resource_id: u64) -> Result<VersionData> {
-        self.wrapper.get(&format!("{}/resources/{}/versions/latest", crate::BASE_URL, resource_id), None).await
+        self.wrapper.get(Endpoint::LatestVersion(resource_id), None).await
     }
 
     pub async fn fetch(&self, resource_id: u64, version_id: u64) -> Result<VersionData> {
-        self.wrapper.get(&format!("{}/resources/{}/versions/{}", crate::BASE_URL, resource_id, version_id), None).await
+        self.wrapper.get(Endpoint::Version(resource_id, version_id), None).await
     }
 
     pub async fn delete(&self, resource_id: u64, version_id: u64) -> Result<()> {
-        self.wrapper.delete(&format!("{}/resources/{}/versions/{}", crate::BASE_URL, resource_id, version_id)).await
+        self.wrapper.delete(Endpoint::Version(resource_id, version_id)).await
+    }
+
+    /// Returns the authenticated endpoint URL from which a specific version's file can be downloaded.
+    ///
+    /// Requests to this URL must carry the same 'Authorization' header as any other API request.
+    pub fn download_url(&self, resource_id: u64, version_id: u64) -> Url {
+        Endpoint::VersionDownload(resource_id, version_id).url(&self.wrapper.base_url)
+    }
+
+    /// Download a specific version's file into memory.
+    pub async fn download(&self, resource_id: u64, version_id: u64) -> Result<Bytes> {
+        Ok(self.wrapper.get_response(Endpoint::VersionDownload(resource_id, version_id)).await?.bytes().await?)
+    }
+
+    /// Stream a specific version's file into an asynchronous writer, returning the number of bytes written.
+    ///
+    /// The writer is a `futures` `AsyncWrite`, so a Tokio writer (eg. `tokio::fs::File`) can be adapted via
+    /// `tokio_util::compat`.
+    pub async fn download_to<W>(&self, resource_id: u64, version_id: u64, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let response = self.wrapper.get_response(Endpoint::VersionDownload(resource_id, version_id)).await?;
+        let mut body = http::Body::new(response);
+        let mut written = 0;
+
+        while let Some(chunk) = body.chunk().await? {
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+
+        writer.flush().await?;
+        Ok(written)
     }
 }
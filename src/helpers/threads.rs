@@ -1,29 +1,147 @@
 // Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
+use crate::data::threads::{BasicThreadData, ReplyBody, ReplyData, ThreadData};
+use crate::endpoints::Endpoint;
 use crate::error::Result;
-use crate::data::threads::{BasicThreadData, ThreadData, ReplyData, ReplyBody};
+use crate::pagination;
+use crate::range::{self, DateRange};
+use crate::sort::{Order, SortOptions, ThreadFilter, ThreadReplySortField};
+use crate::stream::{IdWatch, ItemStream};
 use crate::APIWrapper;
-use crate::sort::SortOptions;
 
+use std::time::Duration;
+
+/// Helping functions for the threads the authenticated member can reply to.
+///
+/// # Note
+/// The API only exposes the number of reactions each reply has received (see `ReplyData::like_count`), and has no
+/// endpoints for listing who reacted or for reacting to a reply.
 pub struct ThreadsHelper<'a> {
     pub(crate) wrapper: &'a APIWrapper,
 }
 
 impl<'a> ThreadsHelper<'a> {
+    /// List the threads the authenticated member can reply to.
+    ///
+    /// # Note
+    /// The API lists threads started by the authenticated member alongside those they've participated in, without
+    /// distinguishing between them.
     pub async fn list_threads(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<BasicThreadData>> {
-        self.wrapper.get(&format!("{}/threads", crate::BASE_URL), sort).await
+        self.wrapper.get(Endpoint::Threads, sort).await
+    }
+
+    /// List every thread by walking pages until an empty one is returned.
+    pub async fn list_all_threads(&self) -> Result<Vec<BasicThreadData>> {
+        pagination::collect_all(|page| async move { self.list_threads(Some(&SortOptions::default().page(page))).await })
+            .await
+    }
+
+    /// List the threads on a page which match a filter (eg. only the open threads within a forum).
+    ///
+    /// # Note
+    /// The API can't filter the threads it lists, and only includes a thread's forum, state, and type within its
+    /// details. Each listed thread is therefore fetched to be matched, costing a request per thread.
+    pub async fn list_threads_filtered(
+        &self,
+        filter: &ThreadFilter,
+        sort: Option<&SortOptions<'_>>,
+    ) -> Result<Vec<ThreadData>> {
+        let threads = self.list_threads(sort).await?;
+        self.fetch_matching(filter, &threads).await
+    }
+
+    /// List every thread which matches a filter by walking pages until an empty one is returned.
+    ///
+    /// # Note
+    /// As with `list_threads_filtered`, each listed thread is fetched to be matched.
+    pub async fn list_all_threads_filtered(&self, filter: &ThreadFilter) -> Result<Vec<ThreadData>> {
+        let threads = self.list_all_threads().await?;
+        self.fetch_matching(filter, &threads).await
+    }
+
+    async fn fetch_matching(&self, filter: &ThreadFilter, threads: &[BasicThreadData]) -> Result<Vec<ThreadData>> {
+        let mut matching = Vec::new();
+
+        for thread in threads {
+            let thread = self.fetch_thread(*thread.thread_id()).await?;
+
+            if filter.matches(&thread) {
+                matching.push(thread);
+            }
+        }
+
+        Ok(matching)
+    }
+
+    /// Stream the threads, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_threads(&self, sort: SortOptions<'a>) -> ItemStream<'a, BasicThreadData> {
+        ItemStream::new(self.wrapper, Endpoint::Threads, sort)
     }
 
     pub async fn fetch_thread(&self, thread_id: u64) -> Result<ThreadData> {
-        self.wrapper.get(&format!("{}/threads/{}", crate::BASE_URL, thread_id), None).await
+        self.wrapper.get(Endpoint::Thread(thread_id), None).await
     }
 
     pub async fn list_replies(&self, thread_id: u64, sort: Option<&SortOptions<'_>>) -> Result<Vec<ReplyData>> {
-        self.wrapper.get(&format!("{}/threads/{}/replies", crate::BASE_URL, thread_id), sort).await
+        self.wrapper.get(Endpoint::ThreadReplies(thread_id), sort).await
+    }
+
+    /// List every reply to a thread by walking pages until an empty one is returned.
+    pub async fn list_all_replies(&self, thread_id: u64) -> Result<Vec<ReplyData>> {
+        pagination::collect_all(|page| async move {
+            self.list_replies(thread_id, Some(&SortOptions::default().page(page))).await
+        })
+        .await
+    }
+
+    /// Stream the replies to a thread, decoding each as it arrives rather than buffering whole pages.
+    pub fn stream_replies(&self, thread_id: u64, sort: SortOptions<'a>) -> ItemStream<'a, ReplyData> {
+        ItemStream::new(self.wrapper, Endpoint::ThreadReplies(thread_id), sort)
+    }
+
+    /// List only the replies to a thread which were posted within a date range.
+    ///
+    /// Replies are walked newest-first, so only the pages back to the start of the range are fetched.
+    pub async fn list_replies_between(&self, thread_id: u64, range: DateRange) -> Result<Vec<ReplyData>> {
+        let fetch = |page| async move {
+            self.list_replies(thread_id, Some(&DateRange::sort_options(ThreadReplySortField::PostDate, page))).await
+        };
+
+        range::collect(range, fetch, |reply| *reply.post_date()).await
+    }
+
+    /// Watch a thread for new replies by polling on an interval, yielding each reply once (oldest first).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// let mut replies = wrapper.threads().watch_replies(1, Duration::from_secs(60));
+    ///
+    /// loop {
+    ///     let reply = replies.next().await?;
+    ///
+    ///     if reply.message().contains("refund") {
+    ///         wrapper.threads().reply(1, "Please open a conversation with us for refund requests.").await?;
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub fn watch_replies(&self, thread_id: u64, interval: Duration) -> IdWatch<'a, ReplyData> {
+        let wrapper = self.wrapper;
+
+        IdWatch::new(
+            interval,
+            |reply: &ReplyData| *reply.reply_id(),
+            move |page| async move {
+                let sort = SortOptions::default().sort_by(ThreadReplySortField::ReplyId).order_by(Order::Desc);
+                wrapper.threads().list_replies(thread_id, Some(&sort.page(page))).await
+            },
+        )
     }
 
     pub async fn reply(&self, thread_id: u64, message: &str) -> Result<u64> {
-        self.wrapper.post(&format!("{}/threads/{}/replies", crate::BASE_URL, thread_id), &ReplyBody { message }).await
+        self.wrapper.post(Endpoint::ThreadReplies(thread_id), &ReplyBody { message }).await
     }
 }
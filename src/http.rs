@@ -11,151 +11,471 @@
 // approach lacks consistency with the rest of this wrapper and is harder to maintain. We've gone with the former
 // where the outer loop controls the request retry, and the inner loop controls the stalling retry.
 
-use crate::error::APIError;
+#[cfg(feature = "cache")]
+use crate::endpoints::Endpoint;
 use crate::error::Result;
-use crate::throttler::{RateLimitStore, RequestType};
+use crate::error::{APIError, ErrorBody};
+use crate::options::{self, Priority};
+use crate::retry;
+use crate::runtime::Instant;
+use crate::stats;
+use crate::throttler::{RateLimitStore, RequestType, Tracked};
 use crate::APIWrapper;
 
-use reqwest::{Response, StatusCode};
-use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use tokio::time::Duration;
+use std::future::Future;
+use std::sync::atomic::Ordering;
+
+use bytes::Bytes;
+#[cfg(feature = "cache")]
+use reqwest::header::HeaderMap;
+use reqwest::header::AUTHORIZATION;
+use reqwest::{Request, RequestBuilder, Response, StatusCode, Url};
+use serde::de::{value::UnitDeserializer, DeserializeOwned};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How long to wait (in milliseconds) after being rate limited by a response without a valid `Retry-After` header.
+const DEFAULT_RETRY_AFTER_MS: u64 = 1_000;
+
+/// The outcome reported by a response from the API.
+///
+/// Deserialised as a unit enum so that we don't allocate a string for every response.
+#[derive(Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseResult {
+    Success,
+    Error,
+}
 
 /// A structure representing a parsed response from the API.
 #[derive(Deserialize)]
 pub struct APIResponse<D> {
-    pub result: String,
+    pub result: ResponseResult,
     pub data: Option<D>,
-    pub error: Option<APIError>,
+    pub error: Option<ErrorBody>,
 }
 
 impl<D> APIResponse<D> {
     /// Returns whether or not the response was successful.
     pub fn is_success(&self) -> bool {
-        self.result == "success"
-    }
-
-    /// Returns the containing data within the response.
-    ///
-    /// Will panic if the response was not successful.
-    pub fn data(self) -> D {
-        self.data.expect("no data present")
+        self.result == ResponseResult::Success
     }
 
     /// Returns the containing error within the response.
     ///
     /// Will panic if the response was successful.
     pub fn error(self) -> APIError {
-        self.error.expect("no error present")
+        self.error.expect("no error present").into()
     }
 
-    pub fn as_result(self) -> Result<D> {
-        if self.is_success() {
-            Ok(self.data())
-        } else {
-            Err(self.error())
+    /// Returns the containing data if the response was successful, or the containing error otherwise.
+    ///
+    /// Successful responses to writes may not contain any data, in which case it's deserialised from nothing (which
+    /// only succeeds for types such as `()`).
+    pub fn into_result(self) -> Result<D>
+    where
+        D: DeserializeOwned,
+    {
+        if !self.is_success() {
+            return Err(self.error());
+        }
+
+        match self.data {
+            Some(data) => Ok(data),
+            None => D::deserialize(UnitDeserializer::<serde::de::value::Error>::new()).map_err(APIError::deserialize),
         }
     }
 }
 
-pub async fn get<D>(wrapper: &APIWrapper, endpoint: &str) -> Result<APIResponse<D>>
+pub async fn get<D>(wrapper: &APIWrapper, url: &Url) -> Result<APIResponse<D>>
 where
     D: DeserializeOwned,
 {
-    loop {
-        loop {
-            match crate::throttler::stall_for(&wrapper.rate_limit_store, RequestType::READ) {
-                0 => break,
-                stall_for => tokio::time::sleep(Duration::from_millis(stall_for)).await,
-            };
-        }
+    // Health checks must reflect the API's current state, so they're never served from the cache.
+    #[cfg(feature = "cache")]
+    if let Some(cache) = wrapper.cache.as_ref().filter(|_| *url != Endpoint::Health.url(&wrapper.base_url)) {
+        return crate::cache::get(wrapper, cache, url).await;
+    }
 
-        let response = wrapper.http_client.get(endpoint).send().await?;
+    retrying(wrapper, RequestType::Read, || async { parse(get_response(wrapper, url).await?).await }).await
+}
 
-        if !did_hit_limit(&wrapper.rate_limit_store, &response, RequestType::READ) {
-            return Ok(response.json().await?);
-        }
+/// Make a GET request with additional headers (eg. those of a conditional request) and return the raw response once
+/// it's passed the rate limiter.
+#[cfg(feature = "cache")]
+pub(crate) async fn get_response_with(wrapper: &APIWrapper, url: &Url, headers: &HeaderMap) -> Result<Response> {
+    send(wrapper, RequestType::Read, || wrapper.http_client.get(url.clone()).headers(headers.clone())).await
+}
+
+/// Make a GET request and return the raw response once it's passed the rate limiter.
+pub async fn get_response(wrapper: &APIWrapper, url: &Url) -> Result<Response> {
+    send(wrapper, RequestType::Read, || wrapper.http_client.get(url.clone())).await
+}
+
+/// Make a GET request to an endpoint which responds with a raw (non-JSON) body on success.
+///
+/// Unsuccessful responses are still expected to contain a standard JSON error body.
+pub async fn get_raw(wrapper: &APIWrapper, url: &Url) -> Result<Response> {
+    let response =
+        retrying(wrapper, RequestType::Read, || async { check_status(get_response(wrapper, url).await?) }).await?;
+
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(decode::<APIResponse<()>>(&mut Vec::from(response.bytes().await?))?.error())
     }
 }
 
-pub async fn post<D, B>(wrapper: &APIWrapper, endpoint: &str, body: &B) -> Result<APIResponse<D>>
+/// Make an unauthenticated GET request to a URL outside of the API (eg. resource media).
+///
+/// These requests don't count towards the API's rate limits so bypass the throttler entirely.
+pub async fn get_external(wrapper: &APIWrapper, url: &str) -> Result<Bytes> {
+    let request = wrapper.http_client.get(url).build()?;
+    Ok(wrapper.transport.execute(request).await?.error_for_status()?.bytes().await?)
+}
+
+pub async fn post<D, B>(wrapper: &APIWrapper, url: &Url, body: &B) -> Result<APIResponse<D>>
 where
     D: DeserializeOwned,
     B: Serialize,
 {
-    loop {
-        loop {
-            match crate::throttler::stall_for(&wrapper.rate_limit_store, RequestType::WRITE) {
-                0 => break,
-                stall_for => tokio::time::sleep(Duration::from_millis(stall_for)).await,
-            };
-        }
+    let response = retrying(wrapper, RequestType::Write, || async {
+        parse(send(wrapper, RequestType::Write, || wrapper.http_client.post(url.clone()).json(body)).await?).await
+    })
+    .await;
 
-        let response = wrapper.http_client.post(endpoint).json(body).send().await?;
+    invalidate_cached(wrapper, url);
+    response
+}
 
-        if !did_hit_limit(&wrapper.rate_limit_store, &response, RequestType::WRITE) {
-            return Ok(response.json().await?);
-        }
+pub async fn patch<D, B>(wrapper: &APIWrapper, url: &Url, body: &B) -> Result<APIResponse<D>>
+where
+    D: DeserializeOwned,
+    B: Serialize,
+{
+    let response = retrying(wrapper, RequestType::Write, || async {
+        parse(send(wrapper, RequestType::Write, || wrapper.http_client.patch(url.clone()).json(body)).await?).await
+    })
+    .await;
+
+    invalidate_cached(wrapper, url);
+    response
+}
+
+pub async fn delete<D>(wrapper: &APIWrapper, url: &Url) -> Result<APIResponse<D>>
+where
+    D: DeserializeOwned,
+{
+    let response = retrying(wrapper, RequestType::Write, || async {
+        parse(send(wrapper, RequestType::Write, || wrapper.http_client.delete(url.clone())).await?).await
+    })
+    .await;
+
+    invalidate_cached(wrapper, url);
+    response
+}
+
+/// Remove any cached reads made stale by a write to a URL (see `ResponseCache::invalidate_write`), whether or not the
+/// write succeeded, as a failed write may have still been applied.
+fn invalidate_cached(wrapper: &APIWrapper, url: &Url) {
+    #[cfg(feature = "cache")]
+    if let Some(cache) = &wrapper.cache {
+        cache.invalidate_write(&wrapper.base_url, url);
     }
+
+    #[cfg(not(feature = "cache"))]
+    let _ = (wrapper, url);
 }
 
-pub async fn patch<D, B>(wrapper: &APIWrapper, endpoint: &str, body: &B) -> Result<APIResponse<D>>
+/// Run a request under the current scope's or wrapper's retry policy, if one applies to its type.
+///
+/// Writes are only retried under the wrapper's policy if enabled on the wrapper, as a write which failed after reaching
+/// the API may have already been applied.
+pub(crate) async fn retrying<T, F, Fut>(wrapper: &APIWrapper, request_type: RequestType, mut operation: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let retryable = matches!(request_type, RequestType::Read) || wrapper.retry_writes;
+
+    // A policy set within a scope's options overrides the wrapper's entirely, including for writes.
+    match options::current().and_then(|options| options.retry_policy) {
+        Some(Some(policy)) => retry::retry_with(policy.as_ref(), operation).await,
+        Some(None) => operation().await,
+        None => match &wrapper.retry_policy {
+            Some(policy) if retryable => retry::retry_with(policy.as_ref(), operation).await,
+            _ => operation().await,
+        },
+    }
+}
+
+/// How long a request spent stalled locally versus awaiting the API, summed across any resends.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Timings {
+    /// The time spent waiting for a concurrency permit and within the throttler.
+    pub(crate) stalled: Duration,
+    /// The time spent between sending the request and receiving its response headers.
+    pub(crate) network: Duration,
+}
+
+/// Make a GET request without retries, returning the parsed response along with how long it spent in each stage.
+pub(crate) async fn get_timed<D>(wrapper: &APIWrapper, url: &Url) -> Result<(APIResponse<D>, Timings)>
 where
     D: DeserializeOwned,
-    B: Serialize,
 {
+    let (response, timings) = send_timed(wrapper, RequestType::Read, || wrapper.http_client.get(url.clone())).await?;
+    Ok((parse(response).await?, timings))
+}
+
+/// Send a request once it's passed the rate limiter, resending it for as long as we continue to hit the limit.
+///
+/// As the request may need to be sent multiple times, we take a function which builds a fresh request per attempt. If
+/// the wrapper limits how many times (or for how long) a request may be rate limited, a `RateLimited` error is
+/// returned once that limit is exceeded.
+async fn send<F>(wrapper: &APIWrapper, request_type: RequestType, build: F) -> Result<Response>
+where
+    F: Fn() -> RequestBuilder,
+{
+    Ok(send_timed(wrapper, request_type, build).await?.0)
+}
+
+/// Send a request as with `send`, also returning how long it spent in each stage.
+async fn send_timed<F>(wrapper: &APIWrapper, request_type: RequestType, build: F) -> Result<(Response, Timings)>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let mut timings = Timings::default();
+    let deadline = wrapper.rate_limit_deadline.map(|deadline| Instant::now() + deadline);
+    let mut rate_limited = 0;
+
+    let priority = options::current().and_then(|options| options.priority).unwrap_or_default();
+
     loop {
+        let queued = Tracked::new(&wrapper.rate_limit_store.queued);
+        let queued_at = Instant::now();
+
+        let limit = match request_type {
+            RequestType::Read => &wrapper.read_concurrency,
+            RequestType::Write => &wrapper.write_concurrency,
+        };
+
+        // Permits are taken before the throttler so that only requests which could be sent immediately are stalled.
+        let permit = match limit {
+            Some(limit) => Some(limit.acquire().await),
+            None => None,
+        };
+
+        let waiting = (priority == Priority::Interactive)
+            .then(|| Tracked::new(wrapper.rate_limit_store.interactive(request_type)));
+
+        // Each attempt may be sent with a different pooled token, as the previous one may have been rate limited.
+        let token = match (&wrapper.rate_limit_store.pool, request_type) {
+            (Some(pool), RequestType::Read) => Some(pool.select()),
+            _ => None,
+        };
+
         loop {
-            match crate::throttler::stall_for(&wrapper.rate_limit_store, RequestType::WRITE) {
+            match crate::throttler::stall_for(&wrapper.rate_limit_store, request_type, priority, token) {
                 0 => break,
-                stall_for => tokio::time::sleep(Duration::from_millis(stall_for)).await,
+                stall_for => {
+                    let stall_for = Duration::from_millis(stall_for);
+
+                    if deadline.is_some_and(|deadline| Instant::now() + stall_for > deadline) {
+                        return Err(APIError::RateLimited { retry_after: stall_for });
+                    }
+
+                    crate::runtime::sleep(stall_for).await;
+                }
             };
         }
 
-        let response = wrapper.http_client.post(endpoint).json(body).send().await?;
+        drop(waiting);
+        drop(queued);
+        timings.stalled += queued_at.elapsed();
+        let in_flight = Tracked::new(&wrapper.rate_limit_store.in_flight);
+
+        #[cfg(feature = "fault-injection")]
+        crate::faults::inject(&wrapper.faults).await?;
+
+        let timeout = options::current().and_then(|options| options.timeout);
+        let mut headers = wrapper.headers.clone();
+
+        let pooled = wrapper.rate_limit_store.pool.as_ref().zip(token);
+        match pooled.and_then(|(pool, token)| pool.tokens[token].header.as_ref()) {
+            Some(header) => headers.insert(AUTHORIZATION, header.clone()),
+            None => headers.insert(AUTHORIZATION, wrapper.token.read().unwrap().clone()),
+        };
+
+        let request = build().headers(headers);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let request = match timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        };
+
+        let mut request = request.build()?;
 
-        if !did_hit_limit(&wrapper.rate_limit_store, &response, RequestType::WRITE) {
-            return Ok(response.json().await?);
+        for interceptor in &wrapper.interceptors {
+            interceptor.on_request(&mut request)?;
+        }
+
+        let endpoint = stats::endpoint_of(&wrapper.base_url, request.url());
+        let sent_at = Instant::now();
+
+        #[cfg(feature = "otel")]
+        let span = crate::otel::start(&mut request, &endpoint);
+
+        let response = execute(wrapper, request, timeout).await;
+        drop(in_flight);
+
+        #[cfg(feature = "otel")]
+        crate::otel::end(&span, &response);
+
+        let latency = sent_at.elapsed();
+        timings.network += latency;
+
+        wrapper.stats.record(endpoint, response.as_ref().ok().map(Response::status), latency);
+        let response = response?;
+
+        for interceptor in &wrapper.interceptors {
+            interceptor.on_response(&response);
+        }
+
+        drop(permit);
+
+        wrapper.maintenance.store(response.status() == StatusCode::SERVICE_UNAVAILABLE, Ordering::Release);
+
+        match did_hit_limit(&wrapper.rate_limit_store, &response, request_type, token) {
+            None => return Ok((response, timings)),
+            Some(retry) => {
+                rate_limited += 1;
+
+                if wrapper.max_rate_limit_retries.is_some_and(|max| rate_limited > max) {
+                    return Err(APIError::RateLimited { retry_after: Duration::from_millis(retry) });
+                }
+            }
         }
     }
 }
 
-pub async fn delete<D>(wrapper: &APIWrapper, endpoint: &str) -> Result<APIResponse<D>>
+/// Send a built request via the wrapper's transport.
+#[cfg(not(target_arch = "wasm32"))]
+async fn execute(wrapper: &APIWrapper, request: Request, _: Option<Duration>) -> Result<Response> {
+    wrapper.transport.execute(request).await
+}
+
+/// Send a built request via the wrapper's transport.
+///
+/// The wasm backend can't time out requests itself, so we race the request against a timer instead. Dropping the
+/// request's future aborts it.
+#[cfg(target_arch = "wasm32")]
+async fn execute(wrapper: &APIWrapper, request: Request, timeout: Option<Duration>) -> Result<Response> {
+    let Some(timeout) = timeout else {
+        return wrapper.transport.execute(request).await;
+    };
+
+    let timed_out = async {
+        crate::runtime::sleep(timeout).await;
+        Err(APIError::Timeout { source: None })
+    };
+
+    crate::runtime::race(wrapper.transport.execute(request), timed_out).await
+}
+
+/// Parse a response's body once we've checked it isn't a server error.
+async fn parse<D>(response: Response) -> Result<APIResponse<D>>
 where
     D: DeserializeOwned,
 {
-    loop {
-        loop {
-            match crate::throttler::stall_for(&wrapper.rate_limit_store, RequestType::WRITE) {
-                0 => break,
-                stall_for => tokio::time::sleep(Duration::from_millis(stall_for)).await,
-            };
-        }
+    let response = check_status(response)?;
+    let endpoint = response.url().path().to_string();
 
-        let response = wrapper.http_client.delete(endpoint).send().await?;
+    // Converting the buffered body into a vector doesn't copy it, as the body is uniquely owned at this point.
+    decode(&mut Vec::from(response.bytes().await?)).map_err(|error| error.at_endpoint(&endpoint))
+}
 
-        if !did_hit_limit(&wrapper.rate_limit_store, &response, RequestType::WRITE) {
-            return response.json().await?;
-        }
+/// Deserialise a JSON value directly from a buffer, rather than via an intermediate string or value.
+///
+/// The buffer is taken mutably as simd-json (when the `simd-json` feature is enabled) parses in-place, leaving its
+/// contents unspecified afterwards.
+pub(crate) fn decode<D>(body: &mut [u8]) -> Result<D>
+where
+    D: DeserializeOwned,
+{
+    #[cfg(feature = "simd-json")]
+    return Ok(simd_json::serde::from_slice(body)?);
+
+    #[cfg(not(feature = "simd-json"))]
+    return Ok(serde_json::from_slice(body)?);
+}
+
+/// A response's body, read a chunk at a time.
+pub(crate) struct Body {
+    #[cfg(not(target_arch = "wasm32"))]
+    response: Response,
+    /// Within browsers, a response's body can only be read incrementally via a stream.
+    #[cfg(target_arch = "wasm32")]
+    stream: std::pin::Pin<Box<dyn futures_util::Stream<Item = reqwest::Result<Bytes>>>>,
+}
+
+impl Body {
+    pub(crate) fn new(response: Response) -> Body {
+        #[cfg(not(target_arch = "wasm32"))]
+        return Body { response };
+
+        #[cfg(target_arch = "wasm32")]
+        return Body { stream: Box::pin(response.bytes_stream()) };
+    }
+
+    /// Returns the next chunk of the body, or `None` once it's been read in full.
+    pub(crate) async fn chunk(&mut self) -> Result<Option<Bytes>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        return Ok(self.response.chunk().await?);
+
+        #[cfg(target_arch = "wasm32")]
+        return Ok(futures_util::StreamExt::next(&mut self.stream).await.transpose()?);
     }
 }
 
-fn did_hit_limit(store: &RateLimitStore, response: &Response, request_type: RequestType) -> bool {
-    if response.status() != StatusCode::TOO_MANY_REQUESTS {
-        match &request_type {
-            RequestType::READ => store.reset_read(),
-            RequestType::WRITE => store.reset_write(),
-        };
+/// Map server error statuses to an error before we attempt to parse the body, as it's unlikely to be one of the API's
+/// standard JSON responses (eg. an HTML error page from a proxy).
+pub(crate) fn check_status(response: Response) -> Result<Response> {
+    if response.status() == StatusCode::SERVICE_UNAVAILABLE {
+        return Err(APIError::Maintenance);
+    }
 
-        return false;
+    if response.status().is_server_error() {
+        return Err(APIError::ServerError { status: response.status() });
     }
 
-    let retry = response.headers().get("Retry-After").expect("no retry-after header present");
-    let retry: u64 = retry.to_str().expect("non-ascii characters present").parse().expect("not a valid u64 int");
+    Ok(response)
+}
+
+/// Record the outcome of a response within the rate limit store, returning how long to wait (in milliseconds) if we hit
+/// the limit.
+fn did_hit_limit(
+    store: &RateLimitStore,
+    response: &Response,
+    request_type: RequestType,
+    token: Option<usize>,
+) -> Option<u64> {
+    if response.status() != StatusCode::TOO_MANY_REQUESTS {
+        store.reset(request_type, token);
+        return None;
+    }
 
-    match &request_type {
-        RequestType::READ => store.store_read(retry),
-        RequestType::WRITE => store.store_write(retry),
+    let header = response.headers().get("Retry-After");
+    let retry = match header.and_then(|retry| retry.to_str().ok()?.trim().parse().ok()) {
+        Some(retry) => retry,
+        None => {
+            // Eg. a proxy's rate limit, or one given as an HTTP date rather than a delay.
+            log::warn!("Rate limited without a valid Retry-After header ({:?}), backing off by default.", header);
+            DEFAULT_RETRY_AFTER_MS
+        }
     };
 
-    true
+    store.store(request_type, token, retry);
+
+    Some(retry)
 }
@@ -12,52 +12,267 @@
 //!
 //! [Read more.](https://github.com/Majored/rs-bbb-api-wrapper)
 
+pub mod accounts;
+pub mod analytics;
+pub mod anomaly;
+pub mod audit;
+pub mod batch;
+pub mod builder;
+#[cfg(feature = "cache")]
+mod cache;
+pub mod capabilities;
+#[cfg(all(feature = "vcr", not(target_arch = "wasm32")))]
+pub mod cassette;
+pub mod compatibility;
 pub mod data;
+pub mod debug;
+pub(crate) mod endpoints;
 pub mod error;
+pub mod events;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(feature = "fault-injection")]
+pub mod faults;
+pub mod format;
+pub mod goals;
 pub mod helpers;
-pub mod sort;
 pub(crate) mod http;
+pub mod middleware;
+#[cfg(all(feature = "mock", not(target_arch = "wasm32")))]
+pub mod mock;
+pub mod objects;
+pub mod options;
+#[cfg(feature = "otel")]
+mod otel;
+pub mod outbox;
+pub(crate) mod pagination;
+pub mod placeholders;
+pub mod pool;
+pub mod range;
+pub mod rate_limit;
+pub mod refunds;
+pub mod reports;
+pub mod retry;
+pub(crate) mod runtime;
+pub mod scheduler;
+mod semaphore;
+pub mod sort;
+pub mod stats;
+pub mod status;
+pub mod stream;
+#[cfg(feature = "sync")]
+pub mod sync;
+pub mod template;
 pub(crate) mod throttler;
+pub mod timestamps;
+pub mod transcript;
+pub mod transport;
+mod usernames;
+pub mod verifier;
+pub mod watchdog;
+pub mod watchers;
 
+use batch::BatchReport;
+use builder::APIWrapperBuilder;
+use capabilities::Capabilities;
+use compatibility::CompatibilityReport;
 use data::metrics::MetricsSnapshot;
+use debug::DebugSnapshot;
+use endpoints::Endpoint;
 use error::{APIError, Result};
 use helpers::alerts::AlertsHelper;
-use helpers::resources::ResourceHelper;
 use helpers::conversations::ConversationsHelper;
 use helpers::members::MembersHelper;
+use helpers::resources::ResourceHelper;
 use helpers::threads::ThreadsHelper;
-use throttler::RateLimitStore;
+use middleware::RequestInterceptor;
+use pool::TokenPool;
+use retry::RetryPolicy;
+use runtime::Instant;
+use semaphore::Semaphore;
 use sort::SortOptions;
+use stats::{ClientStats, PingStats, StatsCollector};
+use status::{Diagnosis, ServiceStatus};
+use throttler::RateLimitStore;
+use transport::HttpTransport;
+use watchdog::HealthWatchdog;
 
-use std::time::{Duration, Instant};
+use std::future::Future;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::RwLock;
+use std::time::Duration;
 
-use reqwest::{header::HeaderMap, Client, ClientBuilder};
-use serde::{de::DeserializeOwned, Serialize, Deserialize};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Client, Method, Url};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
-/// The base API URL and version which will be prepended to all endpoints.
+/// The default base API URL and version onto which all endpoints are joined.
 pub(crate) const BASE_URL: &str = "https://api.builtbybit.com/v1";
 
+/// The error code we attach to requests made via `APIWrapper::request` with an invalid method and body combination.
+const REQUEST_CODE: &str = "RequestError";
+
+/// The base URL of the website itself, used when constructing links to site content.
+pub(crate) const SITE_URL: &str = "https://builtbybit.com";
+
+/// The error code we attach to tokens which couldn't be loaded.
+const TOKEN_CODE: &str = "TokenError";
+
+/// The placeholder which replaces a token's secret within its debug and serialised output.
+const REDACTED: &str = "<redacted>";
+
 /// An enum representing the two possible API token types.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// The secret is redacted from the token's debug and serialised output, so tokens can be logged or included within
+/// config dumps safely. With the `zeroize` feature enabled, the token's own copy of the secret is also zeroed in
+/// memory once dropped.
+///
+/// # Note
+/// A wrapper keeps the secret within the 'Authorization' header attached to each request, which the HTTP client
+/// doesn't zero. That header is marked as sensitive, so it's at least omitted from the client's debug output.
+#[derive(Clone, Deserialize)]
 pub enum APIToken {
     Private(String),
     Shared(String),
 }
 
 impl APIToken {
-    /// Returns the token as an expected 'Authorization' header value.
-    pub(crate) fn as_header(&self) -> String {
-        match self {
+    /// Parse a token from either its 'Authorization' header form (eg. `Shared y6xWrGkAzh8Gp4qBW...`) or a bare secret,
+    /// which is assumed to be private.
+    ///
+    /// # Example
+    /// ```
+    /// # use bbb_api_wrapper::APIToken;
+    /// assert!(matches!(APIToken::parse("Shared y6xWrGkAzh8Gp4qBW"), APIToken::Shared(_)));
+    /// assert!(matches!(APIToken::parse("y6xWrGkAzh8Gp4qBW"), APIToken::Private(_)));
+    /// ```
+    pub fn parse(value: &str) -> APIToken {
+        let value = value.trim();
+
+        match value.split_once(' ') {
+            Some(("Private", secret)) => APIToken::Private(secret.trim_start().to_string()),
+            Some(("Shared", secret)) => APIToken::Shared(secret.trim_start().to_string()),
+            _ => APIToken::Private(value.to_string()),
+        }
+    }
+
+    /// Load a token from an environment variable (eg. `BBB_TOKEN`), as parsed by `APIToken::parse`.
+    pub fn from_env(name: &str) -> Result<APIToken> {
+        match std::env::var(name) {
+            Ok(value) if !value.trim().is_empty() => Ok(APIToken::parse(&value)),
+            _ => {
+                let message = format!("The environment variable '{}' isn't set to a token", name);
+                Err(APIError::local(TOKEN_CODE, message))
+            }
+        }
+    }
+
+    /// Load a token from a file (eg. a mounted secret), as parsed by `APIToken::parse`.
+    ///
+    /// Surrounding whitespace (such as a trailing newline) is ignored.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<APIToken> {
+        let value = std::fs::read_to_string(&path)?;
+
+        if value.trim().is_empty() {
+            let message = format!("The file '{}' doesn't contain a token", path.as_ref().display());
+            return Err(APIError::local(TOKEN_CODE, message));
+        }
+
+        Ok(APIToken::parse(&value))
+    }
+
+    /// Returns the token as an expected 'Authorization' header value, marked as sensitive so that the HTTP client omits
+    /// it from its debug output.
+    ///
+    /// Fails if the secret contains characters which aren't valid within a header (eg. control characters).
+    pub(crate) fn header_value(&self) -> Result<HeaderValue> {
+        #[cfg_attr(not(feature = "zeroize"), allow(unused_mut))]
+        let mut header = match self {
             APIToken::Private(value) => format!("Private {}", value),
             APIToken::Shared(value) => format!("Shared {}", value),
+        };
+
+        let parsed = HeaderValue::from_str(&header);
+
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut header);
+
+        let mut header = parsed.map_err(|_| {
+            APIError::local(TOKEN_CODE, "The token contains characters which aren't valid within a header".to_string())
+        })?;
+
+        header.set_sensitive(true);
+        Ok(header)
+    }
+}
+
+impl std::fmt::Debug for APIToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            APIToken::Private(_) => f.debug_tuple("Private").field(&REDACTED).finish(),
+            APIToken::Shared(_) => f.debug_tuple("Shared").field(&REDACTED).finish(),
+        }
+    }
+}
+
+impl Serialize for APIToken {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            APIToken::Private(_) => serializer.serialize_newtype_variant("APIToken", 0, "Private", REDACTED),
+            APIToken::Shared(_) => serializer.serialize_newtype_variant("APIToken", 1, "Shared", REDACTED),
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for APIToken {
+    fn drop(&mut self) {
+        use zeroize::Zeroize;
+
+        match self {
+            APIToken::Private(value) | APIToken::Shared(value) => value.zeroize(),
         }
     }
 }
 
 /// The primary wrapping type for interactions with BuiltByBit's API.
 pub struct APIWrapper {
+    /// The client which builds each request, and sends it unless a custom transport is set.
     pub(crate) http_client: Client,
+    pub(crate) transport: Box<dyn HttpTransport>,
+    /// The headers attached to every API request (but not to requests outside of the API).
+    pub(crate) headers: HeaderMap,
+    /// The 'Authorization' header value of the (primary) token, attached alongside the other headers.
+    pub(crate) token: RwLock<HeaderValue>,
+    pub(crate) base_url: Url,
     pub(crate) rate_limit_store: RateLimitStore,
+    /// The number of times a single request may be rate limited before giving up, or `None` to retry indefinitely.
+    pub(crate) max_rate_limit_retries: Option<u32>,
+    /// The total duration a single request may spend rate limited before giving up, or `None` to wait indefinitely.
+    pub(crate) rate_limit_deadline: Option<Duration>,
+    /// The policy under which failed requests are retried, or `None` to never retry them.
+    pub(crate) retry_policy: Option<Box<dyn RetryPolicy + Send + Sync>>,
+    /// Whether or not the retry policy also applies to writes.
+    pub(crate) retry_writes: bool,
+    /// Limits on the number of reads and writes in flight at once, if any.
+    pub(crate) read_concurrency: Option<Semaphore>,
+    pub(crate) write_concurrency: Option<Semaphore>,
+    /// The interceptors called for every API request, in order of registration.
+    pub(crate) interceptors: Vec<Box<dyn RequestInterceptor>>,
+    pub(crate) stats: StatsCollector,
+    /// The cache of successful reads, if enabled.
+    #[cfg(feature = "cache")]
+    pub(crate) cache: Option<cache::ResponseCache>,
+    /// The cache of member IDs which usernames resolve to, if enabled.
+    pub(crate) usernames: Option<usernames::UsernameCache>,
+    pub(crate) maintenance: AtomicBool,
+    /// Whether or not a running watchdog considers the API unhealthy.
+    pub(crate) unhealthy: AtomicBool,
+    /// The number of watchdogs currently running.
+    pub(crate) watchdogs: AtomicUsize,
+    #[cfg(feature = "fault-injection")]
+    pub(crate) faults: std::sync::Mutex<Option<faults::FaultInjector>>,
 }
 
 impl APIWrapper {
@@ -69,78 +284,188 @@ impl APIWrapper {
     /// fail so we conclude that a construction failure has occured.
     ///
     /// # Example
-    /// ```
+    /// ```no_run
+    /// # use bbb_api_wrapper::{APIToken, APIWrapper};
+    /// # async fn run() {
     /// let token = APIToken::Private(String::from("y6xWrGkAzh8Gp4qBWFMG7tDyB+zB+Lub"));
-    /// let wrapper = APIWrapper::build(token).await.unwrap();
+    /// let wrapper = APIWrapper::new(token).await.unwrap();
     ///
     /// println!("Successfully connected to the API.");
+    /// # }
     /// ```
     pub async fn new(token: APIToken) -> Result<APIWrapper> {
-        let mut default_headers = HeaderMap::new();
-        default_headers.insert("Authorization", token.as_header().parse().expect("token not a valid HeaderValue"));
+        APIWrapper::builder(token).build().await
+    }
 
-        let http_client = ClientBuilder::new().https_only(true).default_headers(default_headers).build().expect("http client build failed");
+    /// Construct a builder for an API wrapper, allowing its HTTP client and construction behaviour to be configured.
+    pub fn builder(token: APIToken) -> APIWrapperBuilder {
+        APIWrapperBuilder::new(token)
+    }
 
-        let wrapper = APIWrapper { http_client, rate_limit_store: RateLimitStore::new() };
-        wrapper.health().await?;
+    /// Construct a builder for an API wrapper which spreads reads across a pool of tokens.
+    pub fn pooled(pool: TokenPool) -> APIWrapperBuilder {
+        APIWrapperBuilder::pooled(pool)
+    }
 
-        Ok(wrapper)
+    /// Replace the token which authenticates subsequent requests (eg. to rotate credentials without restarting).
+    ///
+    /// Requests already sent (or being resent after being rate limited) are unaffected until their next attempt. If
+    /// reads are spread across a pool of tokens, this replaces the primary token.
+    ///
+    /// Any cached responses are removed, as the new token may authenticate as a different account.
+    ///
+    /// Fails if the token contains characters which aren't valid within a header, in which case the current token is
+    /// kept.
+    pub fn replace_token(&self, token: APIToken) -> Result<()> {
+        *self.token.write().unwrap() = token.header_value()?;
+
+        #[cfg(feature = "cache")]
+        self.clear_cache();
+
+        Ok(())
+    }
+
+    /// Returns the base URL onto which all endpoints are joined.
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
     }
 
     /// A raw function which makes a GET request to a specific endpoint.
-    async fn get<D>(&self, endpoint: &str, sort: Option<&SortOptions<'_>>) -> Result<D>
+    async fn get<D>(&self, endpoint: Endpoint<'_>, sort: Option<&SortOptions<'_>>) -> Result<D>
     where
         D: DeserializeOwned,
     {
-        if sort.is_some() {
-            let endpoint = format!("{}?{}", endpoint, &sort.unwrap().to_query_string()?);
-            http::get(self, &endpoint).await?.as_result()
-        } else {
-            http::get(self, endpoint).await?.as_result()
+        let mut url = endpoint.url(&self.base_url);
+
+        if let Some(sort) = sort {
+            sort.append_to(&mut url)?;
         }
+
+        http::get(self, &url).await?.into_result()
+    }
+
+    /// A raw function which makes a GET request to an endpoint that responds with a non-JSON body on success.
+    async fn get_response(&self, endpoint: Endpoint<'_>) -> Result<reqwest::Response> {
+        http::get_raw(self, &endpoint.url(&self.base_url)).await
+    }
+
+    /// A raw function which makes an unauthenticated GET request to a URL outside of the API.
+    async fn get_external(&self, url: &str) -> Result<bytes::Bytes> {
+        http::get_external(self, url).await
     }
 
     /// A raw function which makes a POST request to a specific endpoint.
-    async fn post<D, B>(&self, endpoint: &str, body: &B) -> Result<D>
+    async fn post<D, B>(&self, endpoint: Endpoint<'_>, body: &B) -> Result<D>
     where
         D: DeserializeOwned,
         B: Serialize,
     {
-        http::post(self, endpoint, body).await?.as_result()
+        http::post(self, &endpoint.url(&self.base_url), body).await?.into_result()
     }
 
     /// A raw function which makes a PATCH request to a specific endpoint.
-    async fn patch<D, B>(&self, endpoint: &str, body: &B) -> Result<D>
+    async fn patch<D, B>(&self, endpoint: Endpoint<'_>, body: &B) -> Result<D>
     where
         D: DeserializeOwned,
         B: Serialize,
     {
-        http::patch(self, endpoint, body).await?.as_result()
+        http::patch(self, &endpoint.url(&self.base_url), body).await?.into_result()
     }
 
     /// A raw function which makes a DELETE request to a specific endpoint.
-    async fn delete<D>(&self, endpoint: &str) -> Result<D>
+    async fn delete<D>(&self, endpoint: Endpoint<'_>) -> Result<D>
     where
         D: DeserializeOwned,
     {
-        http::delete(self, endpoint).await?.as_result()
+        http::delete(self, &endpoint.url(&self.base_url)).await?.into_result()
     }
 
-    /// Schedule an empty request which we expect to always succeed under nominal conditions.
+    /// Make a GET request to an endpoint this crate doesn't model (yet), returning its data as raw JSON.
+    ///
+    /// The path is relative to the base URL and may include a query string. The request is still rate limited,
+    /// retried, and has its errors handled as with any other.
     ///
     /// # Example
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// let resource = wrapper.get_raw("resources/1").await?;
+    /// println!("Fields not yet modelled: {:?}", resource.get("new_field"));
+    /// # Ok(())
+    /// # }
     /// ```
-    /// wrapper.health().await?;
-    /// println!("Received a successful response from the API.");
+    pub async fn get_raw(&self, path: &str) -> Result<serde_json::Value> {
+        self.get(Endpoint::Raw(path), None).await
+    }
+
+    /// Make a POST request to an endpoint this crate doesn't model (yet), returning its data as raw JSON.
+    ///
+    /// See `get_raw` for how the path is interpreted.
+    pub async fn post_raw<B: Serialize>(&self, path: &str, body: &B) -> Result<serde_json::Value> {
+        self.post(Endpoint::Raw(path), body).await
+    }
+
+    /// Make a request of any method to an endpoint this crate doesn't model (yet), deserialising its data into any
+    /// type.
+    ///
+    /// This is the same layer which every helper method is built upon, so requests are rate limited, retried, cached,
+    /// and intercepted as with any other. The path is relative to the base URL and may include a query string.
+    ///
+    /// POST and PATCH requests must have a body, and GET and DELETE requests mustn't. Sorting and pagination only apply
+    /// to GET requests, and other methods aren't supported by the API.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bbb_api_wrapper::sort::SortOptions;
+    /// # use reqwest::Method;
+    /// # #[derive(serde::Deserialize)]
+    /// # struct WishlistData;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// let sort = SortOptions::default().page(2);
+    /// let wishlist: Vec<WishlistData> =
+    ///     wrapper.request::<_, ()>(Method::GET, "members/wishlist", None, Some(&sort)).await?;
+    /// # Ok(())
+    /// # }
     /// ```
-    pub async fn health(&self) -> Result<()> {
-        let data: String = self.get(&format!("{}/health", BASE_URL), None).await?;
+    pub async fn request<D, B>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+        sort: Option<&SortOptions<'_>>,
+    ) -> Result<D>
+    where
+        D: DeserializeOwned,
+        B: Serialize,
+    {
+        let endpoint = Endpoint::Raw(path);
 
-        if data != "ok" {
-            return Err(APIError::from_raw("HealthEndpointError".to_string(), format!("{} != \"ok\"", data)));
+        match (&method, body) {
+            (&Method::GET, None) => self.get(endpoint, sort).await,
+            (&Method::POST, Some(body)) => self.post(endpoint, body).await,
+            (&Method::PATCH, Some(body)) => self.patch(endpoint, body).await,
+            (&Method::DELETE, None) => self.delete(endpoint).await,
+            (&Method::GET | &Method::DELETE, Some(_)) => {
+                Err(APIError::local(REQUEST_CODE, format!("{} requests can't have a body", method)))
+            }
+            (&Method::POST | &Method::PATCH, None) => {
+                Err(APIError::local(REQUEST_CODE, format!("{} requests must have a body", method)))
+            }
+            _ => Err(APIError::local(REQUEST_CODE, format!("{} requests aren't supported", method))),
         }
+    }
 
-        Ok(())
+    /// Schedule an empty request which we expect to always succeed under nominal conditions.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// wrapper.health().await?;
+    /// println!("Received a successful response from the API.");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn health(&self) -> Result<()> {
+        check_health(self.get(Endpoint::Health, None).await?)
     }
 
     /// Schedule an empty request and measure how long the API took to respond.
@@ -151,8 +476,11 @@ impl APIWrapper {
     /// be argued that the returned duration will be more representative of the true latencies experienced.
     ///
     /// # Example
-    /// ```
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
     /// println!("Took {}ms for the API to respond.", wrapper.ping().await?.as_millis());
+    /// # Ok(())
+    /// # }
     /// ```
     pub async fn ping(&self) -> Result<Duration> {
         let time = Instant::now();
@@ -160,6 +488,254 @@ impl APIWrapper {
         Ok(time.elapsed())
     }
 
+    /// Construct a watchdog which periodically checks the API's health, to be run alongside other work.
+    pub fn health_watchdog(&self) -> HealthWatchdog<'_> {
+        HealthWatchdog::new(self)
+    }
+
+    /// Returns whether or not the API is considered healthy by a running watchdog (see `APIWrapper::health_watchdog`).
+    ///
+    /// This is always true if no watchdog is running, so jobs can check it before each run to pause during outages.
+    pub fn is_healthy(&self) -> bool {
+        !self.unhealthy.load(Ordering::Acquire)
+    }
+
+    /// Probe the API's health several times, a fixed interval apart, and return latency statistics along with how much
+    /// of each probe was spent stalled locally versus awaiting the API.
+    ///
+    /// This distinguishes slowness of the API itself from our own rate limiting, which `ping` doesn't. Probes aren't
+    /// retried, so the first which fails is returned as an error.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// let stats = wrapper.ping_stats(10, Duration::from_millis(500)).await?;
+    /// println!("p95 of {}ms, of which {}ms was stalled locally.", stats.p95.as_millis(), stats.stalled.as_millis());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn ping_stats(&self, samples: u32, interval: Duration) -> Result<PingStats> {
+        let url = Endpoint::Health.url(&self.base_url);
+        let mut probes = Vec::with_capacity(samples as usize);
+
+        for sample in 0..samples {
+            if sample > 0 {
+                runtime::sleep(interval).await;
+            }
+
+            let time = Instant::now();
+            let (response, timings) = http::get_timed(self, &url).await?;
+            check_health(response.into_result()?)?;
+
+            probes.push((time.elapsed(), timings.stalled, timings.network));
+        }
+
+        Ok(PingStats::from_probes(&probes))
+    }
+
+    /// Returns whether or not the most recent response from the API indicated that it's down for maintenance.
+    pub fn is_in_maintenance(&self) -> bool {
+        self.maintenance.load(Ordering::Acquire)
+    }
+
+    /// Wait until the API is available, for at most a maximum duration.
+    ///
+    /// The `health` endpoint is polled for as long as it fails with a retryable error (eg. maintenance downtime or a
+    /// connection failure). This allows long-running jobs to sleep through planned downtime rather than failing. An
+    /// error is returned immediately if it isn't retryable, or if the API is still unavailable once the maximum
+    /// duration has elapsed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// wrapper.wait_until_available(Duration::from_secs(60 * 60)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn wait_until_available(&self, max: Duration) -> Result<()> {
+        const POLL_INTERVAL: Duration = Duration::from_secs(30);
+        let deadline = Instant::now() + max;
+
+        loop {
+            match self.health().await {
+                Err(error) if error.is_retryable() => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+
+                    if remaining.is_zero() {
+                        return Err(error);
+                    }
+
+                    runtime::sleep(remaining.min(POLL_INTERVAL)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Fetch the overall status reported by an external status page, given the URL of its `status.json` summary.
+    ///
+    /// This request is unauthenticated and doesn't count towards the API's rate limits.
+    pub async fn fetch_status(&self, status_url: &str) -> Result<ServiceStatus> {
+        status::fetch(self, status_url).await
+    }
+
+    /// Diagnose why requests may be failing, distinguishing a rejected token from an API-wide outage.
+    ///
+    /// If the API can't be reached and a status page URL is given, the status page is consulted to determine whether
+    /// an outage has been reported.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bbb_api_wrapper::status::Verdict;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper, status_url: &str) {
+    /// match wrapper.diagnose(Some(status_url)).await.verdict {
+    ///     Verdict::TokenRejected => println!("Our token is invalid or lacks permissions."),
+    ///     Verdict::ApiDown => println!("The API is down, so we'll try again later."),
+    ///     _ => {}
+    /// }
+    /// # }
+    /// ```
+    pub async fn diagnose(&self, status_url: Option<&str>) -> Diagnosis {
+        status::diagnose(self, status_url).await
+    }
+
+    /// Cap the number of read requests made per minute below the API's own limit, or remove the cap with `None`.
+    ///
+    /// This is useful when multiple applications share a token and a low-priority one shouldn't consume the budget of
+    /// others. Requests over the cap are stalled locally until they fit within a rolling one-minute window.
+    pub fn set_max_reads_per_minute(&self, per_minute: Option<u64>) {
+        self.rate_limit_store.set_read_ceiling(per_minute);
+    }
+
+    /// Cap the number of write requests made per minute below the API's own limit, or remove the cap with `None`.
+    pub fn set_max_writes_per_minute(&self, per_minute: Option<u64>) {
+        self.rate_limit_store.set_write_ceiling(per_minute);
+    }
+
+    /// Proactively pace read requests to a known per-minute budget, or stop doing so with `None`.
+    ///
+    /// Unlike the API's own rate limiting (which we only learn of once a request is rejected), requests are spaced
+    /// evenly according to a token bucket so that the limit is never hit. Up to `burst` requests may be sent at once
+    /// after a period of inactivity.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) {
+    /// // Send at most one read per second, such that a large batch job never sees a rate limit response.
+    /// wrapper.set_read_budget(Some(60), 1);
+    /// # }
+    /// ```
+    pub fn set_read_budget(&self, per_minute: Option<u64>, burst: u64) {
+        self.rate_limit_store.set_read_budget(per_minute, burst);
+    }
+
+    /// Proactively pace write requests to a known per-minute budget, or stop doing so with `None`.
+    ///
+    /// See `set_read_budget` for how requests are paced.
+    pub fn set_write_budget(&self, per_minute: Option<u64>, burst: u64) {
+        self.rate_limit_store.set_write_budget(per_minute, burst);
+    }
+
+    /// Inject artificial latency and failures into all subsequent requests, or stop doing so with `None`.
+    ///
+    /// Setting a configuration restarts its pseudo-random sequence from the configured seed.
+    #[cfg(feature = "fault-injection")]
+    pub fn set_fault_injection(&self, config: Option<faults::FaultInjection>) {
+        *self.faults.lock().unwrap() = config.map(faults::FaultInjector::new);
+    }
+
+    /// Remove every response held within the cache, if enabled.
+    #[cfg(feature = "cache")]
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Remove the cached responses of an endpoint and any endpoints beneath it (regardless of their query strings), if
+    /// the cache is enabled.
+    ///
+    /// The path is relative to the base URL, so `resources/1` invalidates the cached details of that resource as well
+    /// as its cached updates, reviews, and so on.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # fn run(wrapper: bbb_api_wrapper::APIWrapper) {
+    /// // A resource was edited via the website, so its cached details are now stale.
+    /// wrapper.invalidate_cache("resources/1");
+    /// # }
+    /// ```
+    #[cfg(feature = "cache")]
+    pub fn invalidate_cache(&self, path: &str) {
+        if let Some(cache) = &self.cache {
+            cache.invalidate(&Endpoint::Raw(path).url(&self.base_url));
+        }
+    }
+
+    /// Take a serialisable snapshot of the wrapper's internal state, such as its throttling status and request counts.
+    ///
+    /// This makes no requests and is cheap enough to call from an application's own health endpoint.
+    pub fn debug_snapshot(&self) -> DebugSnapshot {
+        debug::snapshot(self)
+    }
+
+    /// Take a snapshot of the per-endpoint request counts, error counts, and latencies recorded since construction.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # fn run(wrapper: bbb_api_wrapper::APIWrapper) {
+    /// let total = wrapper.client_stats().total();
+    ///
+    /// if let Some(p99) = total.latency.quantile(0.99) {
+    ///     println!("{} requests ({} failed), 99% within {}ms.", total.requests, total.errors, p99.as_millis());
+    /// }
+    /// # }
+    /// ```
+    pub fn client_stats(&self) -> ClientStats {
+        self.stats.snapshot()
+    }
+
+    /// Probe which endpoint families the current token is permitted to use, so that actions which would always be
+    /// rejected can be hidden.
+    ///
+    /// This makes one read request per family. An error is returned if a probe fails for a reason unrelated to
+    /// permissions (eg. the API being unreachable).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// if wrapper.capabilities().await?.licenses.is_available() {
+    ///     println!("This token can manage licenses.");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn capabilities(&self) -> Result<Capabilities> {
+        capabilities::probe_all(self).await
+    }
+
+    /// Check whether the API still matches the version and response shapes this crate was built against.
+    ///
+    /// This makes one read request. Fields added by the API are reported as warnings, whereas an unsupported version
+    /// or a response which no longer deserialises is reported as a mismatch.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// let report = wrapper.compatibility_check().await?;
+    ///
+    /// if !report.is_compatible() {
+    ///     eprintln!("The API has changed in a breaking way: {:?}", report.issues);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn compatibility_check(&self) -> Result<CompatibilityReport> {
+        compatibility::check(self).await
+    }
+
     /// Fetch a snapshot of metrics values from the prior minute along with refresh interval metadata.
     ///
     /// # Note
@@ -167,7 +743,51 @@ impl APIWrapper {
     /// picture of the API's current load. As a result of its purpose, the relevant endpoint (and thus, this method)
     /// is only accessible to staff members.
     pub async fn metrics(&self) -> Result<MetricsSnapshot> {
-        self.get(&format!("{}/metrics", BASE_URL), None).await
+        self.get(Endpoint::Metrics, None).await
+    }
+
+    /// Run a set of requests in order, returning each of their results along with a summary rather than stopping at the
+    /// first failure.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) {
+    /// let licenses = wrapper.resources().licenses();
+    /// let report = wrapper.execute_many((1..=3).map(|id| licenses.fetch(1, id))).await;
+    ///
+    /// println!("{} succeeded, {} failed.", report.summary.succeeded, report.summary.failed);
+    /// # }
+    /// ```
+    pub async fn execute_many<T, I, F>(&self, requests: I) -> BatchReport<T>
+    where
+        I: IntoIterator<Item = F>,
+        F: Future<Output = Result<T>>,
+    {
+        batch::execute(requests).await
+    }
+
+    /// Run a set of requests in order, spreading their start times evenly across a window rather than front-loading
+    /// them against the rate limiter.
+    ///
+    /// This suits large, non-urgent jobs which would otherwise consume the rate limit budget shared with other work.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) {
+    /// let licenses = wrapper.resources().licenses();
+    /// let requests = (1..=500).map(|id| licenses.fetch(1, id));
+    ///
+    /// let report = wrapper.execute_spread(requests, Duration::from_secs(60 * 60)).await;
+    /// println!("{} succeeded, {} failed.", report.summary.succeeded, report.summary.failed);
+    /// # }
+    /// ```
+    pub async fn execute_spread<T, I, F>(&self, requests: I, window: Duration) -> BatchReport<T>
+    where
+        I: IntoIterator<Item = F>,
+        F: Future<Output = Result<T>>,
+    {
+        batch::execute_spread(requests, window).await
     }
 
     /// Construct and return a resource helper type wrapping this instance.
@@ -195,3 +815,12 @@ impl APIWrapper {
         MembersHelper { wrapper: self }
     }
 }
+
+/// Check that the `health` endpoint responded as expected.
+fn check_health(data: String) -> Result<()> {
+    if data != "ok" {
+        return Err(APIError::from_raw("HealthEndpointError".to_string(), format!("{} != \"ok\"", data)));
+    }
+
+    Ok(())
+}
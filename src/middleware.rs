@@ -0,0 +1,58 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Hooks into every request sent to the API, for cross-cutting concerns such as custom headers, request IDs, or audit
+//! logging.
+
+use crate::error::Result;
+
+use reqwest::{Request, Response};
+
+/// An interceptor of the requests sent to the API and the responses received, registered via
+/// `APIWrapperBuilder::with_middleware`.
+///
+/// Interceptors are called in the order they were registered, once per attempt at sending a request (ie. again if a
+/// request is resent after being rate limited or retried). Requests made outside of the API (eg. fetching resource
+/// media) aren't intercepted.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::middleware::RequestInterceptor;
+/// # use bbb_api_wrapper::{APIToken, APIWrapper};
+/// # use reqwest::{Request, Response};
+/// # use std::sync::atomic::{AtomicU64, Ordering};
+/// struct AuditLog {
+///     next_id: AtomicU64,
+/// }
+///
+/// impl RequestInterceptor for AuditLog {
+///     fn on_request(&self, request: &mut Request) -> bbb_api_wrapper::error::Result<()> {
+///         let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+///         request.headers_mut().insert("X-Request-Id", id.into());
+///         Ok(())
+///     }
+///
+///     fn on_response(&self, response: &Response) {
+///         println!("{} {}", response.status(), response.url());
+///     }
+/// }
+///
+/// # async fn run(token: APIToken) -> bbb_api_wrapper::error::Result<()> {
+/// let wrapper = APIWrapper::builder(token).with_middleware(AuditLog { next_id: AtomicU64::new(0) }).build().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait RequestInterceptor: Send + Sync {
+    /// Inspect or modify a request just before it's sent, once it has passed the rate limiter.
+    ///
+    /// Returning an error fails the request without sending it.
+    fn on_request(&self, request: &mut Request) -> Result<()> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Observe a response as soon as it's received, before the wrapper handles it.
+    fn on_response(&self, response: &Response) {
+        let _ = response;
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! An in-memory mock of the API, so that downstream crates can test code built on the wrapper without network access
+//! or a real token.
+//!
+//! The mock serves canned responses keyed by method and endpoint, and records every request it receives so that tests
+//! can assert on the writes made.
+//!
+//! # Example
+//! ```
+//! # use bbb_api_wrapper::data::resources::LicenseModifyPermData;
+//! # use bbb_api_wrapper::mock::MockTransport;
+//! # use reqwest::Method;
+//! # use std::sync::Arc;
+//! # async fn run() -> bbb_api_wrapper::error::Result<()> {
+//! let mock = Arc::new(MockTransport::with_fixtures());
+//! mock.route_data(Method::PATCH, "resources/1/licenses/1", &())?;
+//!
+//! let wrapper = bbb_api_wrapper::mock::wrapper(&mock).await?;
+//! let licenses = wrapper.resources().licenses();
+//!
+//! let license = licenses.fetch_by_member(1, 1337).await?;
+//! let fields = LicenseModifyPermData { permanent: true, active: false };
+//! licenses.modify_permanent(1, *license.license_id(), &fields).await?;
+//!
+//! let write = mock.requests().into_iter().find(|request| request.method == Method::PATCH).unwrap();
+//! assert_eq!(Some(serde_json::json!({ "permanent": true, "active": false })), write.body);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::transport::{self, HttpTransport, TransportFuture};
+use crate::{APIToken, APIWrapper};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use reqwest::{Method, Request, Response, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+
+/// The base URL the mock serves, which is never actually connected to.
+const MOCK_BASE_URL: &str = "http://mock.invalid/v1";
+
+/// Realistic responses to the read endpoints, keyed by endpoint.
+///
+/// A value which is the name of another endpoint is served as that endpoint's response (eg. a member fetched by ID and
+/// by name).
+const FIXTURES: &str = include_str!("fixtures.json");
+
+/// A canned response to a single endpoint.
+#[derive(Debug, Clone)]
+enum MockResponse {
+    Data(Value),
+    Error { status: StatusCode, code: String, message: String },
+}
+
+/// A request received by the mock.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: Method,
+    /// The endpoint relative to the base URL (eg. `resources/1/licenses`).
+    pub path: String,
+    pub query: Option<String>,
+    /// The JSON body of the request, if any.
+    pub body: Option<Value>,
+}
+
+/// A transport which serves canned responses from memory.
+///
+/// Requests to endpoints without a response are rejected as the API would reject a missing resource (with a
+/// `ContentNotFoundError`). List responses are served in full on their first page and as empty on any later page.
+pub struct MockTransport {
+    routes: Mutex<HashMap<(Method, String), MockResponse>>,
+    requests: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MockTransport {
+    /// Construct a mock which only serves the `health` endpoint.
+    pub fn new() -> MockTransport {
+        let mock = MockTransport { routes: Mutex::new(HashMap::new()), requests: Mutex::new(Vec::new()) };
+        mock.route(Method::GET, "health", MockResponse::Data(Value::String("ok".to_string())));
+        mock
+    }
+
+    /// Construct a mock preloaded with realistic responses to the read endpoints of an example member (#87939) and
+    /// their two resources (#1 being a premium plugin with purchases, licenses, reviews, and so on).
+    pub fn with_fixtures() -> MockTransport {
+        let mock = MockTransport::new();
+        let fixtures: HashMap<String, Value> = serde_json::from_str(FIXTURES).expect("fixtures not valid JSON");
+
+        for (path, data) in &fixtures {
+            let data = match data {
+                Value::String(alias) => fixtures.get(alias).unwrap_or(data),
+                _ => data,
+            };
+
+            mock.route(Method::GET, path, MockResponse::Data(data.clone()));
+        }
+
+        mock
+    }
+
+    /// Respond to an endpoint (eg. `resources/1`) with some data, replacing any existing response.
+    pub fn route_data<T: Serialize>(&self, method: Method, path: &str, data: &T) -> Result<()> {
+        self.route(method, path, MockResponse::Data(serde_json::to_value(data)?));
+        Ok(())
+    }
+
+    /// Respond to an endpoint with an error, replacing any existing response.
+    pub fn route_error(&self, method: Method, path: &str, status: StatusCode, code: &str, message: &str) {
+        let error = MockResponse::Error { status, code: code.to_string(), message: message.to_string() };
+        self.route(method, path, error);
+    }
+
+    fn route(&self, method: Method, path: &str, response: MockResponse) {
+        self.routes.lock().unwrap().insert((method, path.trim_matches('/').to_string()), response);
+    }
+
+    /// Returns every request received so far, in the order they were received.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn respond(&self, request: &Request) -> Result<Response> {
+        let path = request.url().path().strip_prefix("/v1").unwrap_or(request.url().path()).trim_matches('/');
+        let query = request.url().query().map(str::to_string);
+        let body = request.body().and_then(|body| body.as_bytes()).and_then(|body| serde_json::from_slice(body).ok());
+
+        self.requests.lock().unwrap().push(RecordedRequest {
+            method: request.method().clone(),
+            path: path.to_string(),
+            query,
+            body,
+        });
+
+        let later_page = request.url().query_pairs().any(|(key, value)| key == "page" && value != "1");
+        let response = self.routes.lock().unwrap().get(&(request.method().clone(), path.to_string())).cloned();
+
+        match response {
+            Some(MockResponse::Data(Value::Array(_))) if later_page => {
+                transport::respond_with_data(request, &Value::Array(Vec::new()))
+            }
+            Some(MockResponse::Data(data)) => transport::respond_with_data(request, &data),
+            Some(MockResponse::Error { status, code, message }) => {
+                Ok(transport::respond_with_error(request, status, &code, &message))
+            }
+            None => {
+                let message = format!("No mock response for {} {}", request.method(), path);
+                Ok(transport::respond_with_error(request, StatusCode::NOT_FOUND, "ContentNotFoundError", &message))
+            }
+        }
+    }
+}
+
+impl Default for MockTransport {
+    fn default() -> Self {
+        MockTransport::new()
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn execute(&self, request: Request) -> TransportFuture<'_> {
+        Box::pin(async move { self.respond(&request) })
+    }
+}
+
+/// Construct a wrapper whose requests are all served by a mock.
+///
+/// The wrapper doesn't retry failed requests, so that errors routed on the mock surface immediately.
+pub async fn wrapper(mock: &Arc<MockTransport>) -> Result<APIWrapper> {
+    APIWrapper::builder(APIToken::Private("mock".to_string()))
+        .base_url(MOCK_BASE_URL.parse().expect("mock base URL not valid"))
+        .transport(Arc::clone(mock))
+        .without_retries()
+        .build()
+        .await
+}
@@ -0,0 +1,119 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+use crate::data::conversations::{ConversationData, ReplyData};
+use crate::data::members::MemberData;
+use crate::error::Result;
+use crate::pagination;
+use crate::sort::SortOptions;
+use crate::stream::IdWatch;
+use crate::transcript::Transcript;
+use crate::APIWrapper;
+
+use std::time::Duration;
+
+/// A handle to a conversation, holding its most recently fetched data.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::objects::Conversation;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// for data in wrapper.conversations().list_all_unread().await? {
+///     let mut conversation = Conversation::new(&wrapper, data);
+///
+///     for recipient in conversation.recipients().await? {
+///         println!("Replying to {}.", recipient.username());
+///     }
+///
+///     conversation.reply("Thanks for getting in touch, we'll be with you shortly.").await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Conversation<'a> {
+    wrapper: &'a APIWrapper,
+    data: ConversationData,
+    recipients: Option<Vec<MemberData>>,
+}
+
+impl<'a> Conversation<'a> {
+    pub fn new(wrapper: &'a APIWrapper, data: ConversationData) -> Conversation<'a> {
+        Conversation { wrapper, data, recipients: None }
+    }
+
+    pub fn id(&self) -> u64 {
+        *self.data.conversation_id()
+    }
+
+    /// Returns the conversation's data as of when it was last fetched.
+    pub fn data(&self) -> &ConversationData {
+        &self.data
+    }
+
+    pub fn into_data(self) -> ConversationData {
+        self.data
+    }
+
+    /// Fetch the conversation's data afresh, returning whether or not it was found.
+    ///
+    /// # Note
+    /// The API has no endpoint for fetching a single conversation, so the unread conversations are searched. If the
+    /// conversation has since been read, it can't be found and its data is left as it was.
+    pub async fn refresh(&mut self) -> Result<bool> {
+        let (wrapper, conversation_id) = (self.wrapper, self.id());
+        let is_match = |conversation: &ConversationData| *conversation.conversation_id() == conversation_id;
+
+        let conversations = pagination::collect_until(
+            |page| async move { wrapper.conversations().list_unread(Some(&SortOptions::default().page(page))).await },
+            |page| page.iter().any(is_match),
+        )
+        .await?;
+
+        match conversations.into_iter().find(is_match) {
+            Some(data) => {
+                self.data = data;
+                self.recipients = None;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub async fn replies(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<ReplyData>> {
+        self.wrapper.conversations().list_replies(self.id(), sort).await
+    }
+
+    /// List every reply by walking pages until an empty one is returned.
+    pub async fn all_replies(&self) -> Result<Vec<ReplyData>> {
+        self.wrapper.conversations().list_all_replies(self.id()).await
+    }
+
+    pub async fn reply(&self, message: &str) -> Result<u64> {
+        self.wrapper.conversations().reply(self.id(), message).await
+    }
+
+    /// Watch for new replies by polling on an interval.
+    pub fn watch_replies(&self, interval: Duration) -> IdWatch<'a, ReplyData> {
+        self.wrapper.conversations().watch_replies(self.id(), interval)
+    }
+
+    /// Fetch every reply and arrange them into a transcript.
+    pub async fn transcript(&self) -> Result<Transcript> {
+        self.wrapper.conversations().transcript(&self.data).await
+    }
+
+    /// Returns the members the conversation is with, fetched on first use and then cached until the next refresh.
+    pub async fn recipients(&mut self) -> Result<&[MemberData]> {
+        if self.recipients.is_none() {
+            let mut recipients = Vec::with_capacity(self.data.recipient_ids().len());
+
+            for recipient_id in self.data.recipient_ids() {
+                recipients.push(self.wrapper.members().fetch_by_id(*recipient_id).await?);
+            }
+
+            self.recipients = Some(recipients);
+        }
+
+        Ok(self.recipients.as_deref().unwrap_or_default())
+    }
+}
@@ -0,0 +1,68 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+use crate::data::members::{BanData, MemberData};
+use crate::error::Result;
+use crate::APIWrapper;
+
+/// A handle to a member, holding their most recently fetched data.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::objects::Member;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let member = Member::fetch_by_name(&wrapper, "Harry").await?;
+///
+/// if let Some(ban) = member.ban().await? {
+///     println!("{} was banned: {}", member.data().username(), ban.reason());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Member<'a> {
+    wrapper: &'a APIWrapper,
+    data: MemberData,
+}
+
+impl<'a> Member<'a> {
+    pub fn new(wrapper: &'a APIWrapper, data: MemberData) -> Member<'a> {
+        Member { wrapper, data }
+    }
+
+    pub async fn fetch(wrapper: &'a APIWrapper, member_id: u64) -> Result<Member<'a>> {
+        Ok(Member::new(wrapper, wrapper.members().fetch_by_id(member_id).await?))
+    }
+
+    pub async fn fetch_by_name(wrapper: &'a APIWrapper, member_name: &str) -> Result<Member<'a>> {
+        Ok(Member::new(wrapper, wrapper.members().fetch_by_name(member_name).await?))
+    }
+
+    /// Fetch the authenticated member.
+    pub async fn fetch_self(wrapper: &'a APIWrapper) -> Result<Member<'a>> {
+        Ok(Member::new(wrapper, wrapper.members().fetch_self().await?))
+    }
+
+    pub fn id(&self) -> u64 {
+        *self.data.member_id()
+    }
+
+    /// Returns the member's data as of when it was last fetched.
+    pub fn data(&self) -> &MemberData {
+        &self.data
+    }
+
+    pub fn into_data(self) -> MemberData {
+        self.data
+    }
+
+    /// Fetch the member's data afresh.
+    pub async fn refresh(&mut self) -> Result<()> {
+        self.data = self.wrapper.members().fetch_by_id(self.id()).await?;
+        Ok(())
+    }
+
+    /// Fetch the member's ban, or `None` if they aren't banned.
+    pub async fn ban(&self) -> Result<Option<BanData>> {
+        self.wrapper.members().fetch_ban(self.id()).await
+    }
+}
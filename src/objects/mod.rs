@@ -0,0 +1,18 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Handles which pair the data of an object with the wrapper it was fetched by, so that an object can be passed
+//! around and operated on as one value rather than as a (wrapper, ID) pair.
+//!
+//! Each handle is a thin layer over the helpers, so requests made through one are rate limited, retried, and cached
+//! as any other.
+
+pub mod conversation;
+pub mod member;
+pub mod resource;
+pub mod thread;
+
+pub use conversation::Conversation;
+pub use member::Member;
+pub use resource::Resource;
+pub use thread::Thread;
@@ -0,0 +1,107 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+use crate::data::resources::{
+    DownloadData, LicenseData, PurchaseData, ResourceData, ResourceModifyData, ReviewData, UpdateData, VersionData,
+};
+use crate::error::Result;
+use crate::objects::Member;
+use crate::sort::SortOptions;
+use crate::APIWrapper;
+
+/// A handle to a resource, holding its most recently fetched data.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::objects::Resource;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let resource = Resource::fetch(&wrapper, 1).await?;
+///
+/// for review in resource.reviews(None).await? {
+///     if *review.rating() <= 2 {
+///         resource.respond_to_review(*review.review_id(), "Sorry to hear that, please get in touch!").await?;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Resource<'a> {
+    wrapper: &'a APIWrapper,
+    data: ResourceData,
+}
+
+impl<'a> Resource<'a> {
+    pub fn new(wrapper: &'a APIWrapper, data: ResourceData) -> Resource<'a> {
+        Resource { wrapper, data }
+    }
+
+    pub async fn fetch(wrapper: &'a APIWrapper, resource_id: u64) -> Result<Resource<'a>> {
+        Ok(Resource::new(wrapper, wrapper.resources().fetch(resource_id).await?))
+    }
+
+    pub fn id(&self) -> u64 {
+        *self.data.resource_id()
+    }
+
+    /// Returns the resource's data as of when it was last fetched.
+    pub fn data(&self) -> &ResourceData {
+        &self.data
+    }
+
+    pub fn into_data(self) -> ResourceData {
+        self.data
+    }
+
+    /// Fetch the resource's data afresh.
+    pub async fn refresh(&mut self) -> Result<()> {
+        self.data = self.wrapper.resources().fetch(self.id()).await?;
+        Ok(())
+    }
+
+    /// Modify the resource, keeping the data it has afterwards.
+    pub async fn modify(&mut self, fields: &ResourceModifyData<'_>) -> Result<()> {
+        self.data = self.wrapper.resources().modify(self.id(), fields).await?;
+        Ok(())
+    }
+
+    /// Fetch the resource's author.
+    pub async fn author(&self) -> Result<Member<'a>> {
+        Member::fetch(self.wrapper, *self.data.author_id()).await
+    }
+
+    pub async fn downloads(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<DownloadData>> {
+        self.wrapper.resources().downloads().list(self.id(), sort).await
+    }
+
+    pub async fn licenses(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<LicenseData>> {
+        self.wrapper.resources().licenses().list(self.id(), sort).await
+    }
+
+    pub async fn purchases(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<PurchaseData>> {
+        self.wrapper.resources().purchases().list(self.id(), sort).await
+    }
+
+    pub async fn reviews(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<ReviewData>> {
+        self.wrapper.resources().reviews().list(self.id(), sort).await
+    }
+
+    pub async fn respond_to_review(&self, review_id: u64, message: &str) -> Result<()> {
+        self.wrapper.resources().reviews().respond(self.id(), review_id, message).await
+    }
+
+    pub async fn updates(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<UpdateData>> {
+        self.wrapper.resources().updates().list(self.id(), sort).await
+    }
+
+    pub async fn latest_update(&self) -> Result<UpdateData> {
+        self.wrapper.resources().updates().latest(self.id()).await
+    }
+
+    pub async fn versions(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<VersionData>> {
+        self.wrapper.resources().versions().list(self.id(), sort).await
+    }
+
+    pub async fn latest_version(&self) -> Result<VersionData> {
+        self.wrapper.resources().versions().latest(self.id()).await
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+use crate::data::threads::{ReplyData, ThreadData};
+use crate::error::Result;
+use crate::sort::SortOptions;
+use crate::stream::IdWatch;
+use crate::APIWrapper;
+
+use std::time::Duration;
+
+/// A handle to a thread, holding its most recently fetched data.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::objects::Thread;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let thread = Thread::fetch(&wrapper, 1).await?;
+///
+/// if *thread.data().thread_open() {
+///     thread.reply("A new version has been released!").await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Thread<'a> {
+    wrapper: &'a APIWrapper,
+    data: ThreadData,
+}
+
+impl<'a> Thread<'a> {
+    pub fn new(wrapper: &'a APIWrapper, data: ThreadData) -> Thread<'a> {
+        Thread { wrapper, data }
+    }
+
+    pub async fn fetch(wrapper: &'a APIWrapper, thread_id: u64) -> Result<Thread<'a>> {
+        Ok(Thread::new(wrapper, wrapper.threads().fetch_thread(thread_id).await?))
+    }
+
+    pub fn id(&self) -> u64 {
+        *self.data.thread_id()
+    }
+
+    /// Returns the thread's data as of when it was last fetched.
+    pub fn data(&self) -> &ThreadData {
+        &self.data
+    }
+
+    pub fn into_data(self) -> ThreadData {
+        self.data
+    }
+
+    /// Fetch the thread's data afresh.
+    pub async fn refresh(&mut self) -> Result<()> {
+        self.data = self.wrapper.threads().fetch_thread(self.id()).await?;
+        Ok(())
+    }
+
+    pub async fn replies(&self, sort: Option<&SortOptions<'_>>) -> Result<Vec<ReplyData>> {
+        self.wrapper.threads().list_replies(self.id(), sort).await
+    }
+
+    /// List every reply by walking pages until an empty one is returned.
+    pub async fn all_replies(&self) -> Result<Vec<ReplyData>> {
+        self.wrapper.threads().list_all_replies(self.id()).await
+    }
+
+    pub async fn reply(&self, message: &str) -> Result<u64> {
+        self.wrapper.threads().reply(self.id(), message).await
+    }
+
+    /// Watch for new replies by polling on an interval.
+    pub fn watch_replies(&self, interval: Duration) -> IdWatch<'a, ReplyData> {
+        self.wrapper.threads().watch_replies(self.id(), interval)
+    }
+}
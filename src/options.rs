@@ -0,0 +1,151 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! One-off overrides of the wrapper's request behaviour (eg. a short timeout for an interactive license check versus a
+//! long-running bulk export).
+//!
+//! Options are scoped to a future rather than passed to each helper method, so any helper call (or sequence of calls)
+//! can be overridden without changing its signature.
+
+use crate::retry::RetryPolicy;
+
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+thread_local! {
+    /// The options of the scope currently being polled on this thread, if any.
+    static CURRENT: RefCell<Option<RequestOptions>> = const { RefCell::new(None) };
+}
+
+/// The class of a request, which decides whose requests are sent first when the rate budget is tight.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Priority {
+    /// A request which someone is waiting on (eg. a license verification), sent ahead of any background requests.
+    #[default]
+    Interactive,
+    /// A request made by bulk or scheduled work (eg. backfilling historical purchases), which yields to interactive
+    /// requests of the same type whilst any are stalled.
+    Background,
+}
+
+/// Overrides applied to every request made within a scope.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::options::RequestOptions;
+/// # use std::time::Duration;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let options = RequestOptions::default().with_timeout(Duration::from_secs(2)).without_retries();
+/// let license = options.scope(wrapper.resources().licenses().fetch_by_member(1, 87939)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct RequestOptions {
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) retry_policy: Option<Option<Arc<dyn RetryPolicy + Send + Sync>>>,
+    pub(crate) priority: Option<Priority>,
+    #[cfg(feature = "cache")]
+    pub(crate) bypass_cache: Option<bool>,
+}
+
+impl RequestOptions {
+    /// Set the maximum duration of each attempt of each request, overriding any timeout set on the wrapper.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry failed requests (including writes) under a policy, rather than the wrapper's.
+    pub fn with_retry_policy<P>(mut self, retry_policy: P) -> Self
+    where
+        P: RetryPolicy + Send + Sync + 'static,
+    {
+        self.retry_policy = Some(Some(Arc::new(retry_policy)));
+        self
+    }
+
+    /// Never retry failed requests (other than those which were rate limited).
+    pub fn without_retries(mut self) -> Self {
+        self.retry_policy = Some(None);
+        self
+    }
+
+    /// Set the priority of each request (interactive by default).
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Send every read to the API rather than serving it from the wrapper's response cache (the fresh response is
+    /// still cached for later reads).
+    #[cfg(feature = "cache")]
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = Some(true);
+        self
+    }
+
+    /// Apply these options to every request made by a future.
+    ///
+    /// Scopes may be nested, in which case the options of the inner scope take precedence where set.
+    pub fn scope<F: Future>(self, future: F) -> Scoped<F> {
+        Scoped { options: self, future: Box::pin(future) }
+    }
+
+    /// Combine with the options of an outer scope, preferring our own where set.
+    fn over(self, outer: Option<&RequestOptions>) -> RequestOptions {
+        match outer {
+            Some(outer) => RequestOptions {
+                timeout: self.timeout.or(outer.timeout),
+                retry_policy: self.retry_policy.or_else(|| outer.retry_policy.clone()),
+                priority: self.priority.or(outer.priority),
+                #[cfg(feature = "cache")]
+                bypass_cache: self.bypass_cache.or(outer.bypass_cache),
+            },
+            None => self,
+        }
+    }
+}
+
+/// A future whose requests are made under a set of options, returned by `RequestOptions::scope`.
+pub struct Scoped<F> {
+    options: RequestOptions,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let this = self.get_mut();
+
+        // The options are installed for the duration of each poll (rather than once), so the future may move between
+        // threads between polls.
+        let previous = CURRENT.with(|current| {
+            let previous = current.borrow_mut().take();
+            *current.borrow_mut() = Some(this.options.clone().over(previous.as_ref()));
+            previous
+        });
+
+        let _restore = Restore(previous);
+        this.future.as_mut().poll(cx)
+    }
+}
+
+/// Reinstates the options of an outer scope once dropped, even if polling the inner future panics.
+struct Restore(Option<RequestOptions>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|current| *current.borrow_mut() = self.0.take());
+    }
+}
+
+/// Returns the options of the scope currently being polled, if any.
+pub(crate) fn current() -> Option<RequestOptions> {
+    CURRENT.with(|current| current.borrow().clone())
+}
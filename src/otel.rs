@@ -0,0 +1,78 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! OpenTelemetry client spans for each request sent to the API, so that its latency shows up within existing traces.
+//!
+//! Spans are created via the globally registered tracer provider as children of the current context, and the trace
+//! context is injected into each request via the globally registered propagator.
+
+use crate::error::Result;
+
+use opentelemetry::global;
+use opentelemetry::propagation::Injector;
+use opentelemetry::trace::{SpanKind, Status, TraceContextExt, Tracer};
+use opentelemetry::{Context, KeyValue};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Request, Response};
+
+/// Injects trace context into the headers of a request.
+struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+impl Injector for HeaderInjector<'_> {
+    fn set(&mut self, key: &str, value: String) {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(key), HeaderValue::try_from(value)) {
+            self.0.insert(name, value);
+        }
+    }
+}
+
+/// Start a client span for a request (named after its method and endpoint) and inject its trace context.
+pub(crate) fn start(request: &mut Request, endpoint: &str) -> Context {
+    let tracer = global::tracer("bbb_api_wrapper");
+
+    let mut attributes = vec![
+        KeyValue::new("http.request.method", request.method().to_string()),
+        KeyValue::new("http.route", endpoint.to_string()),
+        KeyValue::new("url.full", request.url().to_string()),
+    ];
+
+    if let Some(host) = request.url().host_str() {
+        attributes.push(KeyValue::new("server.address", host.to_string()));
+    }
+
+    let span = tracer
+        .span_builder(format!("{} {}", request.method(), endpoint))
+        .with_kind(SpanKind::Client)
+        .with_attributes(attributes)
+        .start_with_context(&tracer, &Context::current());
+
+    let context = Context::current_with_span(span);
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(request.headers_mut()))
+    });
+
+    context
+}
+
+/// End a request's client span with the outcome of sending it.
+pub(crate) fn end(context: &Context, response: &Result<Response>) {
+    let span = context.span();
+
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            span.set_attribute(KeyValue::new("http.response.status_code", i64::from(status.as_u16())));
+
+            if status.is_client_error() || status.is_server_error() {
+                span.set_attribute(KeyValue::new("error.type", status.as_u16().to_string()));
+                span.set_status(Status::error(status.to_string()));
+            }
+        }
+        Err(error) => {
+            span.record_error(error);
+            span.set_status(Status::error(error.to_string()));
+        }
+    }
+
+    span.end();
+}
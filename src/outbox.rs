@@ -0,0 +1,331 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A queue of write operations which is persisted to disk, so that pending writes survive crashes and restarts.
+//!
+//! Operations are written to the outbox file before `push` returns, and are only removed once the API has accepted
+//! them. Delivery is therefore at-least-once: a crash between an operation being accepted and the outbox being
+//! rewritten will cause that operation to be sent again when next flushed.
+
+use crate::data::resources::LicenseModification;
+use crate::error::{APIError, Result};
+use crate::throttler::unix_timestamp;
+use crate::APIWrapper;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// A write operation which can be queued within an outbox.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WriteOperation {
+    ThreadReply { thread_id: u64, message: String },
+    ConversationStart { title: String, message: String, recipient_ids: Vec<u64> },
+    ConversationReply { conversation_id: u64, message: String },
+    ReviewResponse { resource_id: u64, review_id: u64, message: String },
+    LicenseModification { resource_id: u64, license_id: u64, modification: LicenseModification },
+    ProfilePostEdit { profile_post_id: u64, message: String },
+}
+
+impl WriteOperation {
+    /// Send this operation to the API.
+    pub async fn execute(&self, wrapper: &APIWrapper) -> Result<()> {
+        match self {
+            WriteOperation::ThreadReply { thread_id, message } => {
+                wrapper.threads().reply(*thread_id, message).await.map(|_| ())
+            }
+            WriteOperation::ConversationStart { title, message, recipient_ids } => {
+                wrapper.conversations().start(title, message, recipient_ids).await.map(|_| ())
+            }
+            WriteOperation::ConversationReply { conversation_id, message } => {
+                wrapper.conversations().reply(*conversation_id, message).await.map(|_| ())
+            }
+            WriteOperation::ReviewResponse { resource_id, review_id, message } => {
+                wrapper.resources().reviews().respond(*resource_id, *review_id, message).await
+            }
+            WriteOperation::LicenseModification { resource_id, license_id, modification } => {
+                wrapper.resources().licenses().modify(*resource_id, *license_id, modification).await
+            }
+            WriteOperation::ProfilePostEdit { profile_post_id, message } => {
+                wrapper.members().edit_profile_post(*profile_post_id, message).await
+            }
+        }
+    }
+}
+
+/// An operation within an outbox along with its delivery history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: u64,
+    pub operation: WriteOperation,
+    /// The UNIX timestamp (in seconds) at which the operation was queued.
+    pub enqueued_at: u64,
+    /// The UNIX timestamp (in seconds) before which the operation won't be sent, if it was scheduled.
+    #[serde(default)]
+    pub execute_at: Option<u64>,
+    pub attempts: u32,
+    /// The message of the most recent error encountered when sending this operation, if any.
+    pub last_error: Option<String>,
+}
+
+impl OutboxEntry {
+    /// Returns whether or not this entry may be sent at a UNIX timestamp (in seconds).
+    pub fn is_due(&self, now: u64) -> bool {
+        self.execute_at.is_none_or(|execute_at| execute_at <= now)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct OutboxFile {
+    next_id: u64,
+    pending: Vec<OutboxEntry>,
+    dead_letters: Vec<OutboxEntry>,
+}
+
+/// The outcome of flushing an outbox.
+#[derive(Debug, Default)]
+pub struct FlushReport {
+    /// The IDs of entries which were accepted by the API and removed.
+    pub sent: Vec<u64>,
+    /// The entries which were rejected by the API and moved to the dead letters, along with why.
+    pub rejected: Vec<(u64, APIError)>,
+    /// The transient error which stopped the flush early, if any. The entry which encountered it remains pending.
+    pub interrupted: Option<(u64, APIError)>,
+}
+
+/// A persistent queue of write operations.
+///
+/// Operations may be scheduled to be sent at a later time (eg. an update announcement at release time), and the
+/// outbox can be flushed periodically by a [`Scheduler`](crate::scheduler::Scheduler) via `Scheduler::outbox`.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::outbox::{Outbox, WriteOperation};
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let outbox = Outbox::open("outbox.json")?;
+/// outbox.push(WriteOperation::ConversationReply { conversation_id: 1, message: "Thank you!".to_string() })?;
+///
+/// let report = outbox.flush(&wrapper).await?;
+/// println!("Sent {} queued writes, {} remain.", report.sent.len(), outbox.len());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Outbox {
+    path: PathBuf,
+    file: Mutex<OutboxFile>,
+    flushing: AtomicBool,
+}
+
+impl Outbox {
+    /// Open the outbox stored at a path, creating an empty one if the file doesn't exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Outbox> {
+        let path = path.as_ref().to_path_buf();
+
+        let file = match fs::read(&path) {
+            Ok(contents) => serde_json::from_slice(&contents)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => OutboxFile::default(),
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(Outbox { path, file: Mutex::new(file), flushing: AtomicBool::new(false) })
+    }
+
+    /// Queue an operation to be sent on the next flush, returning its ID once it has been persisted.
+    pub fn push(&self, operation: WriteOperation) -> Result<u64> {
+        self.enqueue(operation, None)
+    }
+
+    /// Queue an operation to be sent on the first flush at or after a UNIX timestamp (in seconds), returning its ID
+    /// once it has been persisted.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use bbb_api_wrapper::outbox::{Outbox, WriteOperation};
+    /// # use bbb_api_wrapper::data::resources::LicenseData;
+    /// # fn run(outbox: &Outbox, license: &LicenseData) -> bbb_api_wrapper::error::Result<()> {
+    /// // Remind the purchaser three days before their license expires.
+    /// let reminder = WriteOperation::ConversationStart {
+    ///     title: "Your license is expiring soon".to_string(),
+    ///     message: "Your license expires in three days.".to_string(),
+    ///     recipient_ids: vec![*license.purchaser_id()],
+    /// };
+    ///
+    /// outbox.push_at(reminder, license.end_date().saturating_sub(3 * 86_400))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn push_at(&self, operation: WriteOperation, execute_at: u64) -> Result<u64> {
+        self.enqueue(operation, Some(execute_at))
+    }
+
+    fn enqueue(&self, operation: WriteOperation, execute_at: Option<u64>) -> Result<u64> {
+        let mut file = self.file.lock().unwrap();
+        let id = file.next_id;
+
+        file.next_id += 1;
+        file.pending.push(OutboxEntry {
+            id,
+            operation,
+            enqueued_at: unix_timestamp() / 1000,
+            execute_at,
+            attempts: 0,
+            last_error: None,
+        });
+
+        drop(file);
+        self.persist()?;
+        Ok(id)
+    }
+
+    /// Returns the operations waiting to be sent, in the order they were queued.
+    pub fn pending(&self) -> Vec<OutboxEntry> {
+        self.file.lock().unwrap().pending.clone()
+    }
+
+    /// Returns the operations which were rejected by the API and won't be retried unless requeued.
+    pub fn dead_letters(&self) -> Vec<OutboxEntry> {
+        self.file.lock().unwrap().dead_letters.clone()
+    }
+
+    /// Returns the number of pending operations (including those scheduled for later).
+    pub fn len(&self) -> usize {
+        self.file.lock().unwrap().pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.file.lock().unwrap().pending.is_empty()
+    }
+
+    /// Returns the UNIX timestamp (in seconds) at which the earliest scheduled operation becomes due, or `None` if
+    /// nothing is scheduled for later.
+    pub fn next_scheduled(&self) -> Option<u64> {
+        let now = unix_timestamp() / 1000;
+        self.file.lock().unwrap().pending.iter().filter_map(|entry| entry.execute_at).filter(|at| *at > now).min()
+    }
+
+    /// Remove a pending operation or dead letter without sending it, returning whether or not it existed.
+    pub fn remove(&self, id: u64) -> Result<bool> {
+        let mut file = self.file.lock().unwrap();
+        let before = file.pending.len() + file.dead_letters.len();
+
+        file.pending.retain(|entry| entry.id != id);
+        file.dead_letters.retain(|entry| entry.id != id);
+
+        if file.pending.len() + file.dead_letters.len() == before {
+            return Ok(false);
+        }
+
+        drop(file);
+        self.persist()?;
+        Ok(true)
+    }
+
+    /// Move every dead letter back to the end of the pending queue (eg. after fixing the cause of their rejection).
+    pub fn requeue_dead_letters(&self) -> Result<()> {
+        let mut file = self.file.lock().unwrap();
+        let dead_letters = std::mem::take(&mut file.dead_letters);
+
+        file.pending.extend(dead_letters);
+        drop(file);
+        self.persist()
+    }
+
+    /// Send pending operations which are due in the order they were queued, removing each once accepted.
+    ///
+    /// Operations rejected by the API are moved to the dead letters rather than dropped. A transient failure (eg. the
+    /// API being unreachable or down for maintenance, or the request being abandoned due to rate limiting) stops the
+    /// flush, leaving that operation and those after it pending for the next flush. The returned error is only for
+    /// failures to persist the outbox itself.
+    ///
+    /// If the outbox is already being flushed elsewhere, this returns an empty report immediately.
+    pub async fn flush(&self, wrapper: &APIWrapper) -> Result<FlushReport> {
+        let mut report = FlushReport::default();
+
+        if self.flushing.swap(true, Ordering::AcqRel) {
+            return Ok(report);
+        }
+
+        let _flushing = FlushGuard(&self.flushing);
+        let now = unix_timestamp() / 1000;
+
+        // Entries are identified by ID rather than index, as they may be removed whilst a request is in flight.
+        while let Some((id, operation)) = self.next_due(now, &report) {
+            let result = operation.execute(wrapper).await;
+            let mut file = self.file.lock().unwrap();
+            let index = file.pending.iter().position(|entry| entry.id == id);
+
+            match result {
+                Ok(()) => {
+                    if let Some(index) = index {
+                        file.pending.remove(index);
+                    }
+
+                    report.sent.push(id);
+                }
+                Err(error) if error.is_retryable() || error.is_rate_limited() => {
+                    if let Some(index) = index {
+                        file.pending[index].last_error = Some(error.message());
+                    }
+
+                    report.interrupted = Some((id, error));
+                }
+                Err(error) => {
+                    if let Some(index) = index {
+                        let mut entry = file.pending.remove(index);
+                        entry.last_error = Some(error.message());
+                        file.dead_letters.push(entry);
+                    }
+
+                    report.rejected.push((id, error));
+                }
+            }
+
+            drop(file);
+            self.persist()?;
+
+            if report.interrupted.is_some() {
+                break;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Find the first pending operation which is due and hasn't been attempted during this flush, and record the
+    /// attempt.
+    fn next_due(&self, now: u64, report: &FlushReport) -> Option<(u64, WriteOperation)> {
+        let mut file = self.file.lock().unwrap();
+        let attempted = |id: u64| report.sent.contains(&id) || report.rejected.iter().any(|(other, _)| *other == id);
+
+        let entry = file.pending.iter_mut().find(|entry| entry.is_due(now) && !attempted(entry.id))?;
+        entry.attempts += 1;
+
+        Some((entry.id, entry.operation.clone()))
+    }
+
+    /// Rewrite the outbox file, via a temporary file so that a crash mid-write can't corrupt it.
+    fn persist(&self) -> Result<()> {
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+
+        // The lock is held until the file is renamed, so concurrent writers can't interleave within the temporary file.
+        let file = self.file.lock().unwrap();
+
+        fs::write(&temporary, serde_json::to_vec_pretty(&*file)?)?;
+        fs::rename(&temporary, &self.path)?;
+
+        Ok(())
+    }
+}
+
+/// Clears the flushing flag when a flush completes or is cancelled.
+struct FlushGuard<'a>(&'a AtomicBool);
+
+impl Drop for FlushGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Holds internal helpers for walking the pages of list endpoints.
+
+use crate::error::Result;
+
+use std::future::Future;
+
+/// Fetch successive pages (starting from the first) until an empty page is returned or `stop` reports that the
+/// latest page was the final one needed.
+///
+/// The page for which `stop` returned true is still included within the returned items.
+pub(crate) async fn collect_until<T, F, Fut, S>(mut fetch: F, mut stop: S) -> Result<Vec<T>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+    S: FnMut(&[T]) -> bool,
+{
+    let mut items = Vec::new();
+    let mut page = 1;
+
+    loop {
+        let batch = fetch(page).await?;
+
+        if batch.is_empty() {
+            break;
+        }
+
+        let finished = stop(&batch);
+
+        // Adopt the first page's allocation outright rather than copying it into an empty vector.
+        if items.is_empty() {
+            items = batch;
+        } else {
+            items.extend(batch);
+        }
+
+        if finished {
+            break;
+        }
+
+        page += 1;
+    }
+
+    Ok(items)
+}
+
+/// Fetch successive pages (starting from the first) until an empty page is returned.
+pub(crate) async fn collect_all<T, F, Fut>(fetch: F) -> Result<Vec<T>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    collect_until(fetch, |_| false).await
+}
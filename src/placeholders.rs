@@ -0,0 +1,113 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Parsing and verification of the placeholders BuiltByBit injects into downloaded resource files.
+//!
+//! When a resource is downloaded, occurrences of the placeholders below are replaced with values identifying the
+//! download. Anti-leak tooling can extract these values from a leaked file and cross-check them against the API.
+
+use crate::data::resources::{DownloadData, LicenseData};
+use crate::error::Result;
+use crate::APIWrapper;
+
+/// Replaced with the ID of the downloading member.
+pub const USER_PLACEHOLDER: &str = "%%__USER__%%";
+
+/// Replaced with the ID of the downloaded resource.
+pub const RESOURCE_PLACEHOLDER: &str = "%%__RESOURCE__%%";
+
+/// Replaced with a unique value per download.
+pub const NONCE_PLACEHOLDER: &str = "%%__NONCE__%%";
+
+/// The values extracted from a file's injected placeholders.
+///
+/// A field is `None` if its placeholder had not been replaced (or the extracted value couldn't be parsed).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Placeholders {
+    pub user_id: Option<u64>,
+    pub resource_id: Option<u64>,
+    pub nonce: Option<String>,
+}
+
+impl Placeholders {
+    /// Parse the raw values which were found in place of the user, resource, and nonce placeholders.
+    pub fn parse(user: &str, resource: &str, nonce: &str) -> Placeholders {
+        let nonce = nonce.trim();
+
+        Placeholders {
+            user_id: parse_id(user),
+            resource_id: parse_id(resource),
+            nonce: if nonce.is_empty() || is_placeholder(nonce) { None } else { Some(nonce.to_string()) },
+        }
+    }
+
+    /// Returns whether or not both the user and resource placeholders were injected.
+    pub fn is_injected(&self) -> bool {
+        self.user_id.is_some() && self.resource_id.is_some()
+    }
+}
+
+/// Returns whether or not a value is one of the placeholders in its un-replaced form.
+pub fn is_placeholder(value: &str) -> bool {
+    matches!(value.trim(), USER_PLACEHOLDER | RESOURCE_PLACEHOLDER | NONCE_PLACEHOLDER)
+}
+
+/// Returns each placeholder which still appears un-replaced within some content.
+///
+/// This is useful for detecting builds which were never distributed via BuiltByBit (or had injection disabled).
+pub fn find_unreplaced(content: &[u8]) -> Vec<&'static str> {
+    [USER_PLACEHOLDER, RESOURCE_PLACEHOLDER, NONCE_PLACEHOLDER]
+        .into_iter()
+        .filter(|placeholder| content.windows(placeholder.len()).any(|window| window == placeholder.as_bytes()))
+        .collect()
+}
+
+fn parse_id(value: &str) -> Option<u64> {
+    value.trim().parse().ok()
+}
+
+/// The outcome of cross-checking extracted placeholders against the API.
+#[derive(Debug, Clone)]
+pub struct PlaceholderReport {
+    pub member_id: u64,
+    pub resource_id: u64,
+    pub nonce: Option<String>,
+    pub license: Option<LicenseData>,
+    pub downloads: Vec<DownloadData>,
+}
+
+impl PlaceholderReport {
+    /// Returns whether or not the identified member has downloaded the identified resource.
+    pub fn has_downloaded(&self) -> bool {
+        !self.downloads.is_empty()
+    }
+
+    /// Returns whether or not the identified member holds a license for the identified resource.
+    pub fn has_license(&self) -> bool {
+        self.license.is_some()
+    }
+}
+
+/// Cross-check extracted placeholders against the licenses and downloads endpoints.
+///
+/// Returns `None` if the user or resource placeholders weren't injected, as there's nothing to check against.
+///
+/// # Note
+/// Only the first page of the member's downloads is fetched as we're only interested in whether any exist. The API
+/// doesn't expose download nonces, so the nonce is carried through to the report but can't be checked.
+pub async fn verify(wrapper: &APIWrapper, placeholders: &Placeholders) -> Result<Option<PlaceholderReport>> {
+    let (member_id, resource_id) = match (placeholders.user_id, placeholders.resource_id) {
+        (Some(member_id), Some(resource_id)) => (member_id, resource_id),
+        _ => return Ok(None),
+    };
+
+    let license = match wrapper.resources().licenses().fetch_by_member(resource_id, member_id).await {
+        Ok(license) => Some(license),
+        Err(error) if error.is_not_found() => None,
+        Err(error) => return Err(error),
+    };
+
+    let downloads = wrapper.resources().downloads().list_by_member(resource_id, member_id, None).await?;
+
+    Ok(Some(PlaceholderReport { member_id, resource_id, nonce: placeholders.nonce.clone(), license, downloads }))
+}
@@ -0,0 +1,70 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Spreading of reads across several tokens, so that read-heavy workloads aren't limited by a single token's rate
+//! limit.
+//!
+//! # Example
+//! ```no_run
+//! # use bbb_api_wrapper::pool::{Rotation, TokenPool};
+//! # use bbb_api_wrapper::{APIToken, APIWrapper};
+//! # async fn run() -> bbb_api_wrapper::error::Result<()> {
+//! let pool = TokenPool::new(APIToken::from_env("BBB_TOKEN")?)
+//!     .with_token(APIToken::from_env("BBB_TOKEN_SECONDARY")?)
+//!     .rotation(Rotation::LeastThrottled);
+//!
+//! let wrapper = APIWrapper::pooled(pool).build().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::APIToken;
+
+/// How a pool chooses which of its tokens sends each read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rotation {
+    /// Take turns between tokens, skipping those which are still backing off after being rate limited.
+    #[default]
+    RoundRobin,
+    /// Use whichever token has the shortest back-off remaining, preferring the least recently used if several have
+    /// none.
+    LeastThrottled,
+}
+
+/// A set of tokens which reads are spread across, constructed with the primary token via `TokenPool::new`.
+///
+/// Writes are always sent with the primary token, as each token may authenticate as a different account. The back-off
+/// of each token is tracked separately, so a rate limited token doesn't stall reads which another could send.
+///
+/// Reads made with a pooled token don't consult a custom rate limit backend (see
+/// `APIWrapperBuilder::rate_limit_backend`), which then only tracks writes.
+#[derive(Debug, Clone)]
+pub struct TokenPool {
+    pub(crate) tokens: Vec<APIToken>,
+    pub(crate) rotation: Rotation,
+}
+
+impl TokenPool {
+    /// Construct a pool of a single primary token, which is also used for all writes.
+    ///
+    /// The health check is a read, so it rotates across the pool like any other.
+    pub fn new(primary: APIToken) -> TokenPool {
+        TokenPool { tokens: vec![primary], rotation: Rotation::default() }
+    }
+
+    /// Add another token to spread reads across.
+    pub fn with_token(mut self, token: APIToken) -> Self {
+        self.tokens.push(token);
+        self
+    }
+
+    /// Set how the token which sends each read is chosen (round-robin by default).
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub(crate) fn primary(&self) -> &APIToken {
+        &self.tokens[0]
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Represents date ranges used to bound list queries.
+
+use crate::error::Result;
+use crate::pagination;
+use crate::sort::{Order, SortField, SortOptions};
+
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_HOUR: u64 = 60 * 60;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+
+/// An inclusive range between two UNIX timestamps (in seconds, as used by the API).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct DateRange {
+    pub from: u64,
+    pub to: u64,
+}
+
+impl DateRange {
+    /// Construct a range between two timestamps.
+    pub fn between(from: u64, to: u64) -> DateRange {
+        DateRange { from, to }
+    }
+
+    /// Construct a range from a timestamp up until now.
+    pub fn since(from: u64) -> DateRange {
+        DateRange { from, to: now() }
+    }
+
+    /// Construct a range covering the last number of hours up until now.
+    pub fn last_hours(hours: u64) -> DateRange {
+        let to = now();
+        DateRange { from: to.saturating_sub(hours * SECONDS_PER_HOUR), to }
+    }
+
+    /// Construct a range covering the last number of days up until now.
+    pub fn last_days(days: u64) -> DateRange {
+        let to = now();
+        DateRange { from: to.saturating_sub(days * SECONDS_PER_DAY), to }
+    }
+
+    /// Returns whether or not a timestamp falls within this range.
+    pub fn contains(&self, timestamp: u64) -> bool {
+        self.from <= timestamp && timestamp <= self.to
+    }
+
+    /// Returns whether or not a timestamp falls before the start of this range.
+    pub fn is_before(&self, timestamp: u64) -> bool {
+        timestamp < self.from
+    }
+
+    /// Returns sort options which order a list newest-first by a date field, for a specific page.
+    ///
+    /// Walking pages in this order allows us to stop as soon as we've passed the start of a range.
+    pub fn sort_options<F: SortField>(field: F, page: u64) -> SortOptions<'static> {
+        SortOptions::default().sort_by(field).order_by(Order::Desc).page(page)
+    }
+}
+
+/// Fetch only the items of a newest-first list which fall within a range.
+///
+/// Pages are fetched until one contains an item before the start of the range, and items outside of the range are
+/// then discarded.
+pub(crate) async fn collect<T, F, Fut, D>(range: DateRange, fetch: F, date: D) -> Result<Vec<T>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+    D: Fn(&T) -> u64,
+{
+    let mut items =
+        pagination::collect_until(fetch, |batch| batch.iter().any(|item| range.is_before(date(item)))).await?;
+    items.retain(|item| range.contains(date(item)));
+
+    Ok(items)
+}
+
+fn now() -> u64 {
+    crate::throttler::unix_timestamp() / 1000
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Pluggable storage of the back-off the API asks us to observe after rate limiting a request.
+//!
+//! By default this is held in process-local atomics, so multiple processes sharing a token each learn of the API's
+//! rate limits independently. A custom backend (eg. backed by Redis or a shared file) allows a rate limit hit by one
+//! process to stall the requests of all others.
+
+pub use crate::throttler::RequestType;
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The most recent back-off the API asked for of a request type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Backoff {
+    /// How long (in milliseconds) to wait before sending another request, or 0 if the API didn't ask us to wait.
+    pub retry_after: u64,
+    /// The UNIX timestamp (in milliseconds) from which the wait began.
+    pub since: u64,
+}
+
+impl Backoff {
+    /// Returns how long (in milliseconds) a request sent at a timestamp should still be stalled for.
+    pub fn remaining(&self, time: u64) -> u64 {
+        self.retry_after.saturating_sub(time.saturating_sub(self.since))
+    }
+}
+
+/// A store of the API's rate limiting back-off, which may be shared with other processes.
+///
+/// Methods are called synchronously on every request, so implementations which coordinate over the network should
+/// keep them cheap (eg. by publishing updates in the background and serving reads from a local copy).
+///
+/// Locally configured ceilings and budgets (eg. via `APIWrapper::set_read_budget`) are always tracked per process.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::rate_limit::{Backoff, RateLimitBackend, RequestType};
+/// # use std::sync::Mutex;
+/// /// A backend shared by multiple wrappers within a process, which a real coordinator would replace.
+/// #[derive(Default)]
+/// struct SharedBackend(Mutex<[Backoff; 2]>);
+///
+/// impl RateLimitBackend for SharedBackend {
+///     fn backoff(&self, request_type: RequestType) -> Backoff {
+///         self.0.lock().unwrap()[request_type as usize]
+///     }
+///
+///     fn record_limited(&self, request_type: RequestType, retry_after: u64, time: u64) {
+///         self.0.lock().unwrap()[request_type as usize] = Backoff { retry_after, since: time };
+///     }
+///
+///     // A success reported by one process shouldn't lift a back-off another process has just been asked to observe,
+///     // so we let each back-off elapse on its own instead.
+///     fn record_success(&self, _: RequestType, _: u64) {}
+/// }
+/// ```
+pub trait RateLimitBackend: Send + Sync {
+    /// Returns the most recent back-off of a request type.
+    fn backoff(&self, request_type: RequestType) -> Backoff;
+
+    /// Record that a request was rate limited at a timestamp, and that requests of its type should wait for a
+    /// duration (in milliseconds).
+    fn record_limited(&self, request_type: RequestType, retry_after: u64, time: u64);
+
+    /// Record that a request wasn't rate limited at a timestamp.
+    fn record_success(&self, request_type: RequestType, time: u64);
+}
+
+/// The default backend, which holds the back-off within process-local atomics.
+///
+/// A successful response lifts any back-off of its request type.
+pub struct LocalBackend {
+    read_last_retry: AtomicU64,
+    read_last_request: AtomicU64,
+
+    write_last_retry: AtomicU64,
+    write_last_request: AtomicU64,
+}
+
+impl LocalBackend {
+    pub fn new() -> LocalBackend {
+        LocalBackend {
+            read_last_retry: AtomicU64::new(0),
+            read_last_request: AtomicU64::new(0),
+
+            write_last_retry: AtomicU64::new(0),
+            write_last_request: AtomicU64::new(0),
+        }
+    }
+
+    fn atomics(&self, request_type: RequestType) -> (&AtomicU64, &AtomicU64) {
+        match request_type {
+            RequestType::Read => (&self.read_last_retry, &self.read_last_request),
+            RequestType::Write => (&self.write_last_retry, &self.write_last_request),
+        }
+    }
+}
+
+impl Default for LocalBackend {
+    fn default() -> Self {
+        LocalBackend::new()
+    }
+}
+
+impl RateLimitBackend for LocalBackend {
+    fn backoff(&self, request_type: RequestType) -> Backoff {
+        let (last_retry, last_request) = self.atomics(request_type);
+        Backoff { retry_after: last_retry.load(Ordering::Acquire), since: last_request.load(Ordering::Acquire) }
+    }
+
+    fn record_limited(&self, request_type: RequestType, retry_after: u64, time: u64) {
+        let (last_retry, last_request) = self.atomics(request_type);
+        last_retry.store(retry_after, Ordering::Release);
+        last_request.store(time, Ordering::Release);
+    }
+
+    fn record_success(&self, request_type: RequestType, time: u64) {
+        let (last_retry, last_request) = self.atomics(request_type);
+        last_retry.store(0, Ordering::Release);
+        last_request.store(time, Ordering::Release);
+    }
+}
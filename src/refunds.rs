@@ -0,0 +1,109 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Detection of purchases which are refunded or reversed (eg. via a chargeback) after they were made.
+
+use crate::error::Result;
+use crate::events::Event;
+use crate::range::DateRange;
+use crate::throttler::unix_timestamp;
+use crate::APIWrapper;
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[derive(Default)]
+struct ResourceState {
+    /// Whether or not each recent purchase was reversed when last checked.
+    purchases: HashMap<u64, bool>,
+    primed: bool,
+}
+
+/// Re-checks the recent purchases of a set of resources each time it's polled, producing a `PurchaseReversed` event
+/// when one transitions to being refunded or reversed.
+///
+/// Only purchases made within the lookback period are re-checked, as refunds and chargebacks typically happen soon
+/// after purchase. The first poll of a resource establishes a baseline, so purchases which were already reversed
+/// aren't reported.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::data::resources::{LicenseModification, LicenseModifyPermData};
+/// # use bbb_api_wrapper::events::Event;
+/// # use bbb_api_wrapper::refunds::RefundMonitor;
+/// # use std::time::Duration;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let mut monitor = RefundMonitor::new(&wrapper, Duration::from_secs(60 * 86_400));
+/// monitor.watch_resource(1);
+///
+/// for event in monitor.poll().await? {
+///     if let Event::PurchaseReversed { resource_id, purchase } = event {
+///         // Revoke the license associated with the reversed purchase.
+///         let revoke = LicenseModification::Permanent(LicenseModifyPermData { permanent: true, active: false });
+///         wrapper.resources().licenses().modify(resource_id, *purchase.license_id(), &revoke).await?;
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct RefundMonitor<'a> {
+    wrapper: &'a APIWrapper,
+    lookback: Duration,
+    resources: HashMap<u64, ResourceState>,
+}
+
+impl<'a> RefundMonitor<'a> {
+    /// Construct a monitor which re-checks purchases made within a lookback period.
+    pub fn new(wrapper: &'a APIWrapper, lookback: Duration) -> RefundMonitor<'a> {
+        RefundMonitor { wrapper, lookback, resources: HashMap::new() }
+    }
+
+    /// Watch a resource's purchases for reversals.
+    pub fn watch_resource(&mut self, resource_id: u64) -> &mut Self {
+        self.resources.entry(resource_id).or_default();
+        self
+    }
+
+    /// Stop watching a resource.
+    pub fn unwatch_resource(&mut self, resource_id: u64) -> &mut Self {
+        self.resources.remove(&resource_id);
+        self
+    }
+
+    /// Re-check recent purchases, returning an event for each which has been reversed since the previous poll.
+    ///
+    /// A purchase which is first seen already reversed (ie. it was made and reversed between polls) is also reported.
+    /// Every resource is fetched before any state is updated, so no reversals are lost if an error occurs.
+    pub async fn poll(&mut self) -> Result<Vec<Event>> {
+        let range = DateRange::since((unix_timestamp() / 1000).saturating_sub(self.lookback.as_secs()));
+        let mut fetched = Vec::with_capacity(self.resources.len());
+
+        for &resource_id in self.resources.keys() {
+            fetched.push((resource_id, self.wrapper.resources().purchases().list_between(resource_id, range).await?));
+        }
+
+        let mut events = Vec::new();
+
+        for (resource_id, purchases) in fetched {
+            let state = self.resources.entry(resource_id).or_default();
+            let mut checked = HashMap::with_capacity(purchases.len());
+
+            for purchase in purchases {
+                let reversed = purchase.is_reversed();
+                let previously = state.purchases.get(purchase.purchase_id()).copied();
+
+                checked.insert(*purchase.purchase_id(), reversed);
+
+                if reversed && state.primed && previously != Some(true) {
+                    events.push(Event::PurchaseReversed { resource_id, purchase });
+                }
+            }
+
+            // Purchases which have aged out of the lookback period are forgotten.
+            state.purchases = checked;
+            state.primed = true;
+        }
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A common destination for the outputs of the analytics and audit components, so that any report can be routed to a
+//! file, a webhook (eg. Discord), or custom handling without per-report glue.
+
+use crate::error::{APIError, Result};
+use crate::runtime;
+use crate::throttler::unix_timestamp;
+
+use std::fs;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+
+use reqwest::{Client, Url};
+use serde::Serialize;
+
+/// The error code we attach to webhooks which respond with an unsuccessful status.
+const WEBHOOK_CODE: &str = "WebhookError";
+
+/// The default maximum duration of each request to a webhook.
+const DEFAULT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Discord rejects webhook messages whose content is longer than this many characters.
+const DISCORD_CONTENT_LIMIT: usize = 2000;
+
+/// A named, timestamped report with a JSON body.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub name: String,
+    /// The UNIX timestamp (in seconds) at which the report was generated.
+    pub generated_at: u64,
+    pub body: serde_json::Value,
+}
+
+impl Report {
+    /// Construct a report from any serialisable output (eg. an `AuditReport`), timestamped with the current time.
+    pub fn new<T: Serialize>(name: &str, body: &T) -> Result<Report> {
+        Ok(Report { name: name.to_string(), generated_at: unix_timestamp() / 1000, body: serde_json::to_value(body)? })
+    }
+}
+
+/// A destination which reports can be written to.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::reports::{CallbackSink, FileSink, Report, ReportSink};
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let sinks: Vec<Box<dyn ReportSink>> = vec![
+///     Box::new(FileSink::new("reports")),
+///     Box::new(CallbackSink::new(|report: &Report| {
+///         println!("{}: {}", report.name, report.body);
+///         Ok(())
+///     })),
+/// ];
+///
+/// let audit = wrapper.resources().licenses().audit(1).await?;
+/// let report = Report::new("license-audit", &audit)?;
+///
+/// for sink in &sinks {
+///     sink.write(&report).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait ReportSink {
+    fn write<'a>(&'a self, report: &'a Report) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>>;
+}
+
+/// Writes each report to its own JSON file within a directory, named after the report and its timestamp.
+///
+/// Characters of the report's name which aren't alphanumeric, `-`, or `_` are replaced with `_`, so a name can't
+/// escape the directory.
+pub struct FileSink {
+    directory: PathBuf,
+}
+
+impl FileSink {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> FileSink {
+        FileSink { directory: directory.into() }
+    }
+}
+
+impl ReportSink for FileSink {
+    fn write<'a>(&'a self, report: &'a Report) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
+        Box::pin(async move {
+            fs::create_dir_all(&self.directory)?;
+
+            let path = self.directory.join(format!("{}-{}.json", file_name(&report.name), report.generated_at));
+            fs::write(path, serde_json::to_vec_pretty(report)?)?;
+
+            Ok(())
+        })
+    }
+}
+
+/// Posts each report to a webhook URL.
+///
+/// Requests are made with a separate client to the wrapper's, so the API token is never sent to the webhook. A request
+/// which doesn't complete within the timeout (30 seconds by default) fails with `APIError::Timeout`.
+pub struct WebhookSink {
+    client: Client,
+    url: Url,
+    discord: bool,
+    timeout: Duration,
+}
+
+impl WebhookSink {
+    /// Post each report as its JSON representation.
+    pub fn new(url: Url) -> WebhookSink {
+        WebhookSink { client: Client::new(), url, discord: false, timeout: DEFAULT_WEBHOOK_TIMEOUT }
+    }
+
+    /// Post each report as a Discord webhook message, with the body within a code block (truncated to fit
+    /// Discord's message length limit).
+    pub fn discord(url: Url) -> WebhookSink {
+        WebhookSink { client: Client::new(), url, discord: true, timeout: DEFAULT_WEBHOOK_TIMEOUT }
+    }
+
+    /// Set the maximum duration of each request to the webhook.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl ReportSink for WebhookSink {
+    fn write<'a>(&'a self, report: &'a Report) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
+        Box::pin(async move {
+            let request = self.client.post(self.url.clone());
+
+            let request = if self.discord {
+                request.json(&serde_json::json!({ "content": discord_content(report)? }))
+            } else {
+                request.json(report)
+            };
+
+            // The wasm backend can't time out requests itself, so we race the request against a timer instead.
+            let timed_out = async {
+                runtime::sleep(self.timeout).await;
+                Err(APIError::Timeout { source: None })
+            };
+
+            let response = runtime::race(async { Ok(request.send().await?) }, timed_out).await?;
+
+            if !response.status().is_success() {
+                let message = format!("The webhook responded with status {}", response.status());
+                return Err(APIError::local(WEBHOOK_CODE, message));
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Replace the characters of a report's name which aren't safe within a file name.
+fn file_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' }).collect()
+}
+
+fn discord_content(report: &Report) -> Result<String> {
+    let header = format!("**{}**\n```json\n", report.name);
+    let footer = "\n```";
+    let body = serde_json::to_string_pretty(&report.body)?;
+
+    let available = DISCORD_CONTENT_LIMIT.saturating_sub(header.chars().count() + footer.len());
+
+    let body: String = if body.chars().count() > available {
+        body.chars().take(available.saturating_sub(1)).chain(std::iter::once('…')).collect()
+    } else {
+        body
+    };
+
+    Ok(format!("{}{}{}", header, body, footer))
+}
+
+/// Passes each report to a function (eg. to print it, or forward it to a custom destination).
+pub struct CallbackSink<F> {
+    callback: F,
+}
+
+impl<F> CallbackSink<F>
+where
+    F: Fn(&Report) -> Result<()>,
+{
+    pub fn new(callback: F) -> CallbackSink<F> {
+        CallbackSink { callback }
+    }
+}
+
+impl<F> ReportSink for CallbackSink<F>
+where
+    F: Fn(&Report) -> Result<()>,
+{
+    fn write<'a>(&'a self, report: &'a Report) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
+        Box::pin(async move { (self.callback)(report) })
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Retry policies, and a combinator for retrying multi-call operations with consistent backoff semantics.
+
+use crate::error::{APIError, Result};
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Decides whether, and after how long, a failed operation should be retried.
+///
+/// A policy can be applied to every request made by a wrapper (see `APIWrapperBuilder::retry_policy`), or to a single
+/// operation via `retry_with`.
+pub trait RetryPolicy {
+    /// Returns the delay before retrying after a specific (1-indexed) failed attempt, or `None` to give up and return
+    /// the error.
+    fn retry_after(&self, attempt: u32, error: &APIError) -> Option<Duration>;
+}
+
+/// A retry policy which backs off exponentially between attempts.
+#[derive(Debug, Clone)]
+pub struct ExponentialBackoff {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: f64,
+    /// Whether or not to randomise each delay (to between half and all of its length), so that clients which failed
+    /// together don't retry in lockstep.
+    pub jitter: bool,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        ExponentialBackoff {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: true,
+        }
+    }
+}
+
+impl ExponentialBackoff {
+    /// Set the maximum number of attempts (including the first).
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay before the first retry.
+    pub fn initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    /// Set the upper bound on any single delay.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set the factor by which the delay grows after each attempt.
+    ///
+    /// # Panics
+    /// Panics if the multiplier is negative or not finite.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        assert!(multiplier.is_finite() && multiplier >= 0.0, "backoff multiplier must be finite and non-negative");
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Set whether or not to randomise each delay.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Compute the delay which should follow a specific (1-indexed) failed attempt, before any jitter is applied.
+    ///
+    /// The delay saturates at the maximum delay, including once the growth factor overflows (or if it isn't valid).
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_delay.as_secs_f64() * self.multiplier.powi(exponent);
+
+        Duration::try_from_secs_f64(delay).map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    /// Retries errors which are retryable (see `APIError::is_retryable`) until the maximum number of attempts.
+    fn retry_after(&self, attempt: u32, error: &APIError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !error.is_retryable() {
+            return None;
+        }
+
+        let delay = self.delay_for(attempt);
+
+        if self.jitter {
+            // Each `RandomState` is randomly keyed, which is enough entropy for spreading out retries.
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u32(attempt);
+
+            let factor = 0.5 + 0.5 * (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+            Some(Duration::try_from_secs_f64(delay.as_secs_f64() * factor).unwrap_or(delay))
+        } else {
+            Some(delay)
+        }
+    }
+}
+
+/// Run an operation, retrying it for as long as the policy permits.
+///
+/// Under `ExponentialBackoff`, errors which aren't retryable (see `APIError::is_retryable`) are returned immediately,
+/// as is the error from the final attempt.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::retry::{retry_with, ExponentialBackoff};
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let policy = ExponentialBackoff::default().max_attempts(5);
+/// let member = retry_with(&policy, || async { wrapper.members().fetch_by_id(87939).await }).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn retry_with<T, P, F, Fut>(policy: &P, mut operation: F) -> Result<T>
+where
+    P: RetryPolicy + ?Sized,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        match operation().await {
+            Err(error) => match policy.retry_after(attempt, &error) {
+                Some(delay) => {
+                    crate::runtime::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => return Err(error),
+            },
+            result => return result,
+        }
+    }
+}
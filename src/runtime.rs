@@ -0,0 +1,95 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Holds the timer primitives used throughout the wrapper, backed by whichever async runtime has been selected.
+//!
+//! Exactly one backend is used: `rt-tokio` (the default), then `rt-async-std`, then `rt-smol`, in that order of
+//! precedence if several are enabled. `rt-wasm` is used within browsers (ie. when targeting `wasm32-unknown-unknown`),
+//! where timers are backed by the browser's own and the standard library's clocks are replaced by `performance.now()`.
+
+use std::future::{self, Future};
+use std::pin::pin;
+use std::task::Poll;
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// The standard library's clocks panic when read within browsers.
+#[cfg(target_arch = "wasm32")]
+pub(crate) use web_time::{Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(not(any(feature = "rt-tokio", feature = "rt-async-std", feature = "rt-smol", feature = "rt-wasm")))]
+compile_error!("one of the `rt-tokio`, `rt-async-std`, `rt-smol`, or `rt-wasm` features must be enabled");
+
+/// Sleep for a duration using the selected runtime's timer.
+#[cfg(feature = "rt-tokio")]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+/// Sleep for a duration using the selected runtime's timer.
+#[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+pub(crate) async fn sleep(duration: Duration) {
+    async_std::task::sleep(duration).await;
+}
+
+/// Sleep for a duration using the selected runtime's timer.
+#[cfg(all(feature = "rt-smol", not(any(feature = "rt-tokio", feature = "rt-async-std"))))]
+pub(crate) async fn sleep(duration: Duration) {
+    async_io::Timer::after(duration).await;
+}
+
+/// Sleep for a duration using the browser's timer.
+#[cfg(all(feature = "rt-wasm", not(any(feature = "rt-tokio", feature = "rt-async-std", feature = "rt-smol"))))]
+pub(crate) async fn sleep(duration: Duration) {
+    gloo_timers::future::sleep(duration).await;
+}
+
+/// Wait on two futures at once, returning the output of whichever completes first and dropping the other.
+///
+/// If both are ready on the same poll, the first wins.
+pub(crate) async fn race<A, B, T>(first: A, second: B) -> T
+where
+    A: Future<Output = T>,
+    B: Future<Output = T>,
+{
+    let mut first = pin!(first);
+    let mut second = pin!(second);
+
+    future::poll_fn(|context| match first.as_mut().poll(context) {
+        Poll::Ready(value) => Poll::Ready(value),
+        Poll::Pending => second.as_mut().poll(context),
+    })
+    .await
+}
+
+/// A fixed-period ticker whose first tick completes immediately.
+///
+/// Ticks are scheduled against deadlines rather than relative to when the previous tick completed, so time spent
+/// between ticks doesn't cause drift. If a tick is missed entirely, the next completes immediately and the schedule
+/// restarts from then.
+#[cfg(feature = "sync")]
+pub(crate) struct Interval {
+    period: Duration,
+    next: Instant,
+}
+
+#[cfg(feature = "sync")]
+impl Interval {
+    pub(crate) fn new(period: Duration) -> Interval {
+        Interval { period, next: Instant::now() }
+    }
+
+    /// Wait until the next tick is due.
+    pub(crate) async fn tick(&mut self) {
+        let now = Instant::now();
+
+        if self.next > now {
+            sleep(self.next - now).await;
+            self.next += self.period;
+        } else {
+            self.next = now + self.period;
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A small scheduler for running periodic jobs against a wrapper until shutdown.
+//!
+//! Jobs are driven from a single future rather than spawned, so the scheduler works under any of the supported
+//! runtimes and can never outlive the wrapper it borrows. Jobs run one at a time, which keeps them from competing with
+//! each other for the rate limit budget.
+
+use crate::error::Result;
+use crate::outbox::Outbox;
+use crate::runtime::{self, Instant};
+use crate::APIWrapper;
+
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::time::Duration;
+
+type JobFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + 'a>>;
+
+struct Job<'a> {
+    name: String,
+    period: Duration,
+    next: Instant,
+    run: Box<dyn FnMut(&'a APIWrapper) -> JobFuture<'a> + 'a>,
+}
+
+/// A set of periodic jobs which run against a wrapper.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::scheduler::Scheduler;
+/// # use std::time::Duration;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper, shutdown: impl std::future::Future<Output = ()>) {
+/// let mut scheduler = Scheduler::new(&wrapper);
+///
+/// scheduler.every("metrics", Duration::from_secs(60), |wrapper| async move {
+///     println!("{:?}", wrapper.metrics().await?);
+///     Ok(())
+/// });
+///
+/// scheduler.run_until(shutdown).await;
+/// # }
+/// ```
+pub struct Scheduler<'a> {
+    wrapper: &'a APIWrapper,
+    jobs: Vec<Job<'a>>,
+}
+
+impl<'a> Scheduler<'a> {
+    pub fn new(wrapper: &'a APIWrapper) -> Scheduler<'a> {
+        Scheduler { wrapper, jobs: Vec::new() }
+    }
+
+    /// Register a job which runs immediately and then once every period.
+    ///
+    /// If a job's run is delayed past its next due time (eg. by another long-running job), it runs as soon as possible
+    /// and its schedule restarts from then rather than running repeatedly to catch up.
+    pub fn every<F, Fut>(&mut self, name: &str, period: Duration, mut job: F) -> &mut Self
+    where
+        F: FnMut(&'a APIWrapper) -> Fut + 'a,
+        Fut: Future<Output = Result<()>> + 'a,
+    {
+        self.jobs.push(Job {
+            name: name.to_string(),
+            period,
+            next: Instant::now(),
+            run: Box::new(move |wrapper| Box::pin(job(wrapper))),
+        });
+
+        self
+    }
+
+    /// Register a job which flushes an outbox once every period, sending any queued or scheduled writes which are due.
+    ///
+    /// Scheduled writes are therefore sent within one period of their due time. Rejected writes are logged and left
+    /// within the outbox's dead letters.
+    pub fn outbox(&mut self, outbox: &'a Outbox, period: Duration) -> &mut Self {
+        self.every("outbox", period, move |wrapper| async move {
+            let report = outbox.flush(wrapper).await?;
+
+            for (id, error) in &report.rejected {
+                log::warn!("Outbox entry {} was rejected: {:?}", id, error);
+            }
+
+            match report.interrupted {
+                Some((_, error)) => Err(error),
+                None => Ok(()),
+            }
+        })
+    }
+
+    /// Run jobs indefinitely.
+    pub async fn run(&mut self) {
+        self.run_until(future::pending()).await
+    }
+
+    /// Run jobs until a shutdown future completes.
+    ///
+    /// A job which is running when shutdown completes is allowed to finish; no further jobs are started afterwards.
+    /// Failed runs are logged and retried at the job's next due time.
+    pub async fn run_until<S>(&mut self, shutdown: S)
+    where
+        S: Future<Output = ()>,
+    {
+        let mut shutdown = Box::pin(shutdown);
+
+        loop {
+            let job = match self.jobs.iter_mut().min_by_key(|job| job.next) {
+                Some(job) => job,
+                None => return shutdown.await,
+            };
+
+            let delay = job.next.saturating_duration_since(Instant::now());
+
+            let due = async {
+                runtime::sleep(delay).await;
+                false
+            };
+
+            let stopped = async {
+                (&mut shutdown).await;
+                true
+            };
+
+            if runtime::race(due, stopped).await {
+                return;
+            }
+
+            if let Err(error) = (job.run)(self.wrapper).await {
+                log::warn!("Scheduled job '{}' failed: {:?}", job.name, error);
+            }
+
+            job.next += job.period;
+
+            if job.next <= Instant::now() {
+                job.next = Instant::now() + job.period;
+            }
+        }
+    }
+}
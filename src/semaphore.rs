@@ -0,0 +1,119 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A runtime-agnostic async semaphore, used to limit the number of requests in flight at once.
+
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+
+struct State {
+    available: usize,
+    /// The tasks waiting for a permit in the order they started waiting, keyed by their acquisition's ID.
+    waiters: VecDeque<(u64, Waker)>,
+    /// The acquisitions which have been handed a released permit, but have yet to be polled to take it.
+    granted: HashSet<u64>,
+    next_id: u64,
+}
+
+/// A fair semaphore: permits are handed to waiting tasks in the order they started waiting, so a task can't be starved
+/// by others which start acquiring after it.
+pub(crate) struct Semaphore {
+    state: Mutex<State>,
+}
+
+impl Semaphore {
+    pub(crate) fn new(permits: usize) -> Semaphore {
+        let state = State { available: permits, waiters: VecDeque::new(), granted: HashSet::new(), next_id: 0 };
+        Semaphore { state: Mutex::new(state) }
+    }
+
+    /// Wait until a permit is available, holding it until the returned guard is dropped.
+    pub(crate) fn acquire(&self) -> Acquire<'_> {
+        Acquire { semaphore: self, id: None, acquired: false }
+    }
+
+    /// Hand a permit directly to the longest waiting task, or return it to the pool if none are waiting.
+    fn release(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        match state.waiters.pop_front() {
+            Some((id, waker)) => {
+                state.granted.insert(id);
+                waker.wake();
+            }
+            None => state.available += 1,
+        }
+    }
+}
+
+/// A future which resolves to a permit once one is available.
+pub(crate) struct Acquire<'a> {
+    semaphore: &'a Semaphore,
+    id: Option<u64>,
+    acquired: bool,
+}
+
+impl<'a> Future for Acquire<'a> {
+    type Output = Permit<'a>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Permit<'a>> {
+        let this = self.get_mut();
+        let mut state = this.semaphore.state.lock().unwrap();
+
+        let granted = this.id.is_some_and(|id| state.granted.remove(&id));
+
+        // Free permits are only taken if nobody is already waiting, as they'd otherwise have been handed to them.
+        if granted || (state.available > 0 && state.waiters.is_empty()) {
+            if !granted {
+                state.available -= 1;
+            }
+
+            this.acquired = true;
+            return Poll::Ready(Permit { semaphore: this.semaphore });
+        }
+
+        let id = *this.id.get_or_insert_with(|| {
+            state.next_id += 1;
+            state.next_id
+        });
+
+        match state.waiters.iter_mut().find(|(waiter, _)| *waiter == id) {
+            Some((_, waker)) => waker.clone_from(cx.waker()),
+            None => state.waiters.push_back((id, cx.waker().clone())),
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        let Some(id) = self.id.filter(|_| !self.acquired) else {
+            return;
+        };
+
+        let mut state = self.semaphore.state.lock().unwrap();
+        state.waiters.retain(|(waiter, _)| *waiter != id);
+        let granted = state.granted.remove(&id);
+        drop(state);
+
+        // If we were handed a permit but abandoned before taking it, pass it on to the next waiter.
+        if granted {
+            self.semaphore.release();
+        }
+    }
+}
+
+/// A permit which is returned to its semaphore once dropped.
+pub(crate) struct Permit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.release();
+    }
+}
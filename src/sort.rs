@@ -2,16 +2,301 @@
 // MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
 
 //! Represents the sorting options made available by the API.
+//!
+//! Fields and orders can be given either as typed enums (eg. `PurchaseSortField::PurchaseDate` and `Order::Desc`), or
+//! as raw strings for fields which aren't listed here. Any additional query filters supported by an endpoint can be
+//! composed alongside them via `FilterOptions`.
 
+use crate::data::alerts::{AlertType, ContentType};
+use crate::data::threads::{ThreadData, ThreadType};
 use crate::error::Result;
 
 use serde::Serialize;
 
-#[derive(Default, Serialize)]
+/// The direction in which a list is sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl Order {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Order::Asc => "asc",
+            Order::Desc => "desc",
+        }
+    }
+}
+
+/// A field by which an endpoint's list can be sorted.
+pub trait SortField {
+    /// Returns the field's name as expected by the API.
+    fn as_str(&self) -> &'static str;
+}
+
+macro_rules! sort_fields {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $field:literal),+ $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($variant),+
+        }
+
+        impl SortField for $name {
+            fn as_str(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $field),+
+                }
+            }
+        }
+    };
+}
+
+sort_fields!(
+    /// The fields by which resource lists (public, owned, and collaborated) can be sorted.
+    ResourceSortField { ResourceId => "resource_id", AuthorId => "author_id", Title => "title", Price => "price" }
+);
+
+sort_fields!(
+    /// The fields by which a resource's purchases can be sorted.
+    PurchaseSortField {
+        PurchaseId => "purchase_id",
+        PurchaserId => "purchaser_id",
+        Price => "price",
+        PurchaseDate => "purchase_date",
+        ValidationDate => "validation_date",
+    }
+);
+
+sort_fields!(
+    /// The fields by which a resource's licenses can be sorted.
+    LicenseSortField {
+        LicenseId => "license_id",
+        PurchaserId => "purchaser_id",
+        StartDate => "start_date",
+        EndDate => "end_date",
+    }
+);
+
+sort_fields!(
+    /// The fields by which a resource's downloads can be sorted.
+    DownloadSortField {
+        DownloadId => "download_id",
+        VersionId => "version_id",
+        DownloaderId => "downloader_id",
+        DownloadDate => "download_date",
+    }
+);
+
+sort_fields!(
+    /// The fields by which a resource's reviews can be sorted.
+    ReviewSortField {
+        ReviewId => "review_id",
+        ReviewerId => "reviewer_id",
+        ReviewDate => "review_date",
+        Rating => "rating",
+    }
+);
+
+sort_fields!(
+    /// The fields by which a resource's updates can be sorted.
+    UpdateSortField { UpdateId => "update_id", UpdateDate => "update_date" }
+);
+
+sort_fields!(
+    /// The fields by which a resource's versions can be sorted.
+    VersionSortField { VersionId => "version_id", ReleaseDate => "release_date", DownloadCount => "download_count" }
+);
+
+sort_fields!(
+    /// The fields by which unread conversations can be sorted.
+    ConversationSortField {
+        ConversationId => "conversation_id",
+        CreationDate => "creation_date",
+        LastMessageDate => "last_message_date",
+        ReplyCount => "reply_count",
+    }
+);
+
+sort_fields!(
+    /// The fields by which a conversation's replies can be sorted.
+    ConversationReplySortField { MessageId => "message_id", MessageDate => "message_date" }
+);
+
+sort_fields!(
+    /// The fields by which threads can be sorted.
+    ThreadSortField {
+        ThreadId => "thread_id",
+        CreationDate => "creation_date",
+        LastMessageDate => "last_message_date",
+        ReplyCount => "reply_count",
+        ViewCount => "view_count",
+    }
+);
+
+sort_fields!(
+    /// The fields by which a thread's replies can be sorted.
+    ThreadReplySortField { ReplyId => "reply_id", PostDate => "post_date" }
+);
+
+sort_fields!(
+    /// The fields by which profile posts can be sorted.
+    ProfilePostSortField { ProfilePostId => "profile_post_id", PostDate => "post_date" }
+);
+
+sort_fields!(
+    /// The fields by which unread alerts can be sorted.
+    AlertSortField { AlertDate => "alert_date" }
+);
+
+/// Additional query parameters by which an endpoint filters its list server-side, sent alongside the sort options.
+///
+/// Parameters are passed through to the API as given, so only those documented for the endpoint being listed should
+/// be used. A parameter given more than once is sent once per value.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::sort::{FilterOptions, SortOptions};
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let filter = FilterOptions::default().param("version_id", 42);
+/// let sort = SortOptions::default().filter(filter);
+///
+/// let downloads = wrapper.resources().downloads().list(1, Some(&sort)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FilterOptions<'a> {
+    params: Vec<(&'a str, String)>,
+}
+
+impl<'a> FilterOptions<'a> {
+    /// Add a query parameter.
+    pub fn param<V: ToString>(mut self, name: &'a str, value: V) -> Self {
+        self.params.push((name, value.to_string()));
+        self
+    }
+
+    /// Add every parameter of another set of filters.
+    pub fn merge(mut self, other: FilterOptions<'a>) -> Self {
+        self.params.extend(other.params);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+
+    pub fn to_query_string(&self) -> String {
+        url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.params.iter().map(|(name, value)| (*name, value.as_str())))
+            .finish()
+    }
+}
+
+/// The filters by which unread alerts can be listed, as accepted by `AlertsHelper::list_unread_filtered`.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::data::alerts::ContentType;
+/// # use bbb_api_wrapper::sort::AlertFilter;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let filter = AlertFilter::default().content_type(ContentType::ResourceReview);
+/// let reviews = wrapper.alerts().list_all_unread_filtered(&filter).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlertFilter {
+    pub content_type: Option<ContentType>,
+    pub alert_type: Option<AlertType>,
+}
+
+impl AlertFilter {
+    /// Only list alerts on content of a type.
+    pub fn content_type(mut self, content_type: ContentType) -> Self {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    /// Only list alerts of a type.
+    pub fn alert_type(mut self, alert_type: AlertType) -> Self {
+        self.alert_type = Some(alert_type);
+        self
+    }
+}
+
+impl From<&AlertFilter> for FilterOptions<'_> {
+    fn from(filter: &AlertFilter) -> Self {
+        let mut options = FilterOptions::default();
+
+        if let Some(content_type) = &filter.content_type {
+            options = options.param("content_type", content_type);
+        }
+        if let Some(alert_type) = &filter.alert_type {
+            options = options.param("alert_type", alert_type);
+        }
+
+        options
+    }
+}
+
+/// The filters by which threads can be listed, as accepted by `ThreadsHelper::list_threads_filtered`.
+///
+/// The API doesn't accept these as query parameters, so they're matched against each thread's details instead.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::data::threads::ThreadType;
+/// # use bbb_api_wrapper::sort::ThreadFilter;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let filter = ThreadFilter::default().forum("Plugin Discussion").open(true).thread_type(ThreadType::Question);
+/// let questions = wrapper.threads().list_all_threads_filtered(&filter).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadFilter {
+    pub forum_name: Option<String>,
+    pub open: Option<bool>,
+    pub thread_type: Option<ThreadType>,
+}
+
+impl ThreadFilter {
+    /// Only list threads within a forum, by its name.
+    pub fn forum(mut self, forum_name: &str) -> Self {
+        self.forum_name = Some(forum_name.to_string());
+        self
+    }
+
+    /// Only list threads which are open (or closed) to new replies.
+    pub fn open(mut self, open: bool) -> Self {
+        self.open = Some(open);
+        self
+    }
+
+    /// Only list threads of a type.
+    pub fn thread_type(mut self, thread_type: ThreadType) -> Self {
+        self.thread_type = Some(thread_type);
+        self
+    }
+
+    /// Returns whether or not a thread matches every filter which is set.
+    pub fn matches(&self, thread: &ThreadData) -> bool {
+        self.forum_name.as_ref().is_none_or(|forum_name| forum_name == thread.forum_name())
+            && self.open.is_none_or(|open| open == *thread.thread_open())
+            && self.thread_type.as_ref().is_none_or(|thread_type| *thread_type == thread.kind())
+    }
+}
+
+#[derive(Clone, Default, Serialize)]
 pub struct SortOptions<'a> {
     pub sort: Option<&'a str>,
     pub order: Option<&'a str>,
     pub page: Option<u64>,
+    #[serde(skip)]
+    pub filter: FilterOptions<'a>,
 }
 
 impl<'a> SortOptions<'a> {
@@ -25,12 +310,54 @@ impl<'a> SortOptions<'a> {
         self
     }
 
+    /// Sort by a typed field of the endpoint being listed.
+    pub fn sort_by<F: SortField>(mut self, field: F) -> Self {
+        self.sort = Some(field.as_str());
+        self
+    }
+
+    /// Sort in a typed order.
+    pub fn order_by(mut self, order: Order) -> Self {
+        self.order = Some(order.as_str());
+        self
+    }
+
     pub fn page(mut self, page: u64) -> Self {
         self.page = Some(page);
         self
     }
 
+    /// Filter the list by additional query parameters, merged with any already set.
+    pub fn filter(mut self, filter: FilterOptions<'a>) -> Self {
+        self.filter = std::mem::take(&mut self.filter).merge(filter);
+        self
+    }
+
+    /// Append the encoded options to a URL's query, keeping any query it already has (eg. from a raw endpoint).
+    pub(crate) fn append_to(&self, url: &mut reqwest::Url) -> Result<()> {
+        let query = self.to_query_string()?;
+
+        match url.query() {
+            _ if query.is_empty() => (),
+            Some(existing) if !existing.is_empty() => {
+                let query = format!("{}&{}", existing, query);
+                url.set_query(Some(&query));
+            }
+            _ => url.set_query(Some(&query)),
+        }
+
+        Ok(())
+    }
+
     pub fn to_query_string(&self) -> Result<String> {
-        Ok(serde_qs::to_string(self)?)
-    }    
-}
\ No newline at end of file
+        let query = serde_qs::to_string(self)?;
+
+        if self.filter.is_empty() {
+            Ok(query)
+        } else if query.is_empty() {
+            Ok(self.filter.to_query_string())
+        } else {
+            Ok(format!("{}&{}", query, self.filter.to_query_string()))
+        }
+    }
+}
@@ -0,0 +1,215 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Client-side statistics of the requests sent to the API, so that operators can alert on its degradation as seen from
+//! their own side (eg. rising latency or error rates) rather than relying on the API's own metrics.
+//!
+//! With the `metrics` feature enabled, each request is also recorded via the [metrics](https://docs.rs/metrics) facade
+//! under the `bbb_api_requests_total`, `bbb_api_errors_total`, `bbb_api_rate_limited_total`, and
+//! `bbb_api_request_duration_seconds` names, labelled by endpoint.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::{StatusCode, Url};
+use serde::Serialize;
+
+/// The upper bounds (in milliseconds) of the buckets into which request latencies are counted.
+pub const LATENCY_BUCKETS: &[u64] = &[10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000];
+
+/// Latency statistics from a series of health check probes, as returned by `APIWrapper::ping_stats`.
+///
+/// Each probe's latency is measured from when it was scheduled until its response was parsed, and is split into the
+/// time stalled locally (awaiting a concurrency permit or the rate limiter) and the time awaiting the API.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PingStats {
+    pub samples: u32,
+    pub min: Duration,
+    pub mean: Duration,
+    /// The latency within which 95% of probes completed.
+    pub p95: Duration,
+    pub max: Duration,
+    /// The mean time each probe spent stalled locally.
+    pub stalled: Duration,
+    /// The mean time each probe spent between being sent and receiving its response headers.
+    pub network: Duration,
+}
+
+impl PingStats {
+    /// Compute the statistics of probes, given each one's total latency and the time it spent stalled and on the
+    /// network.
+    pub(crate) fn from_probes(probes: &[(Duration, Duration, Duration)]) -> PingStats {
+        if probes.is_empty() {
+            return PingStats::default();
+        }
+
+        let mut latencies: Vec<Duration> = probes.iter().map(|probe| probe.0).collect();
+        latencies.sort_unstable();
+
+        let samples = probes.len() as u32;
+        let p95 = latencies[((latencies.len() as f64 * 0.95).ceil() as usize).clamp(1, latencies.len()) - 1];
+
+        PingStats {
+            samples,
+            min: latencies[0],
+            mean: latencies.iter().sum::<Duration>() / samples,
+            p95,
+            max: latencies[latencies.len() - 1],
+            stalled: probes.iter().map(|probe| probe.1).sum::<Duration>() / samples,
+            network: probes.iter().map(|probe| probe.2).sum::<Duration>() / samples,
+        }
+    }
+}
+
+/// A point-in-time view of the statistics of every endpoint requested so far, keyed by endpoint (with IDs and names
+/// replaced by placeholders, eg. `resources/{id}/reviews`).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClientStats {
+    pub endpoints: BTreeMap<String, EndpointStats>,
+}
+
+impl ClientStats {
+    /// Returns the statistics of every endpoint combined.
+    pub fn total(&self) -> EndpointStats {
+        let mut total = EndpointStats::default();
+
+        for stats in self.endpoints.values() {
+            total.requests += stats.requests;
+            total.errors += stats.errors;
+            total.rate_limited += stats.rate_limited;
+            total.latency.merge(&stats.latency);
+        }
+
+        total
+    }
+}
+
+/// The statistics of a single endpoint, counted per attempt (ie. a resent or retried request counts again).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EndpointStats {
+    pub requests: u64,
+    /// The number of requests which failed to send or received an error status (other than being rate limited).
+    pub errors: u64,
+    /// The number of requests which were rejected due to rate limiting.
+    pub rate_limited: u64,
+    pub latency: LatencyHistogram,
+}
+
+/// A histogram of request latencies, from sending a request until its response headers were received.
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyHistogram {
+    /// The number of requests within each bucket of `LATENCY_BUCKETS`, followed by those slower than every bucket.
+    pub buckets: Vec<u64>,
+    pub count: u64,
+    pub sum_ms: u64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        LatencyHistogram { buckets: vec![0; LATENCY_BUCKETS.len() + 1], count: 0, sum_ms: 0 }
+    }
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, latency: Duration) {
+        let latency = latency.as_millis() as u64;
+        let bucket = LATENCY_BUCKETS.iter().position(|&bound| latency <= bound).unwrap_or(LATENCY_BUCKETS.len());
+
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.sum_ms += latency;
+    }
+
+    fn merge(&mut self, other: &LatencyHistogram) {
+        self.buckets.iter_mut().zip(&other.buckets).for_each(|(bucket, other)| *bucket += other);
+        self.count += other.count;
+        self.sum_ms += other.sum_ms;
+    }
+
+    /// Returns the mean latency, if any requests have been recorded.
+    pub fn mean(&self) -> Option<Duration> {
+        (self.count > 0).then(|| Duration::from_millis(self.sum_ms / self.count))
+    }
+
+    /// Returns an upper bound of the latency within which a fraction (eg. `0.99`) of requests completed, or `None` if
+    /// no requests have been recorded or the fraction falls beyond the largest bucket.
+    pub fn quantile(&self, fraction: f64) -> Option<Duration> {
+        let target = (self.count as f64 * fraction.clamp(0.0, 1.0)).ceil().max(1.0) as u64;
+        let mut seen = 0;
+
+        for (bucket, bound) in self.buckets.iter().zip(LATENCY_BUCKETS) {
+            seen += bucket;
+
+            if seen >= target {
+                return Some(Duration::from_millis(*bound));
+            }
+        }
+
+        None
+    }
+}
+
+pub(crate) struct StatsCollector {
+    endpoints: Mutex<HashMap<String, EndpointStats>>,
+}
+
+impl StatsCollector {
+    pub(crate) fn new() -> StatsCollector {
+        StatsCollector { endpoints: Mutex::new(HashMap::new()) }
+    }
+
+    /// Record an attempt at a request, given its response status (if one was received) and latency.
+    pub(crate) fn record(&self, endpoint: String, status: Option<StatusCode>, latency: Duration) {
+        let rate_limited = status == Some(StatusCode::TOO_MANY_REQUESTS);
+        // A revalidated cache entry (ie. `304 Not Modified`) is as successful as a full response.
+        let error = !rate_limited && !status.is_some_and(|status| status.is_success() || status.is_redirection());
+
+        #[cfg(feature = "metrics")]
+        {
+            let status = status.map_or_else(|| "none".to_string(), |status| status.as_u16().to_string());
+            metrics::counter!("bbb_api_requests_total", "endpoint" => endpoint.clone(), "status" => status)
+                .increment(1);
+            metrics::histogram!("bbb_api_request_duration_seconds", "endpoint" => endpoint.clone())
+                .record(latency.as_secs_f64());
+
+            if error {
+                metrics::counter!("bbb_api_errors_total", "endpoint" => endpoint.clone()).increment(1);
+            }
+            if rate_limited {
+                metrics::counter!("bbb_api_rate_limited_total", "endpoint" => endpoint.clone()).increment(1);
+            }
+        }
+
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let stats = endpoints.entry(endpoint).or_default();
+
+        stats.requests += 1;
+        stats.errors += error as u64;
+        stats.rate_limited += rate_limited as u64;
+        stats.latency.record(latency);
+    }
+
+    pub(crate) fn snapshot(&self) -> ClientStats {
+        ClientStats { endpoints: self.endpoints.lock().unwrap().clone().into_iter().collect() }
+    }
+}
+
+/// Returns the endpoint of a URL relative to the base URL, with IDs and names replaced by placeholders so that each
+/// endpoint is counted once regardless of what it was requested for.
+pub(crate) fn endpoint_of(base: &Url, url: &Url) -> String {
+    let path = url.path().strip_prefix(base.path().trim_end_matches('/')).unwrap_or(url.path());
+    let mut endpoint = Vec::new();
+
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        if endpoint.last() == Some(&"usernames") {
+            endpoint.push("{name}");
+        } else if segment.bytes().all(|byte| byte.is_ascii_digit()) {
+            endpoint.push("{id}");
+        } else {
+            endpoint.push(segment);
+        }
+    }
+
+    endpoint.join("/")
+}
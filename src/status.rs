@@ -0,0 +1,106 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Probing of an external status page, to distinguish local problems (eg. a revoked token) from an API-wide outage.
+//!
+//! The probe expects a status summary in the widely-used Statuspage format (ie. a `status.json` document containing
+//! an `indicator` and `description`). The status page's URL is supplied by the caller.
+
+use crate::error::{APIError, Result};
+use crate::http;
+use crate::APIWrapper;
+
+use serde::{Deserialize, Serialize};
+
+/// The overall severity reported by a status page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusIndicator {
+    /// All systems are operational.
+    None,
+    Minor,
+    Major,
+    Critical,
+    /// Planned maintenance is in progress.
+    Maintenance,
+}
+
+/// The overall status reported by a status page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub indicator: StatusIndicator,
+    pub description: String,
+}
+
+impl ServiceStatus {
+    /// Returns whether or not the status page reports an ongoing incident or maintenance.
+    pub fn is_degraded(&self) -> bool {
+        self.indicator != StatusIndicator::None
+    }
+}
+
+#[derive(Deserialize)]
+struct StatusSummary {
+    status: ServiceStatus,
+}
+
+/// A conclusion as to why requests may be failing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Verdict {
+    /// The API is reachable and accepted our token.
+    Healthy,
+    /// The API is reachable but rejected our token (or its permissions).
+    TokenRejected,
+    /// The API is reachable, but a request failed for a reason unrelated to our token (eg. it was rate limited, its
+    /// response couldn't be decoded, or it couldn't be sent).
+    RequestFailed,
+    /// The API is unreachable and the status page (or the API itself) reports an outage or maintenance.
+    ApiDown,
+    /// The API is unreachable but no outage is reported, which suggests a local network problem.
+    Unreachable,
+}
+
+/// The outcome of diagnosing the wrapper's connectivity.
+#[derive(Debug)]
+pub struct Diagnosis {
+    pub verdict: Verdict,
+    /// The error encountered whilst making requests to the API, if any.
+    pub error: Option<APIError>,
+    /// The status reported by the status page, if one was probed and could be fetched.
+    pub status: Option<ServiceStatus>,
+}
+
+/// Fetch the overall status from a status page's summary URL.
+pub(crate) async fn fetch(wrapper: &APIWrapper, url: &str) -> Result<ServiceStatus> {
+    let body = http::get_external(wrapper, url).await?;
+    Ok(http::decode::<StatusSummary>(&mut Vec::from(body))?.status)
+}
+
+/// Diagnose the wrapper's connectivity, consulting a status page if the API can't be reached.
+pub(crate) async fn diagnose(wrapper: &APIWrapper, status_url: Option<&str>) -> Diagnosis {
+    // The health endpoint tells us whether the API is reachable, whilst fetching ourselves exercises the token.
+    let error = match wrapper.health().await {
+        Ok(()) => match wrapper.members().fetch_self().await {
+            Ok(_) => return Diagnosis { verdict: Verdict::Healthy, error: None, status: None },
+            // Fetching ourselves takes no parameters, so any error the API itself returns is due to our token.
+            Err(error @ APIError::Api { .. }) => {
+                return Diagnosis { verdict: Verdict::TokenRejected, error: Some(error), status: None };
+            }
+            Err(error) if !error.is_retryable() => {
+                return Diagnosis { verdict: Verdict::RequestFailed, error: Some(error), status: None };
+            }
+            Err(error) => error,
+        },
+        Err(error) => error,
+    };
+
+    let status = match status_url {
+        Some(url) => fetch(wrapper, url).await.ok(),
+        None => None,
+    };
+
+    let reported_down = status.as_ref().is_some_and(ServiceStatus::is_degraded);
+
+    let verdict = if reported_down || error.is_maintenance() { Verdict::ApiDown } else { Verdict::Unreachable };
+    Diagnosis { verdict, error: Some(error), status }
+}
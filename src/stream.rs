@@ -0,0 +1,399 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Incremental parsing of list responses, yielding items as they're decoded.
+//!
+//! Rather than buffering a whole response body and then its decoded list, the body is read chunk-by-chunk and each
+//! array element is decoded as soon as its final byte arrives. Peak memory is therefore bounded by the size of a chunk
+//! plus a single item, regardless of how many pages are walked.
+//!
+//! Also holds watches, which poll a list on an interval and yield only the items which are new since the last poll.
+
+use crate::endpoints::Endpoint;
+use crate::error::{APIError, Result};
+use crate::http::{self, APIResponse, Body};
+use crate::options::{Priority, RequestOptions};
+use crate::pagination;
+use crate::runtime;
+use crate::sort::SortOptions;
+use crate::APIWrapper;
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures_util::stream::{self, Stream};
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+/// A list of items which are fetched and decoded incrementally.
+///
+/// If a page is given within the sort options, only that page is streamed. Otherwise, every page is walked from the
+/// first until an empty one is returned.
+///
+/// # Example
+/// ```no_run
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// let mut purchases = wrapper.resources().purchases().stream(1, Default::default());
+///
+/// while let Some(purchase) = purchases.next().await {
+///     println!("{}", purchase?.purchase_id());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ItemStream<'a, T> {
+    wrapper: &'a APIWrapper,
+    endpoint: Endpoint<'a>,
+    sort: SortOptions<'a>,
+    page: u64,
+    walk_pages: bool,
+    current: Option<(Body, ArrayScanner)>,
+    page_items: u64,
+    finished: bool,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> ItemStream<'a, T>
+where
+    T: DeserializeOwned,
+{
+    pub(crate) fn new(wrapper: &'a APIWrapper, endpoint: Endpoint<'a>, sort: SortOptions<'a>) -> ItemStream<'a, T> {
+        ItemStream {
+            wrapper,
+            endpoint,
+            page: sort.page.unwrap_or(1),
+            walk_pages: sort.page.is_none(),
+            sort,
+            current: None,
+            page_items: 0,
+            finished: false,
+            _item: PhantomData,
+        }
+    }
+
+    /// Returns the next item, or `None` once every item has been yielded.
+    ///
+    /// The stream ends after yielding an error.
+    pub async fn next(&mut self) -> Option<Result<T>> {
+        if self.finished {
+            return None;
+        }
+
+        match self.advance().await {
+            Ok(Some(item)) => Some(Ok(item)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(error) => {
+                self.finished = true;
+                Some(Err(error))
+            }
+        }
+    }
+
+    /// Convert into a `futures` stream, for use with stream combinators (eg. `take_while` or `try_for_each`).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use futures_util::TryStreamExt;
+    /// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+    /// let reviews = wrapper.resources().reviews().stream(1, Default::default()).into_stream();
+    ///
+    /// reviews
+    ///     .try_for_each(|review| async move {
+    ///         println!("{}", review);
+    ///         Ok(())
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_stream(self) -> impl Stream<Item = Result<T>> + 'a
+    where
+        T: 'a,
+    {
+        stream::unfold(self, |mut items| async move { items.next().await.map(|item| (item, items)) })
+    }
+
+    async fn advance(&mut self) -> Result<Option<T>> {
+        loop {
+            let (body, scanner) = match &mut self.current {
+                Some(current) => (&mut current.0, &mut current.1),
+                None => {
+                    self.sort.page = Some(self.page);
+
+                    let mut url = self.endpoint.url(&self.wrapper.base_url);
+                    self.sort.append_to(&mut url)?;
+
+                    let response = http::get_raw(self.wrapper, &url).await?;
+                    self.page_items = 0;
+
+                    let current = self.current.insert((Body::new(response), ArrayScanner::default()));
+                    (&mut current.0, &mut current.1)
+                }
+            };
+
+            if let Some(element) = scanner.scan()? {
+                let item = http::decode(&mut scanner.buffer[element])?;
+                scanner.consume();
+                self.page_items += 1;
+
+                return Ok(Some(item));
+            }
+
+            if scanner.state == ScanState::Finished {
+                self.current = None;
+
+                if !self.walk_pages || self.page_items == 0 {
+                    return Ok(None);
+                }
+
+                self.page += 1;
+                continue;
+            }
+
+            match body.chunk().await? {
+                Some(chunk) => scanner.buffer.extend_from_slice(&chunk),
+                None => {
+                    let message = "Response body ended before the list was complete".to_string();
+                    return Err(APIError::Deserialize { endpoint: None, source: message.into() });
+                }
+            }
+        }
+    }
+}
+
+type ListPage<'a, T> = Box<dyn Fn(u64) -> Pin<Box<dyn Future<Output = Result<Vec<T>>> + 'a>> + 'a>;
+
+/// Wait for a watch's interval (unless this is its first poll), then make the poll with background priority so that it
+/// yields to other requests whilst rate limited.
+pub(crate) async fn poll_on_interval<F>(first: bool, interval: Duration, poll: F) -> Result<()>
+where
+    F: Future<Output = Result<()>>,
+{
+    if !first {
+        runtime::sleep(interval).await;
+    }
+
+    RequestOptions::default().with_priority(Priority::Background).scope(poll).await
+}
+
+/// A watch of a list whose items have increasing IDs (eg. a conversation's or thread's replies), yielding each new item
+/// once (oldest first).
+///
+/// The first poll establishes a baseline and yields no items, so pre-existing items aren't reported as new (unless
+/// resuming via `since`). Each later poll walks the list newest-first, stopping at the first page which reaches those
+/// already seen, and yields the items with an ID greater than any seen before.
+///
+/// A poll which fails yields its error, and polling resumes after the interval if the watch is awaited again.
+pub struct IdWatch<'a, T> {
+    list_newest: ListPage<'a, T>,
+    id_of: fn(&T) -> u64,
+    interval: Duration,
+    last_seen: Option<u64>,
+    pending: VecDeque<T>,
+    polled: bool,
+}
+
+impl<'a, T: 'a> IdWatch<'a, T> {
+    /// Construct a watch from a function which lists a page of items newest-first, and one which returns an item's ID.
+    pub(crate) fn new<F, Fut>(interval: Duration, id_of: fn(&T) -> u64, list_newest: F) -> IdWatch<'a, T>
+    where
+        F: Fn(u64) -> Fut + 'a,
+        Fut: Future<Output = Result<Vec<T>>> + 'a,
+    {
+        IdWatch {
+            list_newest: Box::new(move |page| Box::pin(list_newest(page))),
+            id_of,
+            interval,
+            last_seen: None,
+            pending: VecDeque::new(),
+            polled: false,
+        }
+    }
+
+    /// Resume from a previously seen item, yielding every item after it rather than establishing a baseline.
+    pub fn since(mut self, id: u64) -> Self {
+        self.last_seen = Some(id);
+        self
+    }
+
+    /// Returns the ID of the most recent item seen, if any (eg. to persist and resume from via `since`).
+    pub fn last_seen(&self) -> Option<u64> {
+        self.last_seen
+    }
+
+    /// Wait for the next new item.
+    pub async fn next(&mut self) -> Result<T> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Ok(item);
+            }
+
+            let first = !std::mem::replace(&mut self.polled, true);
+            poll_on_interval(first, self.interval, self.poll()).await?;
+        }
+    }
+
+    /// Convert into a `futures` stream, for use with stream combinators.
+    pub fn into_stream(self) -> impl Stream<Item = Result<T>> + 'a {
+        stream::unfold(self, |mut watch| async move { Some((watch.next().await, watch)) })
+    }
+
+    async fn poll(&mut self) -> Result<()> {
+        let (list_newest, id_of) = (&self.list_newest, self.id_of);
+
+        let Some(last_seen) = self.last_seen else {
+            let newest = list_newest(1).await?;
+            self.last_seen = Some(newest.iter().map(id_of).max().unwrap_or(0));
+            return Ok(());
+        };
+
+        let items = pagination::collect_until(list_newest, |page| page.iter().any(|item| id_of(item) <= last_seen));
+        let mut items: Vec<T> = items.await?.into_iter().filter(|item| id_of(item) > last_seen).collect();
+
+        items.sort_unstable_by_key(id_of);
+        self.last_seen = Some(items.last().map_or(last_seen, id_of));
+        self.pending.extend(items);
+
+        Ok(())
+    }
+}
+
+#[derive(Default, PartialEq, Eq)]
+enum ScanState {
+    /// Searching the top-level response object for the start of the `data` array.
+    #[default]
+    Envelope,
+    /// The `data` key has been found and its array is expected to follow.
+    AwaitingArray,
+    /// Within the `data` array.
+    Items,
+    Finished,
+}
+
+/// A resumable scanner which locates the bounds of each element of the `data` array within a partially-read body.
+///
+/// The scanner only tracks nesting and string boundaries; decoding the elements themselves is left to serde.
+#[derive(Default)]
+struct ArrayScanner {
+    buffer: Vec<u8>,
+    cursor: usize,
+    state: ScanState,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    string_start: usize,
+    is_data_key: bool,
+    element_start: Option<usize>,
+}
+
+impl ArrayScanner {
+    /// Scan the buffered bytes, returning the bounds of the next complete element if one is available.
+    fn scan(&mut self) -> Result<Option<Range<usize>>> {
+        while self.cursor < self.buffer.len() {
+            let byte = self.buffer[self.cursor];
+            self.cursor += 1;
+
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+
+                    if self.state == ScanState::Envelope && self.depth == 1 {
+                        self.is_data_key = &self.buffer[self.string_start..self.cursor - 1] == b"data";
+                    }
+                }
+
+                continue;
+            }
+
+            match self.state {
+                ScanState::Envelope => match byte {
+                    b'"' => {
+                        self.in_string = true;
+                        self.string_start = self.cursor;
+                    }
+                    b'{' | b'[' => self.depth += 1,
+                    b'}' | b']' => {
+                        self.depth = self.depth.saturating_sub(1);
+
+                        // The response had no data array, so there's nothing to yield unless it reported an error
+                        // instead. Nothing has been consumed yet, so the buffer holds the whole response.
+                        if self.depth == 0 {
+                            self.state = ScanState::Finished;
+
+                            let response: APIResponse<IgnoredAny> = http::decode(&mut self.buffer[..self.cursor])?;
+                            return match response.error {
+                                Some(error) if !response.is_success() => Err(error.into()),
+                                _ => Ok(None),
+                            };
+                        }
+                    }
+                    b':' if self.depth == 1 && self.is_data_key => self.state = ScanState::AwaitingArray,
+                    _ => {}
+                },
+                ScanState::AwaitingArray => match byte {
+                    b' ' | b'\t' | b'\n' | b'\r' => {}
+                    b'[' => {
+                        self.state = ScanState::Items;
+                        self.depth = 0;
+                    }
+                    b'n' => {
+                        self.state = ScanState::Finished;
+                        return Ok(None);
+                    }
+                    _ => {
+                        let message = "Response data wasn't a list".to_string();
+                        return Err(APIError::Deserialize { endpoint: None, source: message.into() });
+                    }
+                },
+                ScanState::Items => match byte {
+                    b' ' | b'\t' | b'\n' | b'\r' => {}
+                    b',' if self.depth == 0 => {
+                        if let Some(element) = self.take_element() {
+                            return Ok(Some(element));
+                        }
+                    }
+                    b']' if self.depth == 0 => {
+                        self.state = ScanState::Finished;
+                        return Ok(self.take_element());
+                    }
+                    b'}' | b']' => self.depth -= 1,
+                    _ => {
+                        if self.depth == 0 && self.element_start.is_none() {
+                            self.element_start = Some(self.cursor - 1);
+                        }
+
+                        match byte {
+                            b'"' => self.in_string = true,
+                            b'{' | b'[' => self.depth += 1,
+                            _ => {}
+                        }
+                    }
+                },
+                ScanState::Finished => return Ok(None),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the bounds of the element which was just terminated (excluding its delimiter).
+    fn take_element(&mut self) -> Option<Range<usize>> {
+        self.element_start.take().map(|start| start..self.cursor - 1)
+    }
+
+    /// Discard all bytes which have already been scanned.
+    fn consume(&mut self) {
+        self.buffer.drain(..self.cursor);
+        self.cursor = 0;
+    }
+}
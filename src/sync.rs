@@ -0,0 +1,345 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A feature-gated engine which maintains a local SQLite mirror of data relating to owned resources.
+//!
+//! Downloads are synced incrementally - pages are walked newest-first and we stop as soon as a page contains a row at
+//! or below the newest one we've already mirrored. Purchases (eg. refunds) and reviews (eg. author responses) may be
+//! modified after creation, so pages are instead walked back until they pass the lookback window before the newest
+//! mirrored row, and any re-fetched rows which differ from the mirror are updated. Licenses are fully refreshed on
+//! each sync.
+
+use crate::data::resources::{BasicResourceData, DownloadData, LicenseData, PurchaseData, ReviewData};
+use crate::error::{APIError, Result};
+use crate::pagination;
+use crate::sort::{DownloadSortField, Order, PurchaseSortField, ReviewSortField, SortOptions};
+use crate::APIWrapper;
+
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::{params, Connection};
+
+/// The error code we attach to failures of the local mirror database.
+const SYNC_STORAGE_CODE: &str = "SyncStorageError";
+
+/// The default period before the newest mirrored purchase or review within which rows are re-fetched on each sync.
+pub const DEFAULT_SYNC_LOOKBACK: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS resources (
+        resource_id INTEGER PRIMARY KEY,
+        author_id INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        price REAL NOT NULL,
+        currency TEXT NOT NULL,
+        last_synced INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS purchases (
+        purchase_id INTEGER PRIMARY KEY,
+        resource_id INTEGER NOT NULL,
+        purchaser_id INTEGER NOT NULL,
+        license_id INTEGER NOT NULL,
+        renewal INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        price REAL NOT NULL,
+        currency TEXT NOT NULL,
+        purchase_date INTEGER NOT NULL,
+        validation_date INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS licenses (
+        license_id INTEGER PRIMARY KEY,
+        resource_id INTEGER NOT NULL,
+        purchaser_id INTEGER NOT NULL,
+        validated INTEGER NOT NULL,
+        active INTEGER NOT NULL,
+        permanent INTEGER NOT NULL,
+        start_date INTEGER NOT NULL,
+        end_date INTEGER NOT NULL,
+        previous_end_date INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS downloads (
+        download_id INTEGER PRIMARY KEY,
+        resource_id INTEGER NOT NULL,
+        version_id INTEGER NOT NULL,
+        downloader_id INTEGER NOT NULL,
+        download_date INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS reviews (
+        review_id INTEGER PRIMARY KEY,
+        resource_id INTEGER NOT NULL,
+        reviewer_id INTEGER NOT NULL,
+        review_date INTEGER NOT NULL,
+        rating INTEGER NOT NULL,
+        message TEXT NOT NULL,
+        response TEXT NOT NULL
+    );
+";
+
+/// The number of rows which were inserted or updated during a single sync.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncReport {
+    pub resources: u64,
+    pub purchases: u64,
+    pub licenses: u64,
+    pub downloads: u64,
+    pub reviews: u64,
+}
+
+/// An engine which mirrors purchases, licenses, downloads, and reviews for all owned resources into SQLite.
+pub struct SyncEngine<'a> {
+    wrapper: &'a APIWrapper,
+    connection: Connection,
+    lookback: Duration,
+}
+
+impl<'a> SyncEngine<'a> {
+    /// Open (or create) a mirror database at the provided path.
+    pub fn open<P: AsRef<Path>>(wrapper: &'a APIWrapper, path: P) -> Result<SyncEngine<'a>> {
+        Self::from_connection(wrapper, Connection::open(path)?)
+    }
+
+    /// Open a mirror database which only lives in memory for the lifetime of this engine.
+    pub fn open_in_memory(wrapper: &'a APIWrapper) -> Result<SyncEngine<'a>> {
+        Self::from_connection(wrapper, Connection::open_in_memory()?)
+    }
+
+    /// Construct an engine from an existing connection, creating the mirror tables if they don't already exist.
+    pub fn from_connection(wrapper: &'a APIWrapper, connection: Connection) -> Result<SyncEngine<'a>> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(SyncEngine { wrapper, connection, lookback: DEFAULT_SYNC_LOOKBACK })
+    }
+
+    /// Set the period before the newest mirrored purchase or review within which rows are re-fetched and updated.
+    ///
+    /// Defaults to [`DEFAULT_SYNC_LOOKBACK`]. Changes to rows older than this window won't be picked up.
+    pub fn lookback(mut self, lookback: Duration) -> Self {
+        self.lookback = lookback;
+        self
+    }
+
+    /// Returns the underlying connection so that the mirror can be queried.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Perform a single incremental sync of all owned resources.
+    pub async fn sync(&mut self) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+        let wrapper = self.wrapper;
+
+        let owned: Vec<BasicResourceData> = pagination::collect_all(|page| async move {
+            wrapper.resources().list_owned(Some(&SortOptions::default().page(page))).await
+        })
+        .await?;
+
+        for resource in &owned {
+            self.store_resource(resource)?;
+            report.resources += 1;
+
+            report.purchases += self.sync_purchases(*resource.resource_id()).await?;
+            report.licenses += self.sync_licenses(*resource.resource_id()).await?;
+            report.downloads += self.sync_downloads(*resource.resource_id()).await?;
+            report.reviews += self.sync_reviews(*resource.resource_id()).await?;
+        }
+
+        Ok(report)
+    }
+
+    /// Sync on a fixed interval, indefinitely.
+    ///
+    /// Failed syncs are logged and retried on the next tick rather than ending the loop.
+    pub async fn run(&mut self, interval: Duration) {
+        let mut interval = crate::runtime::Interval::new(interval);
+
+        loop {
+            interval.tick().await;
+
+            match self.sync().await {
+                Ok(report) => log::debug!("Mirror sync completed: {:?}", report),
+                Err(error) => log::warn!("Mirror sync failed: {:?}", error),
+            }
+        }
+    }
+
+    async fn sync_purchases(&mut self, resource_id: u64) -> Result<u64> {
+        let wrapper = self.wrapper;
+        let cutoff = self.cutoff("purchases", "purchase_date", resource_id)?;
+
+        let purchases: Vec<PurchaseData> = pagination::collect_until(
+            |page| async move {
+                let sort =
+                    SortOptions::default().sort_by(PurchaseSortField::PurchaseDate).order_by(Order::Desc).page(page);
+                wrapper.resources().purchases().list(resource_id, Some(&sort)).await
+            },
+            |batch| batch.iter().any(|p| Some(*p.purchase_date()) < cutoff),
+        )
+        .await?;
+
+        let mut changed = 0;
+        let transaction = self.connection.transaction()?;
+        for purchase in &purchases {
+            changed += transaction.execute(
+                "INSERT INTO purchases VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+                 ON CONFLICT (purchase_id) DO UPDATE SET
+                    license_id = excluded.license_id, renewal = excluded.renewal, status = excluded.status,
+                    price = excluded.price, currency = excluded.currency, validation_date = excluded.validation_date
+                 WHERE (license_id, renewal, status, price, currency, validation_date)
+                    IS NOT (excluded.license_id, excluded.renewal, excluded.status, excluded.price,
+                        excluded.currency, excluded.validation_date)",
+                params![
+                    purchase.purchase_id(),
+                    resource_id,
+                    purchase.purchaser_id(),
+                    purchase.license_id(),
+                    purchase.renewal(),
+                    purchase.status(),
+                    purchase.price(),
+                    purchase.currency(),
+                    purchase.purchase_date(),
+                    purchase.validation_date(),
+                ],
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(changed as u64)
+    }
+
+    async fn sync_licenses(&mut self, resource_id: u64) -> Result<u64> {
+        let wrapper = self.wrapper;
+
+        let licenses: Vec<LicenseData> = pagination::collect_all(|page| async move {
+            wrapper.resources().licenses().list(resource_id, Some(&SortOptions::default().page(page))).await
+        })
+        .await?;
+
+        let transaction = self.connection.transaction()?;
+        for license in &licenses {
+            transaction.execute(
+                "INSERT OR REPLACE INTO licenses VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    license.license_id(),
+                    resource_id,
+                    license.purchaser_id(),
+                    license.validated(),
+                    license.active(),
+                    license.permanent(),
+                    license.start_date(),
+                    license.end_date(),
+                    license.previous_end_date(),
+                ],
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(licenses.len() as u64)
+    }
+
+    async fn sync_downloads(&mut self, resource_id: u64) -> Result<u64> {
+        let wrapper = self.wrapper;
+        let newest = newest_id(&self.connection, "downloads", "download_id", resource_id)?;
+
+        let downloads: Vec<DownloadData> = pagination::collect_until(
+            |page| async move {
+                let sort =
+                    SortOptions::default().sort_by(DownloadSortField::DownloadDate).order_by(Order::Desc).page(page);
+                wrapper.resources().downloads().list(resource_id, Some(&sort)).await
+            },
+            |batch| batch.iter().any(|d| Some(*d.download_id()) <= newest),
+        )
+        .await?;
+
+        let transaction = self.connection.transaction()?;
+        for download in &downloads {
+            transaction.execute(
+                "INSERT OR REPLACE INTO downloads VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    download.download_id(),
+                    resource_id,
+                    download.version_id(),
+                    download.downloader_id(),
+                    download.download_date(),
+                ],
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(downloads.len() as u64)
+    }
+
+    async fn sync_reviews(&mut self, resource_id: u64) -> Result<u64> {
+        let wrapper = self.wrapper;
+        let cutoff = self.cutoff("reviews", "review_date", resource_id)?;
+
+        let reviews: Vec<ReviewData> = pagination::collect_until(
+            |page| async move {
+                let sort = SortOptions::default().sort_by(ReviewSortField::ReviewDate).order_by(Order::Desc).page(page);
+                wrapper.resources().reviews().list(resource_id, Some(&sort)).await
+            },
+            |batch| batch.iter().any(|r| Some(*r.review_date()) < cutoff),
+        )
+        .await?;
+
+        let mut changed = 0;
+        let transaction = self.connection.transaction()?;
+        for review in &reviews {
+            changed += transaction.execute(
+                "INSERT INTO reviews VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (review_id) DO UPDATE SET
+                    rating = excluded.rating, message = excluded.message, response = excluded.response
+                 WHERE (rating, message, response) IS NOT (excluded.rating, excluded.message, excluded.response)",
+                params![
+                    review.review_id(),
+                    resource_id,
+                    review.reviewer_id(),
+                    review.review_date(),
+                    review.rating(),
+                    review.message(),
+                    review.response(),
+                ],
+            )?;
+        }
+        transaction.commit()?;
+
+        Ok(changed as u64)
+    }
+
+    /// Returns the date before which fetched rows fall outside of the lookback window, if any rows are mirrored.
+    fn cutoff(&self, table: &str, column: &str, resource_id: u64) -> Result<Option<u64>> {
+        let query = format!("SELECT MAX({}) FROM {} WHERE resource_id = ?1", column, table);
+        let newest: Option<u64> = self.connection.query_row(&query, [resource_id], |row| row.get(0))?;
+        Ok(newest.map(|newest| newest.saturating_sub(self.lookback.as_secs())))
+    }
+
+    fn store_resource(&self, resource: &BasicResourceData) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO resources VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                resource.resource_id(),
+                resource.author_id(),
+                resource.title(),
+                resource.price(),
+                resource.currency(),
+                crate::throttler::unix_timestamp() / 1000,
+            ],
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Returns the newest (ie. largest) mirrored ID within a table for a specific resource, if any rows exist.
+///
+/// IDs are allocated incrementally by the API, so any fetched row with an ID at or below this value has already been
+/// mirrored by a previous sync.
+fn newest_id(connection: &Connection, table: &str, column: &str, resource_id: u64) -> Result<Option<u64>> {
+    let query = format!("SELECT MAX({}) FROM {} WHERE resource_id = ?1", column, table);
+    Ok(connection.query_row(&query, [resource_id], |row| row.get(0))?)
+}
+
+impl From<rusqlite::Error> for APIError {
+    fn from(value: rusqlite::Error) -> APIError {
+        APIError::local(SYNC_STORAGE_CODE, format!("Mirror database error: {}", value))
+    }
+}
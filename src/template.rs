@@ -0,0 +1,207 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A small placeholder templating system for composing forum and conversation messages.
+//!
+//! Templates contain placeholders such as `{username}`, which are replaced with values from a context when rendered.
+//! Literal braces are written as `{{` and `}}`. Substituted values are escaped so that they can't inject BBCode into
+//! the surrounding message (eg. a username containing `[url=...]`).
+
+use crate::data::members::MemberData;
+use crate::data::resources::{LicenseData, ResourceData};
+use crate::error::{APIError, Result};
+use crate::timestamps::format_utc_date;
+
+use std::collections::HashMap;
+
+/// The error code we attach to templates which are malformed or reference a value that wasn't provided.
+const TEMPLATE_CODE: &str = "TemplateError";
+
+fn template_error(message: String) -> APIError {
+    APIError::local(TEMPLATE_CODE, message)
+}
+
+enum Part {
+    Literal(String),
+    Placeholder(String),
+}
+
+/// A parsed template which can be rendered many times.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::template::{Template, TemplateContext};
+/// # fn main() -> bbb_api_wrapper::error::Result<()> {
+/// let template = Template::parse("Thanks for buying {resource_title}, {username}!")?;
+/// let context = TemplateContext::new().set("resource_title", "Example").set("username", "[b]Harry[/b]");
+///
+/// assert_eq!(
+///     "Thanks for buying Example, [PLAIN][b]Harry[/b][/PLAIN]!",
+///     template.render(&context)?
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Parse a template, failing if it contains an unclosed or empty placeholder, or an unmatched closing brace.
+    pub fn parse(source: &str) -> Result<Template> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(next) = chars.next() {
+            match next {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(next) => name.push(next),
+                            None => return Err(template_error(format!("Unclosed placeholder '{{{}'", name))),
+                        }
+                    }
+
+                    let name = name.trim();
+
+                    if name.is_empty() {
+                        return Err(template_error("Empty placeholder".to_string()));
+                    }
+
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+
+                    parts.push(Part::Placeholder(name.to_string()));
+                }
+                '}' => return Err(template_error("Unmatched '}' (use '}}' for a literal brace)".to_string())),
+                next => literal.push(next),
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Template { parts })
+    }
+
+    /// Returns the names of the placeholders used within this template, in order of first use.
+    pub fn placeholders(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+
+        for part in &self.parts {
+            if let Part::Placeholder(name) = part {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+        }
+
+        names
+    }
+
+    /// Render this template with values from a context, escaping each value for BBCode.
+    ///
+    /// Fails if the context is missing a value for any placeholder.
+    pub fn render(&self, context: &TemplateContext) -> Result<String> {
+        let mut rendered = String::new();
+
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => rendered.push_str(literal),
+                Part::Placeholder(name) => match context.values.get(name) {
+                    Some(value) => rendered.push_str(&escape_bbcode(value)),
+                    None => return Err(template_error(format!("No value provided for placeholder '{{{}}}'", name))),
+                },
+            }
+        }
+
+        Ok(rendered)
+    }
+}
+
+/// The values available to placeholders when rendering a template.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    values: HashMap<String, String>,
+}
+
+impl TemplateContext {
+    pub fn new() -> TemplateContext {
+        TemplateContext::default()
+    }
+
+    /// Set the value of a placeholder, replacing any previous value.
+    pub fn set(mut self, name: &str, value: impl ToString) -> Self {
+        self.values.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Set `{username}` and `{member_id}` from a member.
+    pub fn member(self, member: &MemberData) -> Self {
+        self.set("username", member.username()).set("member_id", member.member_id())
+    }
+
+    /// Set `{resource_title}`, `{resource_id}`, and `{resource_price}` from a resource.
+    pub fn resource(self, resource: &ResourceData) -> Self {
+        self.set("resource_title", resource.title())
+            .set("resource_id", resource.resource_id())
+            .set("resource_price", resource.formatted_price())
+    }
+
+    /// Set `{license_id}`, `{license_start_date}`, and `{license_end_date}` (as UTC dates) from a license.
+    ///
+    /// The end date of a permanent license is given as "never".
+    pub fn license(self, license: &LicenseData) -> Self {
+        let end_date = if *license.permanent() { "never".to_string() } else { format_utc_date(*license.end_date()) };
+
+        self.set("license_id", license.license_id())
+            .set("license_start_date", format_utc_date(*license.start_date()))
+            .set("license_end_date", end_date)
+    }
+}
+
+/// Escape a value so that any BBCode within it is displayed literally.
+///
+/// Values containing square brackets are wrapped within `[PLAIN]` tags, with any closing tag in the value itself
+/// broken up by a zero-width space so that it can't end the wrapping early.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::template::escape_bbcode;
+/// assert_eq!("Harry", escape_bbcode("Harry"));
+/// assert_eq!("[PLAIN]a[\u{200B}/plain][b]b[/PLAIN]", escape_bbcode("a[/plain][b]b"));
+/// ```
+pub fn escape_bbcode(value: &str) -> String {
+    if !value.contains(['[', ']']) {
+        return value.to_string();
+    }
+
+    let mut escaped = String::with_capacity(value.len() + 16);
+    escaped.push_str("[PLAIN]");
+
+    let mut rest = value;
+
+    while let Some(index) = rest.to_ascii_lowercase().find("[/plain") {
+        escaped.push_str(&rest[..index + 1]);
+        escaped.push('\u{200B}');
+        rest = &rest[index + 1..];
+    }
+
+    escaped.push_str(rest);
+    escaped.push_str("[/PLAIN]");
+    escaped
+}
@@ -3,86 +3,357 @@
 
 //! Holds key types for tracking our compliance with the API's rate limits.
 
+use crate::options::Priority;
+use crate::pool::Rotation;
+use crate::rate_limit::{LocalBackend, RateLimitBackend};
+use crate::runtime::{SystemTime, UNIX_EPOCH};
+
+use std::collections::VecDeque;
 use std::convert::TryInto;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use reqwest::header::HeaderValue;
+
+/// The length of the window over which requests-per-minute ceilings are enforced, in milliseconds.
+const CEILING_WINDOW: u64 = 60_000;
+
+/// How long a background request stalls for (in milliseconds) before checking again whether interactive requests are
+/// still waiting.
+const YIELD_STALL: u64 = 100;
+
+/// A token bucket which proactively paces requests to a known budget, rather than waiting to be told to back off.
+///
+/// Tokens refill continuously at the budget's per-minute rate, up to a burst capacity, and each request takes one.
+pub struct TokenBucket {
+    per_minute: u64,
+    burst: u64,
+    tokens: f64,
+    last_refill: u64,
+}
+
+impl TokenBucket {
+    /// Construct a full bucket.
+    pub fn new(per_minute: u64, burst: u64, time: u64) -> TokenBucket {
+        let burst = burst.max(1);
+        TokenBucket { per_minute: per_minute.max(1), burst, tokens: burst as f64, last_refill: time }
+    }
+
+    fn refill(&mut self, time: u64) {
+        let elapsed = time.saturating_sub(self.last_refill) as f64;
+        let refilled = elapsed * self.per_minute as f64 / CEILING_WINDOW as f64;
+
+        self.tokens = (self.tokens + refilled).min(self.burst as f64);
+        self.last_refill = time;
+    }
 
+    /// Returns how long (in milliseconds) until a token is available, having refilled up to a timestamp.
+    fn wait(&mut self, time: u64) -> u64 {
+        self.refill(time);
+
+        if self.tokens >= 1.0 {
+            0
+        } else {
+            (((1.0 - self.tokens) * CEILING_WINDOW as f64 / self.per_minute as f64).ceil() as u64).max(1)
+        }
+    }
+}
+
+/// The class of a request, each of which is rate limited separately by the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RequestType {
-    READ,
-    WRITE,
+    Read,
+    Write,
+}
+
+/// A token within a pool, along with its own back-off.
+pub struct PooledToken {
+    /// The token's 'Authorization' header value, or `None` for the primary token (which the wrapper holds itself, so
+    /// that it can be replaced).
+    pub header: Option<HeaderValue>,
+    pub backend: LocalBackend,
+    /// The UNIX timestamp (in milliseconds) at which the token was last chosen to send a read.
+    pub last_used: AtomicU64,
+}
+
+/// The tokens of a pool which reads are rotated between, the first being the primary token.
+pub struct TokenRotation {
+    pub tokens: Vec<PooledToken>,
+    pub rotation: Rotation,
+    pub next: AtomicUsize,
+}
+
+impl TokenRotation {
+    /// Construct a rotation between the primary token and the header values of any others.
+    pub fn new(secondaries: Vec<HeaderValue>, rotation: Rotation) -> TokenRotation {
+        let tokens = std::iter::once(None)
+            .chain(secondaries.into_iter().map(Some))
+            .map(|header| PooledToken { header, backend: LocalBackend::new(), last_used: AtomicU64::new(0) })
+            .collect();
+
+        TokenRotation { tokens, rotation, next: AtomicUsize::new(0) }
+    }
+
+    /// Choose the token which sends the next read, returning its index.
+    ///
+    /// If every token is backing off, the one whose back-off ends soonest is chosen under either rotation.
+    pub fn select(&self) -> usize {
+        let time = unix_timestamp();
+        let remaining = |index: usize| self.tokens[index].backend.backoff(RequestType::Read).remaining(time);
+
+        let chosen = match self.rotation {
+            Rotation::RoundRobin => {
+                let start = self.next.fetch_add(1, Ordering::AcqRel);
+                let order = (0..self.tokens.len()).map(|offset| (start + offset) % self.tokens.len());
+
+                let free = order.clone().find(|&index| remaining(index) == 0);
+                free.or_else(|| order.min_by_key(|&index| remaining(index)))
+            }
+            Rotation::LeastThrottled => (0..self.tokens.len())
+                .min_by_key(|&index| (remaining(index), self.tokens[index].last_used.load(Ordering::Acquire))),
+        };
+
+        let chosen = chosen.unwrap_or(0);
+        self.tokens[chosen].last_used.store(time, Ordering::Release);
+        chosen
+    }
 }
 
 /// A strucutre for storing the relevant atomic values in order to track our compliance with the API's rate limits.
 pub struct RateLimitStore {
-    pub read_last_retry: AtomicU64,
-    pub read_last_request: AtomicU64,
+    // The back-off the API asked us to observe, which may be shared with other processes.
+    pub backend: Box<dyn RateLimitBackend>,
 
-    pub write_last_retry: AtomicU64,
-    pub write_last_request: AtomicU64,
+    // The pool of tokens which reads are rotated between, each with its own back-off, if any.
+    pub pool: Option<TokenRotation>,
+
+    // A value of 0 indicates that no ceiling has been set.
+    pub read_ceiling: AtomicU64,
+    pub read_window: Mutex<VecDeque<u64>>,
+
+    pub write_ceiling: AtomicU64,
+    pub write_window: Mutex<VecDeque<u64>>,
+
+    // Proactive budgets, if set.
+    pub read_bucket: Mutex<Option<TokenBucket>>,
+    pub write_bucket: Mutex<Option<TokenBucket>>,
+
+    // The number of requests currently stalled by the throttler, and the number sent but yet to receive a response.
+    pub queued: AtomicU64,
+    pub in_flight: AtomicU64,
+
+    // The number of interactive requests of each type currently waiting to pass the throttler.
+    pub read_interactive: AtomicU64,
+    pub write_interactive: AtomicU64,
 }
 
 impl RateLimitStore {
     pub fn new() -> Self {
+        RateLimitStore::with_backend(Box::new(LocalBackend::new()))
+    }
+
+    pub fn with_backend(backend: Box<dyn RateLimitBackend>) -> Self {
         RateLimitStore {
-            read_last_retry: AtomicU64::new(0),
-            read_last_request: AtomicU64::new(unix_timestamp()),
+            backend,
+            pool: None,
+
+            read_ceiling: AtomicU64::new(0),
+            read_window: Mutex::new(VecDeque::new()),
+
+            write_ceiling: AtomicU64::new(0),
+            write_window: Mutex::new(VecDeque::new()),
+
+            read_bucket: Mutex::new(None),
+            write_bucket: Mutex::new(None),
+
+            queued: AtomicU64::new(0),
+            in_flight: AtomicU64::new(0),
+
+            read_interactive: AtomicU64::new(0),
+            write_interactive: AtomicU64::new(0),
+        }
+    }
 
-            write_last_retry: AtomicU64::new(0),
-            write_last_request: AtomicU64::new(unix_timestamp()),
+    /// Returns the gauge of interactive requests waiting to pass the throttler for a request type.
+    pub fn interactive(&self, request_type: RequestType) -> &AtomicU64 {
+        match request_type {
+            RequestType::Read => &self.read_interactive,
+            RequestType::Write => &self.write_interactive,
         }
     }
 
-    pub fn store_read(&self, retry: u64) {
-        self.read_last_retry.store(retry, Ordering::Release);
-        self.read_last_request.store(unix_timestamp(), Ordering::Release);
+    pub fn set_read_ceiling(&self, per_minute: Option<u64>) {
+        self.read_ceiling.store(per_minute.unwrap_or(0), Ordering::Release);
+    }
+
+    pub fn set_write_ceiling(&self, per_minute: Option<u64>) {
+        self.write_ceiling.store(per_minute.unwrap_or(0), Ordering::Release);
     }
 
-    pub fn store_write(&self, retry: u64) {
-        self.write_last_retry.store(retry, Ordering::Release);
-        self.write_last_request.store(unix_timestamp(), Ordering::Release);
+    pub fn set_read_budget(&self, per_minute: Option<u64>, burst: u64) {
+        *self.read_bucket.lock().unwrap() =
+            per_minute.map(|per_minute| TokenBucket::new(per_minute, burst, unix_timestamp()));
     }
 
-    pub fn reset_read(&self) {
-        self.read_last_retry.store(0, Ordering::Release);
-        self.read_last_request.store(unix_timestamp(), Ordering::Release);
+    pub fn set_write_budget(&self, per_minute: Option<u64>, burst: u64) {
+        *self.write_bucket.lock().unwrap() =
+            per_minute.map(|per_minute| TokenBucket::new(per_minute, burst, unix_timestamp()));
     }
 
-    pub fn reset_write(&self) {
-        self.write_last_retry.store(0, Ordering::Release);
-        self.write_last_request.store(unix_timestamp(), Ordering::Release);
+    /// Returns the backend holding the back-off of a request type, given the index of the pooled token sending it (if
+    /// any).
+    pub fn backend(&self, request_type: RequestType, token: Option<usize>) -> &dyn RateLimitBackend {
+        match (&self.pool, request_type, token) {
+            (Some(pool), RequestType::Read, Some(token)) => &pool.tokens[token].backend,
+            _ => self.backend.as_ref(),
+        }
+    }
+
+    pub fn store(&self, request_type: RequestType, token: Option<usize>, retry: u64) {
+        self.backend(request_type, token).record_limited(request_type, retry, unix_timestamp());
+    }
+
+    pub fn reset(&self, request_type: RequestType, token: Option<usize>) {
+        self.backend(request_type, token).record_success(request_type, unix_timestamp());
     }
 }
 
 /// Compute how long, if at all, we should stall the next request in order to be compliant with rate limiting.
 ///
-/// Returned value is in milliseconds. A value of 0 indiciates that there's no need to stall the calling request.
-pub fn stall_for(store: &RateLimitStore, request_type: RequestType) -> u64 {
+/// Returned value is in milliseconds. A value of 0 indiciates that there's no need to stall the calling request, in
+/// which case the request is also counted towards any configured requests-per-minute ceiling and budget.
+///
+/// Background requests are stalled whilst any interactive requests of the same type are waiting (which callers must
+/// track via `RateLimitStore::interactive`), so that interactive requests take each slot as it becomes available.
+///
+/// The back-off observed is that of the pooled token sending the request, if any.
+pub fn stall_for(store: &RateLimitStore, request_type: RequestType, priority: Priority, token: Option<usize>) -> u64 {
     let time = unix_timestamp();
     let mut stall_for = 0;
 
-    if let RequestType::READ = request_type {
-        stall_for = stall_for_helper(&store.read_last_retry, &store.read_last_request, time);
+    if priority == Priority::Background && store.interactive(request_type).load(Ordering::Acquire) > 0 {
+        return YIELD_STALL;
+    }
+
+    if let RequestType::Read = request_type {
+        stall_for = store.backend(request_type, token).backoff(request_type).remaining(time);
+
+        if stall_for == 0 {
+            stall_for = budget_stall_for(&store.read_bucket, &store.read_ceiling, &store.read_window, time);
+        }
     }
-    if let RequestType::WRITE = request_type {
-        stall_for = stall_for_helper(&store.write_last_retry, &store.write_last_request, time);
+    if let RequestType::Write = request_type {
+        stall_for = store.backend(request_type, token).backoff(request_type).remaining(time);
+
+        if stall_for == 0 {
+            stall_for = budget_stall_for(&store.write_bucket, &store.write_ceiling, &store.write_window, time);
+        }
     }
 
     stall_for
 }
 
-/// A helper function for `stall_for` which computes over a generic set of rate limiting parameters.
-fn stall_for_helper(a_last_retry: &AtomicU64, a_last_request: &AtomicU64, time: u64) -> u64 {
-    let mut stall_for = 0;
-    let last_retry = a_last_retry.load(Ordering::Acquire);
-    let last_request = a_last_request.load(Ordering::Acquire);
+/// A helper function for `stall_for` which computes the stall required by a proactive budget and requests-per-minute
+/// ceiling together.
+///
+/// A token is only taken from the budget once the ceiling has also admitted the request, so that a request stalled by
+/// the ceiling doesn't consume a token it never uses.
+fn budget_stall_for(
+    bucket: &Mutex<Option<TokenBucket>>,
+    ceiling: &AtomicU64,
+    window: &Mutex<VecDeque<u64>>,
+    time: u64,
+) -> u64 {
+    let mut bucket = bucket.lock().unwrap();
+
+    if let Some(wait) = bucket.as_mut().map(|bucket| bucket.wait(time)).filter(|wait| *wait > 0) {
+        return wait;
+    }
 
-    if last_retry > 0 && (time - last_request) < last_retry {
-        stall_for = last_retry - (time - last_request);
+    let stall_for = ceiling_stall_for(ceiling, window, time);
+
+    if stall_for == 0 {
+        if let Some(bucket) = bucket.as_mut() {
+            bucket.tokens -= 1.0;
+        }
     }
 
     stall_for
 }
 
+/// A helper function for `stall_for` which computes the stall required to stay under a requests-per-minute ceiling.
+///
+/// If no stall is required, the request is recorded within the window. The check and record happen under the same
+/// lock so that concurrent requests can't both claim the final slot.
+fn ceiling_stall_for(a_ceiling: &AtomicU64, window: &Mutex<VecDeque<u64>>, time: u64) -> u64 {
+    let ceiling = a_ceiling.load(Ordering::Acquire);
+    let mut window = window.lock().unwrap();
+
+    if ceiling == 0 {
+        window.clear();
+        return 0;
+    }
+
+    while window.front().is_some_and(|&request| time.saturating_sub(request) >= CEILING_WINDOW) {
+        window.pop_front();
+    }
+
+    if window.len() as u64 >= ceiling {
+        return (window[0] + CEILING_WINDOW).saturating_sub(time).max(1);
+    }
+
+    window.push_back(time);
+    0
+}
+
+/// Inspect the throttling state of a request type without recording a request.
+///
+/// Returns how long the next request would be stalled for (in milliseconds), along with the number of requests counted
+/// within the current ceiling window.
+pub fn inspect(store: &RateLimitStore, request_type: RequestType) -> (u64, u64) {
+    let time = unix_timestamp();
+
+    let (ceiling, window, bucket) = match request_type {
+        RequestType::Read => (&store.read_ceiling, &store.read_window, &store.read_bucket),
+        RequestType::Write => (&store.write_ceiling, &store.write_window, &store.write_bucket),
+    };
+
+    let ceiling = ceiling.load(Ordering::Acquire);
+    let window = window.lock().unwrap();
+    let mut active = window.iter().filter(|&&request| time.saturating_sub(request) < CEILING_WINDOW);
+
+    let oldest = active.next().copied();
+    let usage = oldest.map_or(0, |_| 1 + active.count() as u64);
+
+    let mut stall_for = store.backend.backoff(request_type).remaining(time);
+
+    if let Some(oldest) = oldest.filter(|_| stall_for == 0 && ceiling > 0 && usage >= ceiling) {
+        stall_for = (oldest + CEILING_WINDOW).saturating_sub(time).max(1);
+    }
+
+    if stall_for == 0 {
+        stall_for = bucket.lock().unwrap().as_mut().map_or(0, |bucket| bucket.wait(time));
+    }
+
+    (stall_for, usage)
+}
+
+/// Counts a request within one of the store's gauges for as long as it's held.
+pub struct Tracked<'a>(&'a AtomicU64);
+
+impl<'a> Tracked<'a> {
+    pub fn new(gauge: &'a AtomicU64) -> Tracked<'a> {
+        gauge.fetch_add(1, Ordering::AcqRel);
+        Tracked(gauge)
+    }
+}
+
+impl Drop for Tracked<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
 /// Return the current time as a UNIX millisecond timestamp.
 pub fn unix_timestamp() -> u64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis().try_into().unwrap()
@@ -0,0 +1,147 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Helpers for presenting the UNIX timestamps returned by the API to people.
+//!
+//! Conversion into localised date-times requires the `timezones` feature.
+
+use crate::throttler::unix_timestamp;
+
+#[cfg(feature = "timezones")]
+use chrono::DateTime;
+#[cfg(feature = "timezones")]
+use chrono_tz::Tz;
+
+/// Convert a UNIX timestamp (in seconds) into a date-time within an IANA timezone (eg. "Europe/London").
+///
+/// Returns `None` if the timezone isn't recognised or the timestamp is out of range.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::timestamps::to_local;
+/// let local = to_local(1_700_000_000, "America/New_York").unwrap();
+/// assert_eq!("2023-11-14 17:13", local.format("%Y-%m-%d %H:%M").to_string());
+/// ```
+#[cfg(feature = "timezones")]
+pub fn to_local(timestamp: u64, timezone: &str) -> Option<DateTime<Tz>> {
+    let timezone: Tz = timezone.parse().ok()?;
+    let utc = DateTime::from_timestamp(i64::try_from(timestamp).ok()?, 0)?;
+
+    Some(utc.with_timezone(&timezone))
+}
+
+/// Describe a UNIX timestamp (in seconds) relative to the current time, eg. "in 3 days" or "2 hours ago".
+pub fn relative(timestamp: u64) -> String {
+    relative_to(timestamp, unix_timestamp() / 1000)
+}
+
+/// Describe a UNIX timestamp (in seconds) relative to another, eg. "in 3 days" or "2 hours ago".
+///
+/// The largest whole unit is used, so 47 hours is described as "in 1 day".
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::timestamps::relative_to;
+/// assert_eq!("in 3 days", relative_to(3 * 86_400, 0));
+/// assert_eq!("2 hours ago", relative_to(0, 2 * 3_600 + 59));
+/// assert_eq!("just now", relative_to(100, 100));
+/// ```
+pub fn relative_to(timestamp: u64, now: u64) -> String {
+    const UNITS: [(u64, &str); 6] = [
+        (31_536_000, "year"),
+        (2_592_000, "month"),
+        (604_800, "week"),
+        (86_400, "day"),
+        (3_600, "hour"),
+        (60, "minute"),
+    ];
+
+    let difference = timestamp.abs_diff(now);
+
+    let (count, unit) = match UNITS.iter().find(|(length, _)| difference >= *length) {
+        Some((length, unit)) => (difference / length, *unit),
+        None => return "just now".to_string(),
+    };
+
+    let plural = if count == 1 { "" } else { "s" };
+
+    if timestamp > now {
+        format!("in {} {}{}", count, unit, plural)
+    } else {
+        format!("{} {}{} ago", count, unit, plural)
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Format a UNIX timestamp (in seconds) as a UTC calendar date, eg. "14 November 2023".
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::timestamps::format_utc_date;
+/// assert_eq!("14 November 2023", format_utc_date(1_700_000_000));
+/// assert_eq!("29 February 2024", format_utc_date(1_709_164_800));
+/// assert_eq!("1 January 1970", format_utc_date(0));
+/// ```
+pub fn format_utc_date(timestamp: u64) -> String {
+    let (year, month, day) = civil_from_days(timestamp / 86_400);
+    format!("{} {} {}", day, MONTHS[month as usize - 1], year)
+}
+
+/// Format a UNIX timestamp (in seconds) as an ISO 8601 UTC calendar date, eg. "2023-11-14".
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::timestamps::format_iso_date;
+/// assert_eq!("2023-11-14", format_iso_date(1_700_000_000));
+/// assert_eq!("1970-01-01", format_iso_date(0));
+/// ```
+pub fn format_iso_date(timestamp: u64) -> String {
+    let (year, month, day) = civil_from_days(timestamp / 86_400);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Convert a number of days since the UNIX epoch into a (year, month, day) proleptic Gregorian date.
+///
+/// This follows Howard Hinnant's `civil_from_days` algorithm, restricted to dates on or after the epoch.
+pub(crate) fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Convert a (year, month, day) proleptic Gregorian date on or after the UNIX epoch into a number of days since it.
+///
+/// This is the inverse of `civil_from_days`, following Howard Hinnant's `days_from_civil` algorithm.
+pub(crate) fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
@@ -0,0 +1,163 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! Utilities for presenting the replies of a conversation, such as when mirroring support conversations elsewhere.
+
+use crate::data::conversations::{ConversationData, ReplyData};
+use crate::timestamps::format_utc_date;
+
+use serde::Serialize;
+
+/// A run of consecutive replies from the same author.
+#[derive(Debug, Clone)]
+pub struct AuthorGroup<'a> {
+    pub author_id: u64,
+    pub replies: &'a [ReplyData],
+}
+
+/// Group consecutive replies by their author, preserving order.
+pub fn group_by_author(replies: &[ReplyData]) -> Vec<AuthorGroup<'_>> {
+    split_runs(replies, |reply| *reply.author_id())
+        .into_iter()
+        .map(|replies| AuthorGroup { author_id: *replies[0].author_id(), replies })
+        .collect()
+}
+
+/// Returns the runs of consecutive replies which were sent after a conversation's `last_read_date`.
+///
+/// Replies are usually fetched oldest-first, in which case there'll be at most one (trailing) segment.
+pub fn unread_segments(replies: &[ReplyData], last_read_date: u64) -> Vec<&[ReplyData]> {
+    split_runs(replies, |reply| *reply.message_date() > last_read_date)
+        .into_iter()
+        .filter(|run| *run[0].message_date() > last_read_date)
+        .collect()
+}
+
+/// Split a slice into maximal runs of consecutive items which share the same key.
+fn split_runs<T, K, F>(items: &[T], key: F) -> Vec<&[T]>
+where
+    K: PartialEq,
+    F: Fn(&T) -> K,
+{
+    let mut runs = Vec::new();
+    let mut start = 0;
+
+    for index in 1..=items.len() {
+        if index == items.len() || key(&items[index]) != key(&items[start]) {
+            runs.push(&items[start..index]);
+            start = index;
+        }
+    }
+
+    runs
+}
+
+/// A single message within a transcript.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptMessage {
+    pub message_id: u64,
+    pub message_date: u64,
+    pub message: String,
+}
+
+/// A run of consecutive messages within a transcript from the same author, which are either all read or all unread.
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptGroup {
+    pub author_id: u64,
+    pub unread: bool,
+    pub messages: Vec<TranscriptMessage>,
+}
+
+/// An ordered, grouped view of a conversation's replies.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::transcript::Transcript;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper) -> bbb_api_wrapper::error::Result<()> {
+/// for conversation in wrapper.conversations().list_unread(None).await? {
+///     let transcript = wrapper.conversations().transcript(&conversation).await?;
+///     println!("{}", transcript.render(|member_id| format!("Member #{}", member_id)));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcript {
+    pub conversation_id: u64,
+    pub title: String,
+    /// The creator of the conversation followed by its recipients.
+    pub participant_ids: Vec<u64>,
+    pub groups: Vec<TranscriptGroup>,
+}
+
+impl Transcript {
+    /// Construct a transcript from a conversation and its replies (in any order), ordering the replies oldest-first.
+    pub fn new(conversation: &ConversationData, replies: &[ReplyData]) -> Transcript {
+        let last_read_date = *conversation.last_read_date();
+
+        let mut replies: Vec<&ReplyData> = replies.iter().collect();
+        replies.sort_by_key(|reply| (*reply.message_date(), *reply.message_id()));
+
+        let mut groups: Vec<TranscriptGroup> = Vec::new();
+
+        for reply in replies {
+            let unread = *reply.message_date() > last_read_date;
+            let message = TranscriptMessage {
+                message_id: *reply.message_id(),
+                message_date: *reply.message_date(),
+                message: reply.message().clone(),
+            };
+
+            match groups.last_mut() {
+                Some(group) if group.author_id == *reply.author_id() && group.unread == unread => {
+                    group.messages.push(message)
+                }
+                _ => groups.push(TranscriptGroup { author_id: *reply.author_id(), unread, messages: vec![message] }),
+            }
+        }
+
+        let mut participant_ids = vec![*conversation.creator_id()];
+        participant_ids.extend(conversation.recipient_ids().iter().filter(|id| **id != *conversation.creator_id()));
+
+        Transcript {
+            conversation_id: *conversation.conversation_id(),
+            title: conversation.title().clone(),
+            participant_ids,
+            groups,
+        }
+    }
+
+    /// Returns the number of messages which are unread.
+    pub fn unread_count(&self) -> usize {
+        self.groups.iter().filter(|group| group.unread).map(|group| group.messages.len()).sum()
+    }
+
+    /// Render this transcript as plain text, resolving author names via the provided function.
+    ///
+    /// Each group is headed by its author and the (UTC) date of its first message, and the start of the unread
+    /// messages is marked.
+    pub fn render<F>(&self, name_of: F) -> String
+    where
+        F: Fn(u64) -> String,
+    {
+        let mut rendered = format!("# {}\n", self.title);
+        let mut marked_unread = false;
+
+        for group in &self.groups {
+            if group.unread && !marked_unread {
+                rendered.push_str("\n--- Unread ---\n");
+                marked_unread = true;
+            }
+
+            let date = format_utc_date(group.messages[0].message_date);
+            rendered.push_str(&format!("\n{} ({}):\n", name_of(group.author_id), date));
+
+            for message in &group.messages {
+                rendered.push_str(&message.message);
+                rendered.push('\n');
+            }
+        }
+
+        rendered
+    }
+}
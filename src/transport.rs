@@ -0,0 +1,106 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! An abstraction over how requests are sent, so that code built on the wrapper can be tested against canned responses
+//! rather than a live token.
+//!
+//! Requests are still built (and rate limited, retried, and so on) by the wrapper as usual, and only the final step of
+//! sending them is delegated to the transport.
+//!
+//! Responses can't be constructed by hand within browsers, so the helpers for doing so aren't available there.
+
+use crate::error::Result;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use reqwest::{Client, Request, Response};
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::{ResponseBuilderExt, StatusCode};
+#[cfg(not(target_arch = "wasm32"))]
+use serde::Serialize;
+
+/// The future returned by a transport.
+///
+/// Within browsers, requests are driven by JavaScript promises which can't be sent between threads, so the future
+/// needn't be `Send` there.
+#[cfg(not(target_arch = "wasm32"))]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<Response>> + Send + 'a>>;
+
+/// The future returned by a transport, which needn't be `Send` within browsers.
+#[cfg(target_arch = "wasm32")]
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = Result<Response>> + 'a>>;
+
+/// A means of sending a request and receiving its response, set via `APIWrapperBuilder::transport`.
+///
+/// The wrapper's HTTP client is used by default.
+///
+/// # Example
+/// ```
+/// # use bbb_api_wrapper::transport::{self, HttpTransport, TransportFuture};
+/// # use bbb_api_wrapper::error::Result;
+/// # use reqwest::{Request, Response, StatusCode};
+/// /// Responds to every request as though the API was down for maintenance.
+/// struct Maintenance;
+///
+/// impl HttpTransport for Maintenance {
+///     fn execute(&self, request: Request) -> TransportFuture<'_> {
+///         Box::pin(async move { Ok(transport::respond(&request, StatusCode::SERVICE_UNAVAILABLE, "")) })
+///     }
+/// }
+/// ```
+pub trait HttpTransport: Send + Sync {
+    fn execute(&self, request: Request) -> TransportFuture<'_>;
+
+    /// Called with the wrapper's HTTP client once it's built, so a transport which sends requests itself (eg. a
+    /// recording `Cassette`) can do so with the wrapper's timeouts and proxies. Does nothing by default.
+    fn use_client(&mut self, client: &Client) {
+        let _ = client;
+    }
+}
+
+impl HttpTransport for Client {
+    fn execute(&self, request: Request) -> TransportFuture<'_> {
+        Box::pin(async move { Ok(Client::execute(self, request).await?) })
+    }
+}
+
+impl<T: HttpTransport + ?Sized> HttpTransport for Arc<T> {
+    fn execute(&self, request: Request) -> TransportFuture<'_> {
+        T::execute(self, request)
+    }
+
+    /// Forwarded only if this is the sole reference to the transport.
+    fn use_client(&mut self, client: &Client) {
+        if let Some(transport) = Arc::get_mut(self) {
+            transport.use_client(client);
+        }
+    }
+}
+
+/// Construct a response to a request with a status and raw body.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn respond<B: Into<Vec<u8>>>(request: &Request, status: StatusCode, body: B) -> Response {
+    let response = http::Response::builder()
+        .status(status)
+        .url(request.url().clone())
+        .body(body.into())
+        .expect("response parts not valid");
+
+    Response::from(response)
+}
+
+/// Construct a successful response to a request, as the API would respond with some data.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn respond_with_data<T: Serialize>(request: &Request, data: &T) -> Result<Response> {
+    let body = serde_json::to_vec(&serde_json::json!({ "result": "success", "data": data }))?;
+    Ok(respond(request, StatusCode::OK, body))
+}
+
+/// Construct an unsuccessful response to a request, as the API would respond with an error.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn respond_with_error(request: &Request, status: StatusCode, code: &str, message: &str) -> Response {
+    let body = serde_json::json!({ "result": "error", "error": { "code": code, "message": message } });
+    respond(request, status, body.to_string())
+}
@@ -0,0 +1,57 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! An in-memory cache of the member IDs which usernames resolve to, so that repeat lookups of the same username (eg.
+//! by chat bot commands) needn't count towards the API's rate limits.
+//!
+//! Usernames are matched case-insensitively (as they are by the site), and any member fetched by name or ID refreshes
+//! the entry of their current username. As a member may change their username, entries expire after a duration.
+
+use crate::runtime::Instant;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub(crate) struct UsernameCache {
+    ttl: Duration,
+    /// Each username's member ID along with when it expires, or `None` if the TTL is too large to ever expire.
+    entries: Mutex<HashMap<String, (u64, Option<Instant>)>>,
+}
+
+impl UsernameCache {
+    pub(crate) fn new(ttl: Duration) -> UsernameCache {
+        UsernameCache { ttl, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// Returns the member ID which a username resolves to, if cached and yet to expire.
+    pub(crate) fn lookup(&self, username: &str) -> Option<u64> {
+        let entries = self.entries.lock().unwrap();
+        let (member_id, expires_at) = entries.get(&username.to_lowercase())?;
+
+        let now = Instant::now();
+        expires_at.is_none_or(|expires_at| expires_at > now).then_some(*member_id)
+    }
+
+    pub(crate) fn store(&self, username: &str, member_id: u64) {
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+
+        // Drop any expired entries and those of the member's previous username, so the cache can't grow unbounded.
+        entries.retain(|_, (id, expires_at)| expires_at.is_none_or(|expires_at| expires_at > now) && *id != member_id);
+        entries.insert(username.to_lowercase(), (member_id, now.checked_add(self.ttl)));
+    }
+
+    pub(crate) fn invalidate(&self, username: &str) {
+        self.entries.lock().unwrap().remove(&username.to_lowercase());
+    }
+
+    /// Returns the number of usernames cached, including any which have expired but have yet to be purged.
+    pub(crate) fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A caching license verifier intended for hot paths such as checking players as they join a server.
+
+use crate::data::resources::LicenseData;
+use crate::error::Result;
+use crate::runtime::Instant;
+use crate::APIWrapper;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How a verification should be decided when the API can't be reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OfflinePolicy {
+    /// Treat the member as unlicensed.
+    Deny,
+    /// Treat the member as licensed.
+    Allow,
+    /// Decide using any cached license regardless of its age, falling back to denial if nothing is cached.
+    UseStale,
+}
+
+/// The status of a member's license at the time of verification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicenseStatus {
+    /// The member holds an active and unexpired license.
+    Valid,
+    /// The member's license has expired but is still within the configured grace period.
+    Grace,
+    /// The member's license is inactive, expired beyond the grace period, or doesn't exist.
+    Invalid,
+}
+
+/// Where the data backing a verification was sourced from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationSource {
+    Api,
+    Cache,
+    StaleCache,
+    OfflinePolicy,
+}
+
+/// The outcome of verifying a single member.
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub status: LicenseStatus,
+    pub source: VerificationSource,
+    pub license: Option<LicenseData>,
+}
+
+impl Verification {
+    /// Returns whether or not the member should be allowed access (ie. valid or within grace).
+    pub fn is_allowed(&self) -> bool {
+        matches!(self.status, LicenseStatus::Valid | LicenseStatus::Grace)
+    }
+}
+
+struct CacheEntry {
+    license: Option<LicenseData>,
+    fetched_at: Instant,
+}
+
+/// A license verifier for a single resource combining lookups with positive/negative caching.
+pub struct LicenseVerifier<'a> {
+    wrapper: &'a APIWrapper,
+    resource_id: u64,
+    positive_ttl: Duration,
+    negative_ttl: Duration,
+    grace_period: Duration,
+    offline_policy: OfflinePolicy,
+    cache: Mutex<HashMap<u64, CacheEntry>>,
+}
+
+impl<'a> LicenseVerifier<'a> {
+    /// Construct a new verifier for a resource.
+    ///
+    /// By default, found licenses are cached for five minutes, missing licenses for one minute, there's no grace
+    /// period, and stale cached data is used when the API can't be reached.
+    pub fn new(wrapper: &'a APIWrapper, resource_id: u64) -> LicenseVerifier<'a> {
+        LicenseVerifier {
+            wrapper,
+            resource_id,
+            positive_ttl: Duration::from_secs(300),
+            negative_ttl: Duration::from_secs(60),
+            grace_period: Duration::ZERO,
+            offline_policy: OfflinePolicy::UseStale,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set how long a found license is cached for.
+    pub fn positive_ttl(mut self, ttl: Duration) -> Self {
+        self.positive_ttl = ttl;
+        self
+    }
+
+    /// Set how long the absence of a license is cached for.
+    pub fn negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Set how long after expiry a temporary license is still considered acceptable.
+    pub fn grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Set how verifications are decided when the API can't be reached.
+    pub fn offline_policy(mut self, offline_policy: OfflinePolicy) -> Self {
+        self.offline_policy = offline_policy;
+        self
+    }
+
+    /// Verify whether or not a member holds a license for this verifier's resource.
+    ///
+    /// # Note
+    /// Only retryable failures (ie. the API couldn't be reached or is failing) trigger the offline policy. Any other
+    /// error is passed through to the caller as we can't make a meaningful decision from it.
+    pub async fn verify(&self, member_id: u64) -> Result<Verification> {
+        if let Some(license) = self.cached(member_id, false) {
+            return Ok(self.verification(license, VerificationSource::Cache));
+        }
+
+        let license = match self.wrapper.resources().licenses().fetch_by_member(self.resource_id, member_id).await {
+            Ok(license) => Some(license),
+            Err(error) if error.is_not_found() => None,
+            Err(error) if error.is_retryable() => return Ok(self.offline(member_id)),
+            Err(error) => return Err(error),
+        };
+
+        let entry = CacheEntry { license: license.clone(), fetched_at: Instant::now() };
+        self.cache.lock().unwrap().insert(member_id, entry);
+
+        Ok(self.verification(license, VerificationSource::Api))
+    }
+
+    /// Remove any cached data for a specific member.
+    pub fn invalidate(&self, member_id: u64) {
+        self.cache.lock().unwrap().remove(&member_id);
+    }
+
+    /// Remove all cached data.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    /// Returns the cached license lookup for a member, if one exists and is within its TTL (or `stale` is set).
+    fn cached(&self, member_id: u64, stale: bool) -> Option<Option<LicenseData>> {
+        let cache = self.cache.lock().unwrap();
+        let entry = cache.get(&member_id)?;
+
+        let ttl = if entry.license.is_some() { self.positive_ttl } else { self.negative_ttl };
+
+        if stale || entry.fetched_at.elapsed() < ttl {
+            Some(entry.license.clone())
+        } else {
+            None
+        }
+    }
+
+    fn offline(&self, member_id: u64) -> Verification {
+        let status = match self.offline_policy {
+            OfflinePolicy::Allow => LicenseStatus::Valid,
+            OfflinePolicy::Deny => LicenseStatus::Invalid,
+            OfflinePolicy::UseStale => match self.cached(member_id, true) {
+                Some(license) => return self.verification(license, VerificationSource::StaleCache),
+                None => LicenseStatus::Invalid,
+            },
+        };
+
+        Verification { status, source: VerificationSource::OfflinePolicy, license: None }
+    }
+
+    fn verification(&self, license: Option<LicenseData>, source: VerificationSource) -> Verification {
+        let status = match &license {
+            Some(license) => self.status(license),
+            None => LicenseStatus::Invalid,
+        };
+
+        Verification { status, source, license }
+    }
+
+    /// Compute the status of a license at the current time.
+    ///
+    /// A temporary license which was deactivated before its end date has been revoked, so isn't given any grace.
+    fn status(&self, license: &LicenseData) -> LicenseStatus {
+        if *license.permanent() {
+            return if *license.active() { LicenseStatus::Valid } else { LicenseStatus::Invalid };
+        }
+
+        let now = crate::throttler::unix_timestamp() / 1000;
+
+        if now < *license.end_date() {
+            return if *license.active() { LicenseStatus::Valid } else { LicenseStatus::Invalid };
+        }
+
+        if now < license.end_date().saturating_add(self.grace_period.as_secs()) {
+            LicenseStatus::Grace
+        } else {
+            LicenseStatus::Invalid
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A watchdog which periodically checks the API's health, so that jobs can be paused during an outage rather than
+//! failing repeatedly.
+//!
+//! Like the scheduler, the watchdog is driven from a future rather than spawned, so should be run alongside other
+//! work (eg. as a watcher, or joined with a scheduler).
+
+use crate::error::APIError;
+use crate::options::{Priority, RequestOptions};
+use crate::runtime::{self, Instant};
+use crate::APIWrapper;
+
+use std::future::Future;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+type OnChange<'a> = Box<dyn FnMut(&HealthChange) + 'a>;
+
+/// The default period between health checks.
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The default number of consecutive failed health checks before the API is considered unhealthy.
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// A change in the API's health, as observed by a watchdog.
+#[derive(Debug)]
+pub enum HealthChange {
+    /// The API has failed several consecutive health checks, along with the most recent failure.
+    Unhealthy { failures: u32, error: APIError },
+    /// The API passed a health check after being unhealthy, along with how long it was considered unhealthy.
+    Healthy { downtime: Duration },
+}
+
+/// A watchdog of the API's health, constructed via `APIWrapper::health_watchdog`.
+///
+/// Health checks are made with background priority and without retries. A check which is rate limited counts as
+/// neither a success nor a failure. Whilst the API is considered unhealthy, `APIWrapper::is_healthy` returns false.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::watchdog::HealthChange;
+/// # use std::time::Duration;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper, shutdown: impl std::future::Future<Output = ()>) {
+/// let watchdog = wrapper.health_watchdog().interval(Duration::from_secs(60)).on_change(|change| match change {
+///     HealthChange::Unhealthy { error, .. } => println!("Pausing sales sync: {}", error),
+///     HealthChange::Healthy { downtime } => println!("Resuming sales sync after {}s.", downtime.as_secs()),
+/// });
+///
+/// watchdog.run_until(shutdown).await;
+/// # }
+/// ```
+pub struct HealthWatchdog<'a> {
+    wrapper: &'a APIWrapper,
+    interval: Duration,
+    failure_threshold: u32,
+    on_change: Option<OnChange<'a>>,
+}
+
+impl<'a> HealthWatchdog<'a> {
+    pub(crate) fn new(wrapper: &'a APIWrapper) -> HealthWatchdog<'a> {
+        HealthWatchdog {
+            wrapper,
+            interval: DEFAULT_INTERVAL,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            on_change: None,
+        }
+    }
+
+    /// Set the period between health checks (30 seconds by default).
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Set the number of consecutive failed health checks before the API is considered unhealthy (3 by default).
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self
+    }
+
+    /// Call a function whenever the API becomes unhealthy, or healthy again.
+    pub fn on_change<F>(mut self, on_change: F) -> Self
+    where
+        F: FnMut(&HealthChange) + 'a,
+    {
+        self.on_change = Some(Box::new(on_change));
+        self
+    }
+
+    /// Check the API's health periodically until a shutdown future completes.
+    pub async fn run_until<S>(mut self, shutdown: S)
+    where
+        S: Future<Output = ()>,
+    {
+        self.wrapper.watchdogs.fetch_add(1, Ordering::AcqRel);
+        let _running = Running(self.wrapper);
+
+        runtime::race(self.watch(), shutdown).await;
+    }
+
+    async fn watch(&mut self) {
+        let options = RequestOptions::default().without_retries().with_priority(Priority::Background);
+        let mut failures = 0;
+        let mut unhealthy_since: Option<Instant> = None;
+
+        loop {
+            match options.clone().scope(self.wrapper.health()).await {
+                Ok(()) => {
+                    failures = 0;
+
+                    if let Some(since) = unhealthy_since.take() {
+                        self.wrapper.unhealthy.store(false, Ordering::Release);
+                        self.notify(HealthChange::Healthy { downtime: since.elapsed() });
+                    }
+                }
+                Err(error) if error.is_rate_limited() => (),
+                Err(error) => {
+                    failures += 1;
+                    log::debug!("Health check failed ({} consecutive): {}", failures, error);
+
+                    if failures == self.failure_threshold {
+                        unhealthy_since = Some(Instant::now());
+                        self.wrapper.unhealthy.store(true, Ordering::Release);
+                        self.notify(HealthChange::Unhealthy { failures, error });
+                    }
+                }
+            }
+
+            runtime::sleep(self.interval).await;
+        }
+    }
+
+    fn notify(&mut self, change: HealthChange) {
+        log::info!("API health changed: {:?}", change);
+
+        if let Some(on_change) = &mut self.on_change {
+            on_change(&change);
+        }
+    }
+}
+
+/// Marks a watchdog as stopped once dropped, however it exits.
+///
+/// The API is no longer considered unhealthy once the last watchdog stops, so `APIWrapper::is_healthy` holds whilst
+/// none are running.
+struct Running<'a>(&'a APIWrapper);
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        if self.0.watchdogs.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.0.unhealthy.store(false, Ordering::Release);
+        }
+    }
+}
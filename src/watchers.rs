@@ -0,0 +1,191 @@
+// Copyright (c) 2021 Harry [Majored] [hello@majored.pw]
+// MIT License (https://github.com/Majored/rs-bbb-api-wrapper/blob/main/LICENSE)
+
+//! A supervisor which runs long-lived watchers concurrently, restarting them with backoff if they fail.
+//!
+//! Like the scheduler, watchers are driven from a single future rather than spawned. Dropping (or shutting down) the
+//! supervisor therefore stops every watcher together, and none can outlive the wrapper they borrow.
+
+use crate::error::{APIError, Result};
+use crate::retry::ExponentialBackoff;
+use crate::runtime::{self, Instant};
+use crate::APIWrapper;
+
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::task::Poll;
+
+/// The attempt beyond which restart delays stop growing, as any sensible policy has reached its maximum delay by then.
+const MAX_BACKOFF_ATTEMPT: u32 = 32;
+
+type WatcherFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + 'a>>;
+type Sleep = Pin<Box<dyn Future<Output = ()>>>;
+
+enum State<'a> {
+    Running(WatcherFuture<'a>, Instant),
+    Restarting(Sleep),
+    Stopped,
+}
+
+struct Watcher<'a> {
+    name: String,
+    start: Box<dyn FnMut(&'a APIWrapper) -> WatcherFuture<'a> + 'a>,
+    state: State<'a>,
+    failures: u32,
+}
+
+/// A failure of a single watcher.
+#[derive(Debug)]
+pub struct WatcherError {
+    pub watcher: String,
+    pub error: APIError,
+}
+
+/// The outcome of running a set of watchers.
+#[derive(Debug, Default)]
+pub struct WatcherReport {
+    /// Every failure, in the order they occurred.
+    pub errors: Vec<WatcherError>,
+    /// The watchers which failed too many consecutive times and weren't restarted.
+    pub abandoned: Vec<String>,
+}
+
+/// A set of watchers (long-running futures, such as pollers) which are supervised together.
+///
+/// A watcher which fails is restarted after a backoff delay, until it has failed the policy's maximum number of
+/// consecutive attempts. A watcher which completes successfully isn't restarted.
+///
+/// # Example
+/// ```no_run
+/// # use bbb_api_wrapper::watchers::WatcherSet;
+/// # use std::time::Duration;
+/// # async fn run(wrapper: bbb_api_wrapper::APIWrapper, shutdown: impl std::future::Future<Output = ()>) {
+/// let mut watchers = WatcherSet::new(&wrapper);
+///
+/// watchers.add("alerts", |wrapper| async move {
+///     loop {
+///         for alert in wrapper.alerts().list_unread(None).await? {
+///             println!("{:?}", alert);
+///         }
+///
+///         tokio::time::sleep(Duration::from_secs(60)).await;
+///     }
+/// });
+///
+/// let report = watchers.run_until(shutdown).await;
+/// println!("{} watcher failures.", report.errors.len());
+/// # }
+/// ```
+pub struct WatcherSet<'a> {
+    wrapper: &'a APIWrapper,
+    policy: ExponentialBackoff,
+    watchers: Vec<Watcher<'a>>,
+}
+
+impl<'a> WatcherSet<'a> {
+    pub fn new(wrapper: &'a APIWrapper) -> WatcherSet<'a> {
+        WatcherSet { wrapper, policy: ExponentialBackoff::default().max_attempts(u32::MAX), watchers: Vec::new() }
+    }
+
+    /// Set the policy which governs restart delays and the maximum number of consecutive failures.
+    ///
+    /// By default, watchers are restarted indefinitely.
+    pub fn backoff(mut self, policy: ExponentialBackoff) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Register a watcher, which is started (and restarted) by calling the provided function.
+    pub fn add<F, Fut>(&mut self, name: &str, mut start: F) -> &mut Self
+    where
+        F: FnMut(&'a APIWrapper) -> Fut + 'a,
+        Fut: Future<Output = Result<()>> + 'a,
+    {
+        self.watchers.push(Watcher {
+            name: name.to_string(),
+            start: Box::new(move |wrapper| Box::pin(start(wrapper))),
+            state: State::Stopped,
+            failures: 0,
+        });
+
+        self
+    }
+
+    /// Run every watcher until they've all stopped.
+    pub async fn run(&mut self) -> WatcherReport {
+        self.run_until(future::pending()).await
+    }
+
+    /// Run every watcher until they've all stopped or a shutdown future completes, at which point every watcher is
+    /// dropped together.
+    pub async fn run_until<S>(&mut self, shutdown: S) -> WatcherReport
+    where
+        S: Future<Output = ()>,
+    {
+        let wrapper = self.wrapper;
+        let policy = &self.policy;
+        let watchers = &mut self.watchers;
+
+        let mut shutdown = Box::pin(shutdown);
+        let mut report = WatcherReport::default();
+
+        for watcher in watchers.iter_mut() {
+            watcher.failures = 0;
+            watcher.state = State::Running((watcher.start)(wrapper), Instant::now());
+        }
+
+        future::poll_fn(|context| {
+            if shutdown.as_mut().poll(context).is_ready() {
+                return Poll::Ready(());
+            }
+
+            for watcher in watchers.iter_mut() {
+                // Keep polling until the watcher is pending (so that we'll be woken) or has stopped.
+                loop {
+                    match &mut watcher.state {
+                        State::Running(running, started) => match running.as_mut().poll(context) {
+                            Poll::Pending => break,
+                            Poll::Ready(Ok(())) => watcher.state = State::Stopped,
+                            Poll::Ready(Err(error)) => {
+                                // A watcher which ran for a while before failing isn't considered to be crash-looping.
+                                if started.elapsed() >= policy.max_delay {
+                                    watcher.failures = 0;
+                                }
+
+                                watcher.failures = watcher.failures.saturating_add(1);
+                                log::warn!("Watcher '{}' failed: {:?}", watcher.name, error);
+                                report.errors.push(WatcherError { watcher: watcher.name.clone(), error });
+
+                                if watcher.failures >= policy.max_attempts {
+                                    report.abandoned.push(watcher.name.clone());
+                                    watcher.state = State::Stopped;
+                                } else {
+                                    let delay = policy.delay_for(watcher.failures.min(MAX_BACKOFF_ATTEMPT));
+                                    watcher.state = State::Restarting(Box::pin(runtime::sleep(delay)));
+                                }
+                            }
+                        },
+                        State::Restarting(sleep) => match sleep.as_mut().poll(context) {
+                            Poll::Pending => break,
+                            Poll::Ready(()) => watcher.state = State::Running((watcher.start)(wrapper), Instant::now()),
+                        },
+                        State::Stopped => break,
+                    }
+                }
+            }
+
+            if watchers.iter().all(|watcher| matches!(watcher.state, State::Stopped)) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await;
+
+        for watcher in watchers.iter_mut() {
+            watcher.state = State::Stopped;
+        }
+
+        report
+    }
+}